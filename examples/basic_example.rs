@@ -8,7 +8,7 @@ use rlgym_sim_rs::obs_builders::advanced_obs::AdvancedObs;
 use rlgym_sim_rs::ObsBuilder;
 use rlgym_sim_rs::reward_functions::common_rewards::misc_rewards::EventReward;
 use rlgym_sim_rs::state_setters::default_state::DefaultState;
-use rocketsim_rs::sim::CarConfig;
+use rocketsim_rs::sim::{CarConfig, GameMode};
 
 
 #[test]
@@ -33,6 +33,12 @@ fn main() {
         gravity: 1.,
         boost_consumption: 1.,
         car_config: CarConfig::octane(),
+        game_mode: GameMode::Soccar,
+        blue_size: None,
+        orange_size: None,
+        car_configs: vec![],
+        shuffle_cars: false,
+        pad_opponent_obs: false,
     };
 
     let game_config = make::MakeConfig {
@@ -43,6 +49,7 @@ fn main() {
         use_single_obs: true,
         action_parser,
         state_setter, 
+        state_modifiers: vec![],
     };
 
     // If you want to render, use this as a second argument:
@@ -3,4 +3,52 @@ use crate::gamestates::game_state::GameState;
 pub trait ActionParser {
     fn get_action_space(&mut self) -> Vec<usize>;
     fn parse_actions(&mut self, actions: Vec<Vec<f32>>, state: &GameState) -> Vec<Vec<f32>>;
+    /// Called after every `parse_actions` with its output, can return the controls to use for each of the `tick_skip` ticks of the step 
+    /// (one list of parsed actions per tick, see `RocketsimWrapper::step_scheduled`). 
+    /// The default of None applies the parsed actions to every tick.
+    fn tick_schedule(&mut self, _parsed_actions: &[Vec<f32>], _tick_skip: usize) -> Option<Vec<Vec<Vec<f32>>>> {
+        None
+    }
+    /// called at the start of every episode, only needed for parsers that keep state between steps
+    fn reset(&mut self, _initial_state: &GameState) {}
+    /// only needed for parsers with randomness, see `Gym::seed`
+    fn set_seed(&mut self, _seed: u64) {}
+}
+
+/// Holds the parsed actions for `hold_steps` calls to `parse_actions` (used by the parsers with a `hold_steps` option)
+#[derive(Clone)]
+pub(crate) struct ActionHold {
+    hold_steps: usize,
+    steps_left: usize,
+    held_actions: Vec<Vec<f32>>,
+}
+
+impl ActionHold {
+    pub(crate) fn new(hold_steps: usize) -> Self {
+        assert!(hold_steps > 0, "hold_steps must be at least 1");
+        ActionHold { hold_steps, steps_left: 0, held_actions: Vec::new() }
+    }
+
+    /// the held actions if they should still be repeated for `n_agents` agents
+    pub(crate) fn next_held(&mut self, n_agents: usize) -> Option<Vec<Vec<f32>>> {
+        // the agent count changed (eg. from a config update), the old actions are not usable
+        if self.steps_left == 0 || self.held_actions.len() != n_agents {
+            return None;
+        }
+        self.steps_left -= 1;
+        Some(self.held_actions.clone())
+    }
+
+    pub(crate) fn hold(&mut self, parsed_actions: &[Vec<f32>]) {
+        if self.hold_steps > 1 {
+            self.held_actions.clear();
+            self.held_actions.extend_from_slice(parsed_actions);
+            self.steps_left = self.hold_steps - 1;
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.steps_left = 0;
+        self.held_actions.clear();
+    }
 }
@@ -1,27 +1,101 @@
-use super::action_parser::ActionParser;
+use super::action_parser::{ActionHold, ActionParser};
 use crate::gamestates::game_state::GameState;
 use crate::math::clip;
 
-#[derive(Clone, Copy, Default)]
-pub struct ContinuousAction;
+/// Continuous action parser, takes 8 floats per agent (throttle, steer, pitch, yaw, roll, jump, boost, handbrake).
+/// 
+/// Analog controls are clamped to [-1, 1] and buttons are pressed when their value is > 0. 
+/// If `squash` is set, all of the values are passed through tanh first (useful for unbounded policy outputs, e.g. SAC).
+/// 
+/// With `hold_steps` > 1 each parsed action is repeated for the next `hold_steps - 1` steps, ignoring the new input.
+#[derive(Clone)]
+pub struct ContinuousAction {
+    squash: bool,
+    hold: ActionHold,
+}
 
 impl ContinuousAction {
-    pub fn new() -> Self {
-        ContinuousAction
+    /// squash defaults to false, hold_steps defaults to 1 (no holding)
+    pub fn new(squash: Option<bool>, hold_steps: Option<usize>) -> Self {
+        ContinuousAction { squash: squash.unwrap_or(false), hold: ActionHold::new(hold_steps.unwrap_or(1)) }
+    }
+}
+
+impl Default for ContinuousAction {
+    fn default() -> Self {
+        Self::new(None, None)
     }
 }
 
 impl ActionParser for ContinuousAction {
     fn parse_actions(&mut self, actions: Vec<Vec<f32>>, _state: &GameState) -> Vec<Vec<f32>> {
+        if let Some(held_actions) = self.hold.next_held(actions.len()) {
+            return held_actions;
+        }
         let mut parsed_actions = Vec::<Vec<f32>>::new();
         for mut action_vec in actions {
-            clip(&mut action_vec, 1., -1.);
+            let act_len = action_vec.len();
+            assert!(act_len == 8, "continuous action was not of length 8 (len: {act_len})");
+            if self.squash {
+                action_vec.iter_mut().for_each(|act| *act = act.tanh());
+            }
+            clip(&mut action_vec[..5], 1., -1.);
+            for act in &mut action_vec[5..] {
+                *act = (*act > 0.) as u8 as f32;
+            }
             parsed_actions.push(action_vec);
         }
+        self.hold.hold(&parsed_actions);
         parsed_actions
     }
 
+    fn reset(&mut self, _initial_state: &GameState) {
+        self.hold.reset();
+    }
+
     fn get_action_space(&mut self) -> Vec<usize> {
         vec![]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuous_clamps_and_thresholds() {
+        let mut parser = ContinuousAction::new(None, None);
+        let parsed = parser.parse_actions(vec![vec![2., -3., 0.5, -0.5, 1., 0.1, 0., -1.]], &GameState::new_test());
+        assert_eq!(parsed[0], vec![1., -1., 0.5, -0.5, 1., 1., 0., 0.]);
+    }
+
+    #[test]
+    fn continuous_squash() {
+        let mut parser = ContinuousAction::new(Some(true), None);
+        let parsed = parser.parse_actions(vec![vec![100., -100., 0., 0.5, 0., 2., -2., 0.]], &GameState::new_test());
+        assert_eq!(&parsed[0][..3], &[1., -1., 0.]);
+        assert_eq!(parsed[0][3], 0.5_f32.tanh());
+        assert_eq!(&parsed[0][5..], &[1., 0., 0.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "continuous action was not of length 8")]
+    fn continuous_wrong_length() {
+        ContinuousAction::new(None, None).parse_actions(vec![vec![0.; 5]], &GameState::new_test());
+    }
+
+    #[test]
+    fn continuous_hold_steps() {
+        let mut parser = ContinuousAction::new(None, Some(3));
+        let state = GameState::new_test();
+        parser.reset(&state);
+
+        let first = vec![vec![1., 0., 0., 0., 0., 1., 0., 0.]; 2];
+        let changed = vec![vec![-1., 0.5, 0., 0., 0., 0., 1., 0.]; 2];
+        assert_eq!(parser.parse_actions(first.clone(), &state), first);
+        // the changed input is ignored for the 2 held steps
+        assert_eq!(parser.parse_actions(changed.clone(), &state), first);
+        assert_eq!(parser.parse_actions(changed.clone(), &state), first);
+        assert_eq!(parser.parse_actions(changed.clone(), &state), changed);
+    }
+}
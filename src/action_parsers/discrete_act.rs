@@ -1,18 +1,30 @@
-use super::action_parser::ActionParser;
+use super::action_parser::{ActionHold, ActionParser};
+use crate::gamestates::game_state::GameState;
 
+/// Discrete action parser, takes a multi-discrete action of length 8 where the first 5 (throttle, steer, pitch, yaw, roll)
+/// are bins that are spread evenly over [-1, 1] and the last 3 (jump, boost, handbrake) are binary.
+/// 
+/// With `hold_steps` > 1 each parsed action is repeated for the next `hold_steps - 1` steps, ignoring the new input, 
+/// so the policy only has to be queried every `hold_steps` steps (on top of the tick skip).
 pub struct DiscreteAction {
     n_bins: usize,
+    hold: ActionHold,
 }
 
 impl DiscreteAction {
-    pub fn new() -> Self {
-        DiscreteAction { n_bins: 3 }
+    /// n_bins is the number of bins for each analog control, defaults to 3 and must be odd so that there is a bin for 0
+    /// 
+    /// hold_steps defaults to 1 (no holding)
+    pub fn new(n_bins: Option<usize>, hold_steps: Option<usize>) -> Self {
+        let n_bins = n_bins.unwrap_or(3);
+        assert!(n_bins % 2 == 1 && n_bins > 1, "n_bins must be an odd number greater than 1");
+        DiscreteAction { n_bins, hold: ActionHold::new(hold_steps.unwrap_or(1)) }
     }
 }
 
 impl Default for DiscreteAction {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, None)
     }
 }
 
@@ -23,17 +35,70 @@ impl ActionParser for DiscreteAction {
         act_space
     }
 
-    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, _state: &crate::gamestates::game_state::GameState) -> Vec<Vec<f32>> {
+    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, _state: &GameState) -> Vec<Vec<f32>> {
+        if let Some(held_actions) = self.hold.next_held(actions.len()) {
+            return held_actions;
+        }
         let mut parsed_actions = Vec::<Vec<f32>>::new();
         // [[self.n_bins; 5], bool, bool, bool]
+        let half_bins = (self.n_bins / 2) as f32;
         for mut action_vec in actions {
-            // let act = &mut action_vec[0];
-            for act in &mut action_vec {
-                *act = *act / (self.n_bins / 2) as f32 - 1.;
+            for act in &mut action_vec[..5] {
+                *act = *act / half_bins - 1.;
             }
             parsed_actions.push(action_vec);
         }
+        self.hold.hold(&parsed_actions);
 
         parsed_actions
     }
+
+    fn reset(&mut self, _initial_state: &GameState) {
+        self.hold.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discrete_bins_map_to_range() {
+        let mut parser = DiscreteAction::new(Some(5), None);
+        assert_eq!(parser.get_action_space(), vec![5, 5, 5, 5, 5, 2, 2, 2]);
+
+        let state = GameState::new_test();
+        let actions = vec![
+            vec![0., 2., 4., 1., 3., 1., 0., 1.],
+        ];
+        let parsed = parser.parse_actions(actions, &state);
+        assert_eq!(parsed[0], vec![-1., 0., 1., -0.5, 0.5, 1., 0., 1.]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn discrete_even_bins_panic() {
+        DiscreteAction::new(Some(4), None);
+    }
+
+    #[test]
+    fn discrete_hold_steps() {
+        let mut parser = DiscreteAction::new(None, Some(3));
+        let state = GameState::new_test();
+        parser.reset(&state);
+
+        let first = vec![vec![2., 0., 1., 1., 1., 1., 0., 0.]];
+        let changed = vec![vec![0., 2., 0., 2., 0., 0., 1., 1.]];
+        let first_parsed = vec![vec![1., -1., 0., 0., 0., 1., 0., 0.]];
+        let changed_parsed = vec![vec![-1., 1., -1., 1., -1., 0., 1., 1.]];
+        assert_eq!(parser.parse_actions(first.clone(), &state), first_parsed);
+        // the changed input is ignored for the 2 held steps
+        assert_eq!(parser.parse_actions(changed.clone(), &state), first_parsed);
+        assert_eq!(parser.parse_actions(changed.clone(), &state), first_parsed);
+        assert_eq!(parser.parse_actions(changed.clone(), &state), changed_parsed);
+
+        // a reset drops the held action
+        parser.reset(&state);
+        assert_eq!(parser.parse_actions(first, &state), first_parsed);
+    }
 }
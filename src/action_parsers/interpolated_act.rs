@@ -0,0 +1,103 @@
+use super::action_parser::ActionParser;
+use super::continous_act::ContinuousAction;
+use crate::gamestates::game_state::GameState;
+
+/// Continuous action parser (see `ContinuousAction`) that ramps the analog controls (throttle, steer, pitch, yaw, roll) linearly 
+/// from the previous action to the new one over the ticks of the step instead of changing them at once, the buttons change on the first tick.
+/// 
+/// At tick `i` (0 indexed) of `tick_skip` the analog controls are `prev + (new - prev) * (i + 1) / tick_skip`, 
+/// so the new action is reached on the last tick. The previous action is all zeros after a reset.
+pub struct InterpolatedContinuousAction {
+    parser: ContinuousAction,
+    prev_actions: Vec<Vec<f32>>,
+}
+
+impl InterpolatedContinuousAction {
+    /// squash defaults to false (see `ContinuousAction::new`)
+    pub fn new(squash: Option<bool>) -> Self {
+        InterpolatedContinuousAction { parser: ContinuousAction::new(squash, None), prev_actions: Vec::new() }
+    }
+}
+
+impl Default for InterpolatedContinuousAction {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ActionParser for InterpolatedContinuousAction {
+    fn get_action_space(&mut self) -> Vec<usize> {
+        self.parser.get_action_space()
+    }
+
+    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, state: &GameState) -> Vec<Vec<f32>> {
+        self.parser.parse_actions(actions, state)
+    }
+
+    fn tick_schedule(&mut self, parsed_actions: &[Vec<f32>], tick_skip: usize) -> Option<Vec<Vec<Vec<f32>>>> {
+        // the agent count changed (eg. from a config update), start from the new actions
+        if self.prev_actions.len() != parsed_actions.len() {
+            self.prev_actions = vec![vec![0.; 8]; parsed_actions.len()];
+        }
+        let schedule = (0..tick_skip)
+            .map(|tick| {
+                let frac = (tick + 1) as f32 / tick_skip as f32;
+                parsed_actions
+                    .iter()
+                    .zip(&self.prev_actions)
+                    .map(|(action, prev_action)| {
+                        let mut tick_action = action.clone();
+                        for (val, prev_val) in tick_action[..5].iter_mut().zip(prev_action) {
+                            *val = prev_val + (*val - prev_val) * frac;
+                        }
+                        tick_action
+                    })
+                    .collect()
+            })
+            .collect();
+        self.prev_actions.clear();
+        self.prev_actions.extend_from_slice(parsed_actions);
+        Some(schedule)
+    }
+
+    fn reset(&mut self, initial_state: &GameState) {
+        self.parser.reset(initial_state);
+        self.prev_actions.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolated_midpoint_is_average() {
+        let state = GameState::new_test();
+        let mut parser = InterpolatedContinuousAction::new(None);
+        parser.reset(&state);
+
+        let first = vec![vec![1., -1., 0.5, 0., 0., 1., 0., 0.]];
+        let parsed = parser.parse_actions(first.clone(), &state);
+        let schedule = parser.tick_schedule(&parsed, 8).unwrap();
+        assert_eq!(schedule.len(), 8);
+        // ramps up from zero and reaches the action on the last tick
+        assert_eq!(schedule[3][0], vec![0.5, -0.5, 0.25, 0., 0., 1., 0., 0.]);
+        assert_eq!(schedule[7], first);
+
+        let second = vec![vec![-1., 1., 0.5, 1., 0., 0., 1., 0.]];
+        let parsed = parser.parse_actions(second.clone(), &state);
+        let schedule = parser.tick_schedule(&parsed, 8).unwrap();
+        let midpoint = &schedule[3][0];
+        for i in 0..5 {
+            assert_eq!(midpoint[i], (first[0][i] + second[0][i]) / 2., "control {i} at the midpoint was not the average");
+        }
+        // buttons are not interpolated
+        assert_eq!(&schedule[0][0][5..], &second[0][5..]);
+        assert_eq!(schedule[7], second);
+
+        // the ramp starts from zero again after a reset
+        parser.reset(&state);
+        let schedule = parser.tick_schedule(&parsed, 2).unwrap();
+        assert_eq!(schedule[0][0][..5], [-0.5, 0.5, 0.25, 0.5, 0.]);
+    }
+}
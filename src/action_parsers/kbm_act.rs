@@ -0,0 +1,123 @@
+use super::action_parser::ActionParser;
+use crate::gamestates::game_state::GameState;
+use crate::math::clip;
+
+/// Keyboard/mouse style action parser, takes 5 values per agent (throttle, steer, pitch, jump, boost/handbrake) 
+/// and maps them to the full 8 length controller action.
+/// 
+/// The last input is combined like the keys on a keyboard, > 1/3 is boost, < -1/3 is handbrake (which is also air roll) and neither in between.
+/// Steer also drives yaw like the A/D keys do, in the air while handbrake (air roll) is held it drives roll instead of yaw. 
+/// Actions are matched to `state.players` by index.
+/// 
+/// If `discrete` is set, throttle, steer and pitch are bins spread evenly over [-1, 1], jump is binary 
+/// and the combined input has 3 bins (handbrake, neither, boost), otherwise the values are continuous like `ContinuousAction`.
+pub struct KBMAction {
+    n_bins: usize,
+    discrete: bool,
+}
+
+impl KBMAction {
+    /// n_bins (only used if discrete) is the number of bins for each analog control, defaults to 3 and must be odd so that there is a bin for 0.
+    /// discrete defaults to true
+    pub fn new(n_bins: Option<usize>, discrete: Option<bool>) -> Self {
+        let n_bins = n_bins.unwrap_or(3);
+        assert!(n_bins % 2 == 1 && n_bins > 1, "n_bins must be an odd number greater than 1");
+        KBMAction {
+            n_bins,
+            discrete: discrete.unwrap_or(true),
+        }
+    }
+}
+
+impl Default for KBMAction {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl ActionParser for KBMAction {
+    fn get_action_space(&mut self) -> Vec<usize> {
+        if self.discrete {
+            vec![self.n_bins, self.n_bins, self.n_bins, 2, 3]
+        } else {
+            vec![5]
+        }
+    }
+
+    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, state: &GameState) -> Vec<Vec<f32>> {
+        let mut parsed_actions = Vec::<Vec<f32>>::with_capacity(actions.len());
+        let half_bins = (self.n_bins / 2) as f32;
+        for (i, mut action_vec) in actions.into_iter().enumerate() {
+            let act_len = action_vec.len();
+            assert!(act_len == 5, "KBM action was not of length 5 (len: {act_len})");
+            if self.discrete {
+                for act in &mut action_vec[..3] {
+                    *act = *act / half_bins - 1.;
+                }
+                action_vec[4] -= 1.;
+            } else {
+                clip(&mut action_vec[..3], 1., -1.);
+            }
+
+            let [throttle, steer, pitch, jump, combined] = [action_vec[0], action_vec[1], action_vec[2], action_vec[3], action_vec[4]];
+            let boost = combined > 1. / 3.;
+            let handbrake = combined < -1. / 3.;
+            let on_ground = state.players.get(i).is_none_or(|player| player.on_ground);
+            let air_roll = handbrake && !on_ground;
+            let (yaw, roll) = if air_roll { (0., steer) } else { (steer, 0.) };
+
+            parsed_actions.push(vec![
+                throttle,
+                steer,
+                pitch,
+                yaw,
+                roll,
+                (jump > 0.) as u8 as f32,
+                boost as u8 as f32,
+                handbrake as u8 as f32,
+            ]);
+        }
+        parsed_actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kbm_steer_yaw_air_and_ground() {
+        let mut state = GameState::new_test();
+        state.players[0].on_ground = true;
+        state.players[1].on_ground = false;
+        let mut parser = KBMAction::new(None, Some(false));
+        assert_eq!(parser.get_action_space(), vec![5]);
+
+        // no handbrake, steer drives yaw on the ground and in the air
+        let parsed = parser.parse_actions(vec![vec![1., -1., 0.5, 0., 0.], vec![1., -1., 0.5, 0., 0.]], &state);
+        assert_eq!(parsed[0], vec![1., -1., 0.5, -1., 0., 0., 0., 0.]);
+        assert_eq!(parsed[1], vec![1., -1., 0.5, -1., 0., 0., 0., 0.]);
+
+        // handbrake held, powerslide on the ground and air roll in the air
+        let parsed = parser.parse_actions(vec![vec![0., 0.5, 0., 1., -1.], vec![0., 0.5, 0., 1., -1.]], &state);
+        assert_eq!(parsed[0], vec![0., 0.5, 0., 0.5, 0., 1., 0., 1.]);
+        assert_eq!(parsed[1], vec![0., 0.5, 0., 0., 0.5, 1., 0., 1.]);
+    }
+
+    #[test]
+    fn kbm_combined_boost_handbrake() {
+        let state = GameState::new_test();
+        let mut parser = KBMAction::new(Some(5), None);
+        assert_eq!(parser.get_action_space(), vec![5, 5, 5, 2, 3]);
+
+        let parsed = parser.parse_actions(vec![vec![4., 2., 0., 1., 2.], vec![0., 3., 4., 0., 0.]], &state);
+        // boost bin
+        assert_eq!(parsed[0], vec![1., 0., -1., 0., 0., 1., 1., 0.]);
+        // handbrake bin
+        assert_eq!(&parsed[1][5..], &[0., 0., 1.]);
+        assert_eq!(&parsed[1][..3], &[-1., 0.5, 1.]);
+
+        let parsed = parser.parse_actions(vec![vec![2., 2., 2., 0., 1.]], &state);
+        assert_eq!(&parsed[0][5..], &[0., 0., 0.]);
+    }
+}
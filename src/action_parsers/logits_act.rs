@@ -0,0 +1,114 @@
+use super::action_parser::ActionParser;
+use crate::gamestates::game_state::GameState;
+
+/// Wraps a discrete action parser (eg. `LookupTableAction` or `DiscreteAction`) so that the actions can be given as the logits of the policy.
+///
+/// Each action is the concatenated logits of every dimension of the wrapped parser's action space (so its sum long),
+/// every dimension is argmaxed into an index (the first one on ties) and the indices are passed on to the wrapped parser.
+pub struct LogitsAction {
+    action_parser: Box<dyn ActionParser>,
+    dims: Vec<usize>,
+}
+
+impl LogitsAction {
+    /// panics if the wrapped parser has no discrete dimensions (an empty action space)
+    pub fn new(mut action_parser: Box<dyn ActionParser>) -> Self {
+        let dims = action_parser.get_action_space();
+        assert!(!dims.is_empty() && dims.iter().all(|&dim| dim > 0), "LogitsAction needs a discrete action parser, got the action space {dims:?}");
+        LogitsAction { action_parser, dims }
+    }
+
+    /// index of the largest logit, the first one on ties (NaNs are never picked unless all logits are NaN)
+    pub fn argmax(logits: &[f32]) -> usize {
+        let mut best = 0;
+        for (i, &logit) in logits.iter().enumerate().skip(1) {
+            if logit > logits[best] || logits[best].is_nan() {
+                best = i;
+            }
+        }
+        best
+    }
+
+    fn logits_to_indices(&self, logits: &[f32]) -> Vec<f32> {
+        let logits_len: usize = self.dims.iter().sum();
+        assert!(logits.len() == logits_len, "logits (len: {}) did not match the action space {:?} (len: {logits_len})", logits.len(), self.dims);
+
+        let mut indices = Vec::with_capacity(self.dims.len());
+        let mut start = 0;
+        for &dim in &self.dims {
+            indices.push(Self::argmax(&logits[start..start + dim]) as f32);
+            start += dim;
+        }
+        indices
+    }
+}
+
+impl ActionParser for LogitsAction {
+    fn get_action_space(&mut self) -> Vec<usize> {
+        self.action_parser.get_action_space()
+    }
+
+    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, state: &GameState) -> Vec<Vec<f32>> {
+        let indices = actions.iter().map(|logits| self.logits_to_indices(logits)).collect();
+        self.action_parser.parse_actions(indices, state)
+    }
+
+    fn tick_schedule(&mut self, parsed_actions: &[Vec<f32>], tick_skip: usize) -> Option<Vec<Vec<Vec<f32>>>> {
+        self.action_parser.tick_schedule(parsed_actions, tick_skip)
+    }
+
+    fn reset(&mut self, initial_state: &GameState) {
+        self.action_parser.reset(initial_state);
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.action_parser.set_seed(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action_parsers::{discrete_act::DiscreteAction, lookup_act::LookupTableAction};
+
+    #[test]
+    fn logits_argmax_selects_table_row() {
+        let state = GameState::new_test();
+        let table = LookupTableAction::make_lookup_table();
+        let mut parser = LogitsAction::new(Box::new(LookupTableAction::new()));
+        assert_eq!(parser.get_action_space(), vec![table.len()]);
+
+        let mut logits = vec![vec![-1.; table.len()], vec![0.; table.len()]];
+        logits[0][42] = 3.;
+        // ties go to the first row
+        logits[1][7] = 1.;
+        logits[1][9] = 1.;
+        let parsed = parser.parse_actions(logits, &state);
+        assert_eq!(parsed, vec![table[42].clone(), table[7].clone()]);
+    }
+
+    #[test]
+    fn logits_split_per_dimension() {
+        let state = GameState::new_test();
+        let mut parser = LogitsAction::new(Box::new(DiscreteAction::new(None, None)));
+        let mut direct = DiscreteAction::new(None, None);
+
+        // 5 dimensions of 3 bins and 3 of 2
+        let indices = [0., 1., 2., 2., 0., 1., 0., 1.];
+        let mut logits = Vec::new();
+        for (i, &index) in indices.iter().enumerate() {
+            let dim = if i < 5 { 3 } else { 2 };
+            logits.extend((0..dim).map(|bin| if bin == index as usize { 0.5 } else { -0.5 }));
+        }
+        assert_eq!(logits.len(), 21);
+        assert_eq!(parser.parse_actions(vec![logits], &state), direct.parse_actions(vec![indices.to_vec()], &state));
+    }
+
+    #[test]
+    #[should_panic(expected = "did not match the action space")]
+    fn logits_wrong_len() {
+        let state = GameState::new_test();
+        let mut parser = LogitsAction::new(Box::new(LookupTableAction::new()));
+        parser.parse_actions(vec![vec![0.; 8]], &state);
+    }
+}
@@ -0,0 +1,103 @@
+use super::action_parser::ActionParser;
+use crate::gamestates::game_state::GameState;
+
+/// Lookup table action parser, matches the 90 action lookup table commonly used with RLGym (Necto, Nexto, etc.).
+/// 
+/// Takes a single index per agent (as the first and only value of each action vec) and returns the corresponding row of the table.
+pub struct LookupTableAction {
+    table: Vec<Vec<f32>>,
+}
+
+impl LookupTableAction {
+    pub fn new() -> Self {
+        LookupTableAction { table: Self::make_lookup_table() }
+    }
+
+    /// builds the standard table, 24 ground actions followed by 66 aerial actions
+    pub fn make_lookup_table() -> Vec<Vec<f32>> {
+        let mut actions = Vec::<Vec<f32>>::with_capacity(90);
+        // ground
+        for throttle in [-1., 0., 1.] {
+            for steer in [-1., 0., 1.] {
+                for boost in [0., 1.] {
+                    for handbrake in [0., 1.] {
+                        if boost == 1. && throttle != 1. {
+                            continue;
+                        }
+                        let throttle = if throttle != 0. { throttle } else { boost };
+                        actions.push(vec![throttle, steer, 0., steer, 0., 0., boost, handbrake]);
+                    }
+                }
+            }
+        }
+        // aerial
+        for pitch in [-1., 0., 1.] {
+            for yaw in [-1., 0., 1.] {
+                for roll in [-1., 0., 1.] {
+                    for jump in [0., 1.] {
+                        for boost in [0., 1.] {
+                            // only need roll for sideflips
+                            if jump == 1. && yaw != 0. {
+                                continue;
+                            }
+                            // duplicate of a ground action
+                            if pitch == 0. && roll == 0. && jump == 0. {
+                                continue;
+                            }
+                            // enable handbrake for potential wavedashes
+                            let handbrake = (jump == 1. && (pitch != 0. || yaw != 0. || roll != 0.)) as u8 as f32;
+                            actions.push(vec![boost, yaw, pitch, yaw, roll, jump, boost, handbrake]);
+                        }
+                    }
+                }
+            }
+        }
+        actions
+    }
+}
+
+impl Default for LookupTableAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionParser for LookupTableAction {
+    fn get_action_space(&mut self) -> Vec<usize> {
+        vec![self.table.len()]
+    }
+
+    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, _state: &GameState) -> Vec<Vec<f32>> {
+        let mut parsed_actions = Vec::<Vec<f32>>::with_capacity(actions.len());
+        for action_vec in actions {
+            parsed_actions.push(self.table[action_vec[0] as usize].clone());
+        }
+        parsed_actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_table_matches_rlgym() {
+        let table = LookupTableAction::make_lookup_table();
+        assert_eq!(table.len(), 90);
+        assert_eq!(table[0], vec![-1., -1., 0., -1., 0., 0., 0., 0.]);
+        // throttle 1, steer 0, boost, no handbrake
+        assert_eq!(table[18], vec![1., 0., 0., 0., 0., 0., 1., 0.]);
+        // first aerial action, pitch -1, yaw -1, roll -1, no jump or boost
+        assert_eq!(table[24], vec![0., -1., -1., -1., -1., 0., 0., 0.]);
+        assert_eq!(table[89], vec![1., 1., 1., 1., 1., 0., 1., 0.]);
+    }
+
+    #[test]
+    fn lookup_parses_indices() {
+        let mut parser = LookupTableAction::new();
+        assert_eq!(parser.get_action_space(), vec![90]);
+        let parsed = parser.parse_actions(vec![vec![18.], vec![0.]], &GameState::new_test());
+        assert_eq!(parsed[0], vec![1., 0., 0., 0., 0., 0., 1., 0.]);
+        assert_eq!(parsed[1], vec![-1., -1., 0., -1., 0., 0., 0., 0.]);
+    }
+}
@@ -2,4 +2,10 @@ pub mod action_parser;
 pub mod continous_act;
 pub mod default_act;
 pub mod discrete_act;
+pub mod interpolated_act;
+pub mod kbm_act;
+pub mod logits_act;
+pub mod lookup_act;
+pub mod rlbot_act;
+pub mod sticky_act;
 pub mod test_parser;
\ No newline at end of file
@@ -0,0 +1,63 @@
+use super::action_parser::ActionParser;
+use crate::gamestates::game_state::GameState;
+
+/// Action parser for actions that are already in RLBot's ControllerState order 
+/// (throttle, steer, pitch, yaw, roll, jump, boost, handbrake) with the bools as 0/1, eg. from RLBot logs.
+/// 
+/// The actions are passed through unchanged after checking that they have 8 values, 
+/// that the analog values are in [-1, 1] and that the buttons are exactly 0 or 1 (panics otherwise).
+/// `CarControls` can be turned into this layout with `IntoArray::into_array`.
+#[derive(Clone, Copy, Default)]
+pub struct RLBotAction {}
+
+impl RLBotAction {
+    pub fn new() -> Self {
+        RLBotAction {}
+    }
+}
+
+impl ActionParser for RLBotAction {
+    fn get_action_space(&mut self) -> Vec<usize> {
+        vec![8]
+    }
+
+    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, _state: &GameState) -> Vec<Vec<f32>> {
+        for action in &actions {
+            let act_len = action.len();
+            assert!(act_len == 8, "RLBot action was not of length 8 (len: {act_len})");
+            assert!(action[..5].iter().all(|val| (-1. ..=1.).contains(val)), "RLBot action analog values were not in [-1, 1]: {action:?}");
+            assert!(action[5..].iter().all(|val| *val == 0. || *val == 1.), "RLBot action buttons were not 0 or 1: {action:?}");
+        }
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketsim_rs::sim::CarControls;
+
+    use super::*;
+    use crate::IntoArray;
+
+    #[test]
+    fn rlbot_controller_state_round_trip() {
+        let controls = CarControls { throttle: 1., steer: -0.25, pitch: 0.5, yaw: -1., roll: 0., jump: true, boost: false, handbrake: true };
+        let action = controls.into_array().to_vec();
+        assert_eq!(action, vec![1., -0.25, 0.5, -1., 0., 1., 0., 1.]);
+
+        let parsed = RLBotAction::new().parse_actions(vec![action.clone()], &GameState::new_test());
+        assert_eq!(parsed, vec![action]);
+    }
+
+    #[test]
+    #[should_panic(expected = "buttons were not 0 or 1")]
+    fn rlbot_invalid_button() {
+        RLBotAction::new().parse_actions(vec![vec![0., 0., 0., 0., 0., 0.5, 0., 0.]], &GameState::new_test());
+    }
+
+    #[test]
+    #[should_panic(expected = "analog values were not in [-1, 1]")]
+    fn rlbot_invalid_analog() {
+        RLBotAction::new().parse_actions(vec![vec![1.5, 0., 0., 0., 0., 0., 0., 0.]], &GameState::new_test());
+    }
+}
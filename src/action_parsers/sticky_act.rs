@@ -0,0 +1,107 @@
+use rand::prelude::*;
+
+use super::action_parser::ActionParser;
+use crate::gamestates::game_state::GameState;
+
+/// Wraps another action parser and with probability `sticky_action_prob` (drawn per player every step) 
+/// repeats the player's previous parsed action instead of the new one, like the sticky actions used for Atari.
+/// 
+/// The previous actions are cleared on reset so the first action of an episode is never replaced.
+pub struct StickyActionParser {
+    action_parser: Box<dyn ActionParser>,
+    sticky_action_prob: f32,
+    prev_actions: Vec<Vec<f32>>,
+    rng: SmallRng,
+}
+
+impl StickyActionParser {
+    /// seed defaults to a random seed, see also `Gym::seed`
+    pub fn new(action_parser: Box<dyn ActionParser>, sticky_action_prob: f32, seed: Option<u64>) -> Self {
+        assert!((0. ..=1.).contains(&sticky_action_prob), "sticky action probability must be in [0, 1]");
+        let seed = match seed {
+            Some(seed) => seed,
+            None => thread_rng().gen(),
+        };
+        StickyActionParser {
+            action_parser,
+            sticky_action_prob,
+            prev_actions: Vec::new(),
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl ActionParser for StickyActionParser {
+    fn get_action_space(&mut self) -> Vec<usize> {
+        self.action_parser.get_action_space()
+    }
+
+    fn parse_actions(&mut self, actions: Vec<Vec<f32>>, state: &GameState) -> Vec<Vec<f32>> {
+        let mut parsed_actions = self.action_parser.parse_actions(actions, state);
+        // the player count changed (eg. from a config update), nothing to repeat
+        if self.prev_actions.len() == parsed_actions.len() {
+            for (action, prev_action) in parsed_actions.iter_mut().zip(&self.prev_actions) {
+                if self.rng.gen::<f32>() < self.sticky_action_prob {
+                    action.clone_from(prev_action);
+                }
+            }
+        }
+        self.prev_actions.clone_from(&parsed_actions);
+        parsed_actions
+    }
+
+    fn tick_schedule(&mut self, parsed_actions: &[Vec<f32>], tick_skip: usize) -> Option<Vec<Vec<Vec<f32>>>> {
+        self.action_parser.tick_schedule(parsed_actions, tick_skip)
+    }
+
+    fn reset(&mut self, initial_state: &GameState) {
+        self.prev_actions.clear();
+        self.action_parser.reset(initial_state);
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self.action_parser.set_seed(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action_parsers::continous_act::ContinuousAction;
+
+    #[test]
+    fn sticky_prob_one_repeats_first_action() {
+        let state = GameState::new_test();
+        let mut parser = StickyActionParser::new(Box::new(ContinuousAction::new(None, None)), 1., Some(0));
+        parser.reset(&state);
+
+        let first = vec![vec![1., -1., 0.5, 0., 0., 1., 0., 1.], vec![0., 0., 0., 0., 0., 0., 1., 0.]];
+        assert_eq!(parser.parse_actions(first.clone(), &state), first);
+        for i in 0..20 {
+            let action = vec![vec![i as f32 / 20.; 8]; 2];
+            assert_eq!(parser.parse_actions(action, &state), first);
+        }
+
+        // the stored action is cleared on reset
+        parser.reset(&state);
+        let next = vec![vec![0.; 8]; 2];
+        assert_eq!(parser.parse_actions(next.clone(), &state), next);
+    }
+
+    #[test]
+    fn sticky_seeded() {
+        let state = GameState::new_test();
+        let run = |seed| {
+            let mut parser = StickyActionParser::new(Box::new(ContinuousAction::new(None, None)), 0.5, None);
+            parser.set_seed(seed);
+            parser.reset(&state);
+            (0..50).map(|i| parser.parse_actions(vec![vec![(i % 3) as f32 - 1.; 8]; 2], &state)).collect::<Vec<_>>()
+        };
+        assert_eq!(run(3), run(3));
+        // prob 0 never repeats
+        let mut parser = StickyActionParser::new(Box::new(ContinuousAction::new(None, None)), 0., Some(1));
+        parser.parse_actions(vec![vec![1.; 8]], &state);
+        assert_eq!(parser.parse_actions(vec![vec![0.; 8]], &state), vec![vec![0.; 8]]);
+    }
+}
@@ -5,6 +5,9 @@ pub const BACK_WALL_Y: f32 = 5120.;
 pub const CEILING_Z: f32 = 2044.;
 pub const BACK_NET_Y: f32 = 6000.;
 pub const GOAL_HEIGHT: f32 = 642.775;
+pub const GOAL_HALF_WIDTH: f32 = 892.755;
+/// height above which a car is considered to be off the ramps (the curved part of the walls), as in RLGym
+pub const RAMP_HEIGHT: f32 = 256.;
 
 pub const ORANGE_GOAL_CENTER: Position = Position {
     x: 0.,
@@ -42,7 +45,12 @@ pub const NUM_ACTIONS: usize = 8;
 
 pub const GRAVITY_Z: f32 = -650.;
 
+/// max boost of a car in the sim, the boost in `PlayerData`/`CarWrapper` is divided by this
 pub const ROCKETSIM_BOOST_MAX: f32 = 100.;
+/// max boost of a car in the gym (`PlayerData::boost_amount`, `CarWrapper::boost`)
+pub const BOOST_MAX: f32 = 1.;
+/// boost that the cars get on kickoff (in the gym scale), used by the kickoff state setters
+pub const KICKOFF_BOOST: f32 = 0.33;
 
 // 100/3
 pub const ROCKETSIM_BOOST_PER_SEC: f32 = ROCKETSIM_BOOST_MAX / 3.;
@@ -83,3 +91,28 @@ pub const BOOST_LOCATIONS: [[f32; 3]; 34] = [
     [1792.0, 4184.0, 70.0],
     [0.0, 4240.0, 70.0],
 ];
+
+#[cfg(test)]
+mod tests {
+    use rocketsim_rs::consts;
+
+    use super::*;
+
+    #[test]
+    fn constants_match_rocketsim() {
+        assert_eq!(SIDE_WALL_X, consts::ARENA_EXTENT_X);
+        assert_eq!(BACK_WALL_Y, consts::ARENA_EXTENT_Y);
+        assert_eq!(ORANGE_GOAL_CENTER.y, consts::ARENA_EXTENT_Y);
+        assert_eq!(BLUE_GOAL_CENTER.y, -consts::ARENA_EXTENT_Y);
+        assert!(CEILING_Z <= consts::ARENA_HEIGHT);
+        // RLGym's ball radius is slightly larger than the sim's collision radius
+        assert!(BALL_RADIUS >= consts::BALL_COLLISION_RADIUS_SOCCAR);
+        assert_eq!(CAR_MAX_SPEED, consts::CAR_MAX_SPEED);
+        assert_eq!(BALL_MAX_SPEED, consts::BALL_MAX_SPEED);
+        assert_eq!(CAR_MAX_ANG_VEL, consts::CAR_MAX_ANG_SPEED);
+        assert_eq!(SUPERSONIC_THRESHOLD, consts::SUPERSONIC_START_SPEED);
+        assert_eq!(GRAVITY_Z, consts::GRAVITY_Z);
+        assert_eq!(ROCKETSIM_BOOST_MAX, consts::BOOST_MAX);
+        assert_eq!(ROCKETSIM_BOOST_PER_SEC, consts::BOOST_USED_PER_SECOND);
+    }
+}
@@ -1,8 +1,14 @@
+use std::collections::VecDeque;
+
 use crate::gamestates::game_state::GameState;
+use crate::math::Vec3;
 
 use super::terminal_condition::TerminalCondition;
 
-/// Returns a terminal signal when max_steps has been hit
+/// Returns a truncated signal when max_steps has been hit.
+/// 
+/// Steps are counted per agent step (one call per `Gym::step`, so after tick skip) and not per physics tick.
+/// The timeout is reported through `is_truncated`, the gym merges it into `done` unless truncation is enabled in `make`.
 pub struct TimeoutCondition {
     steps: i64,
     max_steps: i64,
@@ -30,7 +36,9 @@ impl TerminalCondition for TimeoutCondition {
     
 }
 
-/// Returns a terminal signal when there have been no ball touches in max_steps
+/// Returns a truncated signal when there have been no ball touches in max_steps.
+/// 
+/// A touch is detected through `PlayerData.ball_touched` of any player, which resets the counter.
 pub struct NoTouchTimeoutCondition {
     steps: i64,
     max_steps: i64,
@@ -62,7 +70,9 @@ impl TerminalCondition for NoTouchTimeoutCondition {
     }
 }
 
-/// Returns a terminal signal when the ball has been scored
+/// Returns a terminal signal when the ball has been scored.
+/// 
+/// The scores are re-baselined after every detected goal so that multiple goals in one episode are each detected once.
 pub struct GoalScoredCondition {
     blue_score: i32,
     orange_score: i32,
@@ -81,7 +91,10 @@ impl Default for GoalScoredCondition {
 }
 
 impl TerminalCondition for GoalScoredCondition {
-    fn reset(&mut self, _initial_state: &GameState) {}
+    fn reset(&mut self, initial_state: &GameState) {
+        self.blue_score = initial_state.blue_score;
+        self.orange_score = initial_state.orange_score;
+    }
 
     fn is_terminal(&mut self, current_state: &GameState) -> bool {
         if current_state.blue_score != self.blue_score || current_state.orange_score != self.orange_score {
@@ -97,3 +110,383 @@ impl TerminalCondition for GoalScoredCondition {
         false
     }
 }
+
+/// Returns a terminal signal on the first step that a ball touch is detected after reset.
+/// 
+/// Touches are detected through `PlayerData.ball_touched`, which the sim wrapper only sets on the step of a new touch.
+/// If team is set, only touches from players of that team (0 for blue, 1 for orange) end the episode.
+pub struct BallTouchedCondition {
+    team: Option<u8>,
+}
+
+impl BallTouchedCondition {
+    pub fn new(team: Option<u8>) -> Self {
+        BallTouchedCondition { team }
+    }
+}
+
+impl Default for BallTouchedCondition {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl TerminalCondition for BallTouchedCondition {
+    fn reset(&mut self, _initial_state: &GameState) {}
+
+    fn is_terminal(&mut self, current_state: &GameState) -> bool {
+        current_state
+            .players
+            .iter()
+            .any(|player| player.ball_touched && self.team.is_none_or(|team| player.team_num == team as i32))
+    }
+
+    fn is_truncated(&mut self, _current_state: &GameState) -> bool {
+        false
+    }
+}
+
+/// Returns a terminal signal once one team has scored `diff` more goals than the other since reset (a mercy rule).
+/// 
+/// The score difference at reset is the baseline, so scores carried over from before the reset don't count.
+pub struct MercyRuleCondition {
+    diff: i32,
+    initial_diff: i32,
+}
+
+impl MercyRuleCondition {
+    pub fn new(diff: i32) -> Self {
+        assert!(diff > 0, "the goal difference must be positive");
+        MercyRuleCondition { diff, initial_diff: 0 }
+    }
+}
+
+impl TerminalCondition for MercyRuleCondition {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.initial_diff = initial_state.blue_score - initial_state.orange_score;
+    }
+
+    fn is_terminal(&mut self, current_state: &GameState) -> bool {
+        let current_diff = current_state.blue_score - current_state.orange_score;
+        (current_diff - self.initial_diff).abs() >= self.diff
+    }
+
+    fn is_truncated(&mut self, _current_state: &GameState) -> bool {
+        false
+    }
+}
+
+/// Returns a terminal signal when the ball is inside the box between `min` and `max` (x, y, z, inclusive), 
+/// eg. a corner for positional drills or the attacking third for a scoring region.
+pub struct BallInRegionCondition {
+    min: [f32; 3],
+    max: [f32; 3],
+}
+
+impl BallInRegionCondition {
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        assert!(min.iter().zip(&max).all(|(min, max)| min <= max), "the min bounds of the region must not be above the max bounds");
+        BallInRegionCondition { min, max }
+    }
+}
+
+impl TerminalCondition for BallInRegionCondition {
+    fn reset(&mut self, _initial_state: &GameState) {}
+
+    fn is_terminal(&mut self, current_state: &GameState) -> bool {
+        let pos = current_state.ball.position.into_array();
+        pos.iter().zip(self.min.iter().zip(&self.max)).all(|(val, (min, max))| (min..=max).contains(&val))
+    }
+
+    fn is_truncated(&mut self, _current_state: &GameState) -> bool {
+        false
+    }
+}
+
+/// Returns a truncated signal when every car moved less than `min_movement` (uu, summed over the steps) during the last `steps` steps, 
+/// eg. when the cars are wedged against a wall. The position history is cleared on reset and when the number of players changes.
+pub struct StuckTruncationCondition {
+    steps: usize,
+    min_movement: f32,
+    /// positions of the cars on the last `steps + 1` steps (including the reset state), oldest first
+    history: VecDeque<Vec<Vec3>>,
+}
+
+impl StuckTruncationCondition {
+    /// min_movement defaults to 50. (uu)
+    pub fn new(steps: usize, min_movement: Option<f32>) -> Self {
+        assert!(steps > 0, "steps must be at least 1");
+        StuckTruncationCondition { steps, min_movement: min_movement.unwrap_or(50.), history: VecDeque::with_capacity(steps + 1) }
+    }
+
+    fn push_positions(&mut self, state: &GameState) {
+        let positions: Vec<Vec3> = state.players.iter().map(|player| player.car_data.pos()).collect();
+        if self.history.back().is_some_and(|prev| prev.len() != positions.len()) {
+            self.history.clear();
+        }
+        if self.history.len() > self.steps {
+            self.history.pop_front();
+        }
+        self.history.push_back(positions);
+    }
+}
+
+impl TerminalCondition for StuckTruncationCondition {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.history.clear();
+        self.push_positions(initial_state);
+    }
+
+    fn is_terminal(&mut self, _current_state: &GameState) -> bool {
+        false
+    }
+
+    fn is_truncated(&mut self, current_state: &GameState) -> bool {
+        self.push_positions(current_state);
+        if self.history.len() <= self.steps {
+            return false;
+        }
+        let num_cars = self.history[0].len();
+        (0..num_cars).all(|car| {
+            let movement: f32 = self.history.iter().zip(self.history.iter().skip(1)).map(|(prev, next)| prev[car].dist(next[car])).sum();
+            movement < self.min_movement
+        })
+    }
+}
+
+/// Returns a truncated signal when the ball stayed within `radius` (uu) of the same point for `steps` steps, 
+/// unlike `NoTouchTimeoutCondition` touches that barely move the ball don't restart the count.
+/// 
+/// The point is where the ball was when it last left the radius (or on reset), so slow drifting restarts the count once it adds up to `radius`.
+pub struct BallStillTimeout {
+    steps: usize,
+    radius: f32,
+    anchor: Vec3,
+    still_steps: usize,
+}
+
+impl BallStillTimeout {
+    /// radius defaults to 100. (uu)
+    pub fn new(steps: usize, radius: Option<f32>) -> Self {
+        assert!(steps > 0, "steps must be at least 1");
+        BallStillTimeout { steps, radius: radius.unwrap_or(100.), anchor: Vec3::new(0., 0., 0.), still_steps: 0 }
+    }
+}
+
+impl TerminalCondition for BallStillTimeout {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.anchor = initial_state.ball.pos();
+        self.still_steps = 0;
+    }
+
+    fn is_terminal(&mut self, _current_state: &GameState) -> bool {
+        false
+    }
+
+    fn is_truncated(&mut self, current_state: &GameState) -> bool {
+        let ball_pos = current_state.ball.pos();
+        if ball_pos.dist(self.anchor) > self.radius {
+            self.anchor = ball_pos;
+            self.still_steps = 0;
+        } else {
+            self.still_steps += 1;
+        }
+        self.still_steps >= self.steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_values::{BACK_WALL_Y, CEILING_Z, ORANGE_TEAM, SIDE_WALL_X};
+    use crate::gamestates::physics_object::Position;
+
+    #[test]
+    fn timeout_condition_counts_steps() {
+        let state = GameState::new_test();
+        let max_steps = 5;
+        let mut condition = TimeoutCondition::new(max_steps);
+        condition.reset(&state);
+
+        for _ in 0..max_steps - 1 {
+            assert!(!condition.is_truncated(&state));
+        }
+        assert!(condition.is_truncated(&state), "timeout did not trigger after {max_steps} steps");
+
+        condition.reset(&state);
+        assert!(!condition.is_truncated(&state), "reset did not zero the step counter");
+    }
+
+    #[test]
+    fn goal_scored_condition_rebaselines() {
+        let mut state = GameState::new_test();
+        state.blue_score = 3;
+        state.orange_score = 1;
+        let mut condition = GoalScoredCondition::new();
+        condition.reset(&state);
+        assert!(!condition.is_terminal(&state), "scores carried over from before reset were counted as a goal");
+
+        state.blue_score += 1;
+        assert!(condition.is_terminal(&state));
+        assert!(!condition.is_terminal(&state), "the same goal was detected twice");
+
+        state.orange_score += 1;
+        assert!(condition.is_terminal(&state));
+        assert!(!condition.is_terminal(&state));
+    }
+
+    #[test]
+    fn mercy_rule_condition_threshold() {
+        let mut state = GameState::new_test();
+        state.blue_score = 2;
+        let mut condition = MercyRuleCondition::new(3);
+        condition.reset(&state);
+        assert!(!condition.is_terminal(&state), "the lead from before reset was counted");
+
+        // orange scores 3 in a row, blue answers once in between
+        for (blue_goals, orange_goals, terminal) in [(0, 1, false), (0, 1, false), (1, 0, false), (0, 1, false), (0, 1, true)] {
+            state.blue_score += blue_goals;
+            state.orange_score += orange_goals;
+            assert_eq!(condition.is_terminal(&state), terminal, "blue {} orange {}", state.blue_score, state.orange_score);
+        }
+        assert!(!condition.is_truncated(&state));
+
+        condition.reset(&state);
+        assert!(!condition.is_terminal(&state));
+        state.blue_score += 3;
+        assert!(condition.is_terminal(&state));
+    }
+
+    #[test]
+    fn stuck_truncation_condition_window() {
+        let mut state = GameState::new_test();
+        let steps = 4;
+        let mut condition = StuckTruncationCondition::new(steps, Some(10.));
+        condition.reset(&state);
+
+        // stationary cars truncate after the window is full
+        for _ in 0..steps - 1 {
+            assert!(!condition.is_truncated(&state));
+        }
+        assert!(condition.is_truncated(&state), "stationary cars were not truncated after {steps} steps");
+        assert!(!condition.is_terminal(&state));
+
+        // one car moving is enough to not be stuck, until it has been still for the whole window again
+        condition.reset(&state);
+        state.players[1].car_data.position.x += 100.;
+        assert!(!condition.is_truncated(&state));
+        for _ in 0..steps - 1 {
+            assert!(!condition.is_truncated(&state));
+        }
+        assert!(condition.is_truncated(&state));
+
+        // small moves add up over the window
+        condition.reset(&state);
+        for _ in 0..steps * 2 {
+            state.players[0].car_data.position.y += 3.;
+            assert!(!condition.is_truncated(&state));
+        }
+    }
+
+    #[test]
+    fn ball_in_region_condition_bounds() {
+        let mut state = GameState::new_test();
+        // the orange attacking third
+        let mut condition = BallInRegionCondition::new([-SIDE_WALL_X, -BACK_WALL_Y, 0.], [SIDE_WALL_X, -BACK_WALL_Y / 3., CEILING_Z]);
+        condition.reset(&state);
+
+        state.ball.position = Position { x: 1000., y: -4000., z: 500. };
+        assert!(condition.is_terminal(&state));
+        state.ball.position = Position { x: 1000., y: 0., z: 500. };
+        assert!(!condition.is_terminal(&state));
+        // the bounds are inclusive
+        state.ball.position = Position { x: 0., y: -BACK_WALL_Y / 3., z: 0. };
+        assert!(condition.is_terminal(&state));
+        assert!(!condition.is_truncated(&state));
+
+        // only in the region if every axis is inside
+        let mut condition = BallInRegionCondition::new([3000., 4000., 0.], [SIDE_WALL_X, BACK_WALL_Y, 500.]);
+        state.ball.position = Position { x: 3500., y: 4500., z: 100. };
+        assert!(condition.is_terminal(&state));
+        state.ball.position = Position { x: 3500., y: 4500., z: 1000. };
+        assert!(!condition.is_terminal(&state));
+        state.ball.position = Position { x: -3500., y: 4500., z: 100. };
+        assert!(!condition.is_terminal(&state));
+    }
+
+    #[test]
+    fn ball_touched_condition_team_filter() {
+        let mut state = GameState::new_test();
+        let mut condition = BallTouchedCondition::new(Some(ORANGE_TEAM as u8));
+        condition.reset(&state);
+        assert!(!condition.is_terminal(&state));
+
+        // blue touches are ignored when the orange team is required
+        state.players[0].ball_touched = true;
+        assert!(!condition.is_terminal(&state));
+
+        state.players[1].team_num = ORANGE_TEAM;
+        state.players[1].ball_touched = true;
+        assert!(condition.is_terminal(&state), "an orange touch did not end the episode");
+
+        // the touch is only flagged on the step it happened
+        state.players[0].ball_touched = false;
+        state.players[1].ball_touched = false;
+        assert!(!condition.is_terminal(&state));
+
+        let mut condition = BallTouchedCondition::new(None);
+        condition.reset(&state);
+        state.players[0].ball_touched = true;
+        assert!(condition.is_terminal(&state), "a touch from any team did not end the episode");
+    }
+
+    #[test]
+    fn no_touch_timeout_reset_by_touch() {
+        let mut state = GameState::new_test();
+        let mut condition = NoTouchTimeoutCondition::new(3);
+        condition.reset(&state);
+
+        assert!(!condition.is_truncated(&state));
+        assert!(!condition.is_truncated(&state));
+
+        state.players[1].ball_touched = true;
+        assert!(!condition.is_truncated(&state), "a touch did not reset the timer");
+        state.players[1].ball_touched = false;
+
+        assert!(!condition.is_truncated(&state));
+        assert!(!condition.is_truncated(&state));
+        assert!(condition.is_truncated(&state), "timeout did not trigger after 3 steps without a touch");
+    }
+
+    #[test]
+    fn ball_still_timeout_truncates_stationary_ball() {
+        let mut state = GameState::new_test();
+        let steps = 5;
+        let mut condition = BallStillTimeout::new(steps, Some(50.));
+        condition.reset(&state);
+
+        // jittering inside the radius still counts as still
+        for i in 0..steps - 1 {
+            state.ball.position.x += if i % 2 == 0 { 20. } else { -20. };
+            assert!(!condition.is_truncated(&state));
+        }
+        assert!(condition.is_truncated(&state), "nearly stationary ball was not truncated after {steps} steps");
+        assert!(!condition.is_terminal(&state));
+
+        // leaving the radius restarts the count
+        condition.reset(&state);
+        for _ in 0..steps - 1 {
+            assert!(!condition.is_truncated(&state));
+        }
+        state.ball.position.y += 200.;
+        assert!(!condition.is_truncated(&state));
+        for _ in 0..steps - 1 {
+            assert!(!condition.is_truncated(&state));
+        }
+        assert!(condition.is_truncated(&state));
+
+        // reset restarts the count too
+        condition.reset(&state);
+        assert!(!condition.is_truncated(&state));
+    }
+}
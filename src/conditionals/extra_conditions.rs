@@ -2,6 +2,9 @@ use crate::gamestates::game_state::GameState;
 
 use super::terminal_condition::TerminalCondition;
 
+/// Combines multiple terminal conditions, terminal/truncated if any of the conditions are.
+/// 
+/// Every condition is evaluated each step (no short-circuiting) so that stateful conditions like `TimeoutCondition` keep counting.
 pub struct CombinedTerminalConditions {
     conditionals: Vec<Box<dyn TerminalCondition>>,
 }
@@ -22,13 +25,19 @@ impl TerminalCondition for CombinedTerminalConditions {
     }
 
     fn is_terminal(&mut self, current_state: &GameState) -> bool {
-        self.conditionals.iter_mut().map(|f| f.is_terminal(current_state))
-        .any(|x| x)
+        let mut terminal = false;
+        for conditional in self.conditionals.iter_mut() {
+            terminal |= conditional.is_terminal(current_state);
+        }
+        terminal
     }
 
     fn is_truncated(&mut self, current_state: &GameState) -> bool {
-        self.conditionals.iter_mut().map(|f| f.is_truncated(current_state))
-        .any(|x| x)
+        let mut truncated = false;
+        for conditional in self.conditionals.iter_mut() {
+            truncated |= conditional.is_truncated(current_state);
+        }
+        truncated
     }
 }
 
@@ -67,3 +76,30 @@ impl TerminalCondition for NoTouchKickoffTimeoutCondition {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conditionals::common_conditions::{GoalScoredCondition, TimeoutCondition};
+
+    #[test]
+    fn combined_conditions_evaluate_every_child() {
+        let mut state = GameState::new_test();
+        let mut condition = CombinedTerminalConditions::new(vec![
+            Box::new(GoalScoredCondition::new()),
+            Box::new(TimeoutCondition::new(3)),
+        ]);
+        condition.reset(&state);
+
+        assert!(!condition.is_terminal(&state));
+        assert!(!condition.is_truncated(&state));
+
+        state.blue_score += 1;
+        assert!(condition.is_terminal(&state));
+        assert!(!condition.is_truncated(&state));
+
+        // the timeout must have kept counting while the goal condition was terminal
+        assert!(!condition.is_terminal(&state));
+        assert!(condition.is_truncated(&state));
+    }
+}
@@ -1,4 +1,9 @@
-use rocketsim_rs::sim::CarConfig;
+use std::collections::HashMap;
+
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use rocketsim_rs::{sim::{BallState, CarConfig, GameMode}, GameState as SimGameState};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{
     action_parsers::action_parser::ActionParser,
@@ -7,7 +12,12 @@ use crate::{
     reward_functions::reward_fn::RewardFn,
     sim_wrapper::wrapper::RocketsimWrapper,
     state_setters::{state_setter::StateSetter, wrappers::state_wrapper::StateWrapper}, make::MakeConfig,
+    state_generator::state_modifier::StateModifier,
+    error::GymError,
+    math::obs_to_f64,
 };
+#[cfg(feature = "parallel_obs")]
+use crate::obs_builders::obs_builder::par_build_observations;
 
 use crate::gamestates::game_state::GameState;
 
@@ -19,6 +29,7 @@ pub struct GameMatch {
     pub _obs_builder: Vec<Box<dyn ObsBuilder>>,
     pub _action_parser: Box<dyn ActionParser>,
     pub _state_setter: Box<dyn StateSetter>,
+    pub _state_modifiers: Vec<Box<dyn StateModifier>>,
     pub agents: usize,
     pub observation_space: Vec<usize>,
     pub use_single_obs: bool,
@@ -28,11 +39,27 @@ pub struct GameMatch {
     // pub last_touch: i32,
     pub _initial_score: i32,
     pub sim_wrapper: RocketsimWrapper,
+    /// (tick, num steps, dt, prediction) of the last ball prediction, see `predict_ball`
+    ball_prediction_cache: Option<(u64, usize, f32, Vec<BallState>)>,
+    /// reused by `build_observations_into`
+    obs_buffers: Vec<Vec<f32>>,
+    /// for `GameConfig.shuffle_cars`
+    shuffle_rng: SmallRng,
+    /// per tick actions from the action parser for the next step, see `ActionParser::tick_schedule`
+    tick_schedule: Option<Vec<Vec<Vec<f32>>>>,
 }
 
-/// Config struct that takes mutators, team size, tick skip, and spawn opponents.
+/// Config struct that takes mutators, team size, tick skip, spawn opponents, the car config, the game mode and optionally per team car counts.
 /// Should be used in the `make` function.
 /// 
+/// `car_configs` sets the car config of each car in spawn order (blue cars first), cars past the end of it use `car_config`.
+/// 
+/// `blue_size` and `orange_size` override the car count of each team (eg. 1v2), if they are None then `team_size` is used for blue 
+/// and for orange too if `spawn_opponents` is true. See `blue_count` and `orange_count`.
+/// 
+/// `game_mode` is passed through to RocketSim, so Soccar, Hoops, Heatseeker and Snowday are supported (Dropshot is not supported by RocketSim). 
+/// Note that the constants in `common_values` (goal locations, boost pads, etc.) are for Soccar.
+/// 
 /// # Default
 /// ```rust,ignore
 /// fn default() -> Self {
@@ -43,18 +70,69 @@ pub struct GameMatch {
 ///         tick_skip: 8, 
 ///         spawn_opponents: true, 
 ///         car_config: CarConfig::octane(),
+///         game_mode: GameMode::Soccar,
+///         blue_size: None,
+///         orange_size: None,
+///         car_configs: vec![],
+///         shuffle_cars: false,
+///         pad_opponent_obs: false,
 ///     }
 /// }
 /// ```
 /// 
-#[derive(Clone, Copy, Debug)]
+/// With the `serde` feature, `car_config` and `car_configs` are (de)serialized by name (see `car_config_name`) so only the preset configs can be serialized.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GameConfig {
     pub gravity: f32,
     pub boost_consumption: f32,
     pub team_size: usize,
     pub tick_skip: usize,
     pub spawn_opponents: bool,
+    #[cfg_attr(feature = "serde", serde(with = "car_config_serde"))]
     pub car_config: &'static CarConfig,
+    #[cfg_attr(feature = "serde", serde(with = "GameModeDef"))]
+    pub game_mode: GameMode,
+    pub blue_size: Option<usize>,
+    pub orange_size: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default, with = "car_configs_serde"))]
+    pub car_configs: Vec<&'static CarConfig>,
+    /// randomly permutes which car each agent index controls on every reset (seeded by `Gym::seed`),
+    /// the observations, actions, rewards and stats of an agent all stay with its car for the episode.
+    /// `PlayerData.car_id` tells which car an agent got.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shuffle_cars: bool,
+    /// pads the obs of `DefaultObs` and `AdvancedObs` with zeroed allies and opponents up to `max_team_size` cars per team,
+    /// so the obs shape stays the same with `spawn_opponents` false (e.g. the 1v1 shape in a 1v0) or uneven teams (e.g. the 2v2 shape in a 1v2)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pad_opponent_obs: bool,
+}
+
+impl GameConfig {
+    /// number of blue cars
+    pub fn blue_count(&self) -> usize {
+        self.blue_size.unwrap_or(self.team_size)
+    }
+
+    /// number of orange cars
+    pub fn orange_count(&self) -> usize {
+        self.orange_size.unwrap_or(if self.spawn_opponents { self.team_size } else { 0 })
+    }
+
+    /// number of cars on the larger team
+    pub fn max_team_size(&self) -> usize {
+        self.blue_count().max(self.orange_count())
+    }
+
+    /// total number of cars (and so agents)
+    pub fn num_cars(&self) -> usize {
+        self.blue_count() + self.orange_count()
+    }
+
+    /// car config of the car at spawn_index (blue cars first)
+    pub fn car_config_for(&self, spawn_index: usize) -> &'static CarConfig {
+        self.car_configs.get(spawn_index).copied().unwrap_or(self.car_config)
+    }
 }
 
 impl Default for GameConfig {
@@ -66,36 +144,121 @@ impl Default for GameConfig {
             tick_skip: 8, 
             spawn_opponents: true, 
             car_config: CarConfig::octane(),
+            game_mode: GameMode::Soccar,
+            blue_size: None,
+            orange_size: None,
+            car_configs: vec![],
+            shuffle_cars: false,
+            pad_opponent_obs: false,
         }
     }
 }
 
+/// Returns the name of a preset car config ("octane", "dominus", "plank", "breakout", "hybrid" or "merc"), 
+/// or None if the config is not one of the presets
+pub fn car_config_name(car_config: &'static CarConfig) -> Option<&'static str> {
+    CAR_CONFIG_PRESETS
+        .iter()
+        .find(|(_, preset)| std::ptr::eq(car_config, preset()))
+        .map(|(name, _)| *name)
+}
+
+/// Returns the preset car config with the given name, see `car_config_name`
+pub fn car_config_from_name(name: &str) -> Option<&'static CarConfig> {
+    CAR_CONFIG_PRESETS.iter().find(|(preset_name, _)| *preset_name == name).map(|(_, preset)| preset())
+}
+
+type CarConfigPreset = (&'static str, fn() -> &'static CarConfig);
+
+const CAR_CONFIG_PRESETS: [CarConfigPreset; 6] = [
+    ("octane", CarConfig::octane),
+    ("dominus", CarConfig::dominus),
+    ("plank", CarConfig::plank),
+    ("breakout", CarConfig::breakout),
+    ("hybrid", CarConfig::hybrid),
+    ("merc", CarConfig::merc),
+];
+
+#[cfg(feature = "serde")]
+mod car_config_serde {
+    use rocketsim_rs::sim::CarConfig;
+    use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(car_config: &&'static CarConfig, serializer: S) -> Result<S::Ok, S::Error> {
+        match super::car_config_name(car_config) {
+            Some(name) => serializer.serialize_str(name),
+            None => Err(S::Error::custom("only the preset car configs can be serialized")),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<&'static CarConfig, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        super::car_config_from_name(&name).ok_or_else(|| D::Error::custom(format!("unknown car config name: {name}")))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod car_configs_serde {
+    use rocketsim_rs::sim::CarConfig;
+    use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(car_configs: &[&'static CarConfig], serializer: S) -> Result<S::Ok, S::Error> {
+        let names = car_configs
+            .iter()
+            .map(|car_config| super::car_config_name(car_config).ok_or_else(|| S::Error::custom("only the preset car configs can be serialized")))
+            .collect::<Result<Vec<_>, _>>()?;
+        serializer.collect_seq(names)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<&'static CarConfig>, D::Error> {
+        let names = Vec::<String>::deserialize(deserializer)?;
+        names
+            .iter()
+            .map(|name| super::car_config_from_name(name).ok_or_else(|| D::Error::custom(format!("unknown car config name: {name}"))))
+            .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "GameMode")]
+enum GameModeDef {
+    Soccar,
+    Hoops,
+    Heatseeker,
+    Snowday,
+    TheVoid,
+}
+
+/// Per-player match stats, see the `Stats` of the sim wrapper for how shots and saves are defined
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Stats {
     pub goals: u16,
     pub own_goals: u16,
     pub assists: u16,
-    // pub saves: u16,
-    // pub shots: u16,
-    // pub demolitions: u16,
+    pub saves: u16,
+    pub shots: u16,
+    pub demolitions: u16,
 }
 
 impl GameMatch {
     pub fn new(
         config: MakeConfig,
     ) -> Self {
-        let num_agents = if config.game_config.spawn_opponents { config.game_config.team_size * 2 } else { config.game_config.team_size };
+        let num_agents = config.game_config.num_cars();
 
         // rocketsim start
-        let sim_wrapper = RocketsimWrapper::new(config.game_config);
+        let sim_wrapper = RocketsimWrapper::new(config.game_config.clone());
 
-        GameMatch {
+        let mut game_match = GameMatch {
             game_config: config.game_config,
             _reward_fn: config.reward_fn,
             _terminal_condition: config.terminal_condition,
             _obs_builder: config.obs_builder,
             _action_parser: config.action_parser,
             _state_setter: config.state_setter,
+            _state_modifiers: config.state_modifiers,
             agents: num_agents,
             observation_space: Vec::<usize>::new(),
             use_single_obs: config.use_single_obs,
@@ -104,47 +267,108 @@ impl GameMatch {
             _spectator_ids: vec![0; 6],
             _initial_score: 0,
             sim_wrapper,
-        }
+            ball_prediction_cache: None,
+            obs_buffers: Vec::new(),
+            shuffle_rng: SmallRng::from_entropy(),
+            tick_schedule: None,
+        };
+        game_match.auto_detect_spaces();
+
+        game_match
     }
 
     pub fn episode_reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
         self._spectator_ids = initial_state.players.iter().map(|x| x.car_id).collect();
-        self._prev_actions = vec![vec![0.; 8]; self.agents];
+        // zeroed in place so the buffers are kept between episodes
+        self._prev_actions.resize_with(self.agents, Vec::new);
+        for prev_act in &mut self._prev_actions {
+            prev_act.clear();
+            prev_act.resize(8, 0.);
+        }
         self._terminal_condition.reset(initial_state);
         self._reward_fn.reset(initial_state, reward_stage);
+        self._action_parser.reset(initial_state);
         if self.use_single_obs {
             self._obs_builder[0].reset(initial_state);
         } else {
             self._obs_builder.iter_mut().map(|func| func.reset(initial_state)).for_each(drop);
         }
         self._initial_score = initial_state.blue_score - initial_state.orange_score;
+        self.ball_prediction_cache = None;
     }
 
+    /// Builds the obs of every player. With the `parallel_obs` feature, `use_single_obs` and an obs builder that supports it 
+    /// (see `ObsBuilder::as_parallel`) the players are built in parallel on the rayon thread pool.
     pub fn build_observations(&mut self, state: &GameState) -> Vec<Vec<f32>> {
-        if !self.use_single_obs {
-            let obs_build_len = self._obs_builder.len();
-            let player_len = state.players.len();
-            assert!(obs_build_len >= player_len, "not enough observation builders (len: {obs_build_len}) were provided for the amount of players (len: {player_len})");
-        }
+        self.prepare_obs_builders(state);
 
+        #[cfg(feature = "parallel_obs")]
         if self.use_single_obs {
-            self._obs_builder[0].pre_step(state, &self.game_config);
+            if let Some(obs_builder) = self._obs_builder[0].as_parallel() {
+                return par_build_observations(obs_builder, state, &self.game_config, &self._prev_actions);
+            }
+        }
 
+        if self.use_single_obs {
             state.players
             .iter()
-            .map(|player| self._obs_builder[0].build_obs(player, state, &self.game_config))
+            .zip(&self._prev_actions)
+            .map(|(player, prev_act)| self._obs_builder[0].build_obs(player, state, &self.game_config, prev_act))
             .collect()
         } else {
-            self._obs_builder.iter_mut().map(|func| func.pre_step(state, &self.game_config)).for_each(drop);
-
             state.players
             .iter()
+            .zip(&self._prev_actions)
             .zip(&mut self._obs_builder)
-            .map(|(player, func)| func.build_obs(player, state, &self.game_config))
+            .map(|((player, prev_act), func)| func.build_obs(player, state, &self.game_config, prev_act))
             .collect()
         }
     }
 
+    /// Same as `build_observations` but the observations are upcast to f64, the obs builders still work in f32
+    pub fn build_observations_f64(&mut self, state: &GameState) -> Vec<Vec<f64>> {
+        obs_to_f64(self.build_observations_into(state))
+    }
+
+    /// Same as `build_observations` but the observations are built into buffers that are kept between calls (see `ObsBuilder::build_obs_into`), 
+    /// which avoids allocating every obs on every step
+    pub fn build_observations_into(&mut self, state: &GameState) -> &[Vec<f32>] {
+        self.prepare_obs_builders(state);
+
+        self.obs_buffers.resize_with(state.players.len(), Vec::new);
+        for (i, ((player, prev_act), buf)) in state.players.iter().zip(&self._prev_actions).zip(&mut self.obs_buffers).enumerate() {
+            let func = if self.use_single_obs { &mut self._obs_builder[0] } else { &mut self._obs_builder[i] };
+            func.build_obs_into(buf, player, state, &self.game_config, prev_act);
+        }
+
+        &self.obs_buffers
+    }
+
+    /// checks the builder and action counts and gives the obs builders the ball prediction and the pre step
+    fn prepare_obs_builders(&mut self, state: &GameState) {
+        let player_len = state.players.len();
+        if !self.use_single_obs {
+            let obs_build_len = self._obs_builder.len();
+            assert!(obs_build_len >= player_len, "not enough observation builders (len: {obs_build_len}) were provided for the amount of players (len: {player_len})");
+        }
+        let prev_acts_len = self._prev_actions.len();
+        assert!(prev_acts_len == player_len, "previous actions (len: {prev_acts_len}) did not match the amount of players (len: {player_len})");
+
+        let num_builders = if self.use_single_obs { 1 } else { self._obs_builder.len() };
+        for i in 0..num_builders {
+            if let Some((num_steps, dt)) = self._obs_builder[i].ball_prediction_request() {
+                let prediction = self.predict_ball(state.tick_num, num_steps, dt);
+                self._obs_builder[i].set_ball_prediction(&prediction);
+            }
+        }
+
+        if self.use_single_obs {
+            self._obs_builder[0].pre_step(state, &self.game_config);
+        } else {
+            self._obs_builder.iter_mut().map(|func| func.pre_step(state, &self.game_config)).for_each(drop);
+        }
+    }
+
     pub fn get_rewards(&mut self, state: &GameState, done: bool) -> Vec<f32> {
         let mut rewards = Vec::<f32>::with_capacity(self.agents);
 
@@ -166,6 +390,11 @@ impl GameMatch {
         rewards
     }
 
+    /// drains the stats logged by the reward function, see `RewardFn::drain_stats`
+    pub fn drain_reward_stats(&mut self) -> HashMap<String, f32> {
+        self._reward_fn.drain_stats()
+    }
+
     pub fn is_done(&mut self, state: &GameState) -> bool {
         self._terminal_condition.is_terminal(state)
     }
@@ -180,47 +409,141 @@ impl GameMatch {
     }
 
     pub fn get_state(&mut self) -> GameState {
-        self.sim_wrapper.get_rlgym_gamestate(false).0
+        let mut state = self.sim_wrapper.get_rlgym_gamestate(false).0;
+        self.apply_state_modifiers(&mut state);
+        state
+    }
+
+    /// Returns the ball prediction (see `RocketsimWrapper::predict_ball`) for the sim state at tick. 
+    /// 
+    /// The sim is at the tick of the last state it gave (a step's state is taken after its last tick), so tick should be that state's `tick_num`.
+    /// The last prediction is cached so multiple obs builders asking for the same prediction on the same step only run it once.
+    /// Note that the prediction is made from the sim so it does not include any changes made by the state modifiers.
+    pub fn predict_ball(&mut self, tick: u64, num_steps: usize, dt: f32) -> Vec<BallState> {
+        if let Some((cached_tick, cached_steps, cached_dt, prediction)) = &self.ball_prediction_cache {
+            if *cached_tick == tick && *cached_dt == dt && *cached_steps >= num_steps {
+                return prediction[..num_steps].to_vec();
+            }
+        }
+        let prediction = self.sim_wrapper.predict_ball(num_steps, dt);
+        self.ball_prediction_cache = Some((tick, num_steps, dt, prediction.clone()));
+        prediction
     }
 
+    /// applies the state modifiers in order, this is done on every state that comes from the sim before anything else uses it
+    pub fn apply_state_modifiers(&mut self, state: &mut GameState) {
+        for modifier in self._state_modifiers.iter_mut() {
+            modifier.modify_state(state);
+        }
+    }
+
+    /// Parses the actions with the action parser, panics if the parsed actions don't match the players (see `try_parse_actions`)
     pub fn parse_actions(&mut self, actions: Vec<Vec<f32>>, state: &GameState) -> Vec<Vec<f32>> {
-        let parsed_actions = self._action_parser.parse_actions(actions, state);
-        let acts_len = parsed_actions.len();
+        self.try_parse_actions(actions, state).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Parses the actions with the action parser and returns an error instead of panicking if 
+    /// there isn't one action of length 8 per player. Panics from within the action parser itself are not caught.
+    pub fn try_parse_actions(&mut self, actions: Vec<Vec<f32>>, state: &GameState) -> Result<Vec<Vec<f32>>, GymError> {
         let players_len = state.players.len();
-        assert!(acts_len == players_len, "parsed actions was not the same length (len: {acts_len}) as player count (len: {players_len})");
-        self._prev_actions = parsed_actions.to_vec();
-        parsed_actions
+        if actions.len() != players_len {
+            return Err(GymError::ActionCountMismatch { actions: actions.len(), players: players_len });
+        }
+        let parsed_actions = self._action_parser.parse_actions(actions, state);
+        check_parsed_actions(&parsed_actions, players_len)?;
+        self.tick_schedule = self._action_parser.tick_schedule(&parsed_actions, self.game_config.tick_skip);
+        if let Some(tick_schedule) = &self.tick_schedule {
+            for tick_actions in tick_schedule {
+                check_parsed_actions(tick_actions, players_len)?;
+            }
+        }
+        self._prev_actions.clone_from(&parsed_actions);
+        Ok(parsed_actions)
+    }
+
+    /// Steps the sim with the parsed actions, or with the per tick actions if the action parser gave a tick schedule for them
+    pub fn step_sim(&mut self, actions: Vec<Vec<f32>>, get_sim_state: bool) -> (GameState, Option<Vec<SimGameState>>) {
+        match self.tick_schedule.take() {
+            Some(tick_schedule) => self.sim_wrapper.step_scheduled(tick_schedule, get_sim_state),
+            None => self.sim_wrapper.step(actions, get_sim_state),
+        }
+    }
+
+    /// Draws a new car for each agent if `GameConfig.shuffle_cars` is set, should be done before the reset state is set in the sim
+    pub fn shuffle_agents(&mut self) {
+        if self.game_config.shuffle_cars {
+            let mut agent_order: Vec<usize> = (0..self.agents).collect();
+            agent_order.shuffle(&mut self.shuffle_rng);
+            self.sim_wrapper.set_agent_order(agent_order);
+        }
     }
 
     pub fn get_reset_state(&mut self, state: &GameState) -> StateWrapper {
-        let mut new_state = self._state_setter.build_wrapper(self.game_config.team_size, self.game_config.spawn_opponents, Some(state));
+        let mut new_state = self._state_setter.build_wrapper(self.game_config.blue_count(), self.game_config.orange_count(), Some(state));
         self._state_setter.reset(&mut new_state);
         new_state
     }
 
+    /// Seeds the state setter with `seed` and the car shuffling, action parser, reward function and obs builders with sub-seeds derived from it
     pub fn set_seeds(&mut self, seed: u64) {
         self._state_setter.set_seed(seed);
+        self.shuffle_rng = SmallRng::seed_from_u64(sub_seed(seed, 0));
+        self._action_parser.set_seed(sub_seed(seed, 1));
+        self._reward_fn.set_seed(sub_seed(seed, 2));
+        for (i, obs_builder) in self._obs_builder.iter_mut().enumerate() {
+            obs_builder.set_seed(sub_seed(seed, 3 + i as u64));
+        }
+    }
+
+    /// returns the stats of each player, in the same order as the players of the `GameState`
+    pub fn get_stats(&self) -> Vec<Stats> {
+        self.sim_wrapper
+            .get_stats()
+            .into_iter()
+            .map(|(_, stats)| Stats {
+                goals: stats.goals as u16,
+                own_goals: stats.own_goals as u16,
+                assists: stats.assists as u16,
+                saves: stats.saves as u16,
+                shots: stats.shots as u16,
+                demolitions: stats.demolitions as u16,
+            })
+            .collect()
     }
 
     pub fn get_config(&self) -> GameConfig {
-        self.game_config
+        self.game_config.clone()
     }
 
     pub fn update_settings(&mut self, new_config: GameConfig, new_obs_builder: Option<Vec<Box<dyn ObsBuilder>>>) -> GameState {
         // TODO: do extra modes and more mutators
-        self.game_config = new_config;
-        let car_count = if new_config.spawn_opponents {
-            new_config.team_size * 2
-        } else {
-            new_config.team_size
-        };
+        self.game_config = new_config.clone();
+        let car_count = new_config.num_cars();
         self.agents = car_count;
-        if let Some(val) = new_obs_builder { self._obs_builder = val }
-        self.sim_wrapper.set_game_config(new_config, false).0
+        self._prev_actions = vec![vec![0.; 8]; car_count];
+        if let Some(val) = new_obs_builder {
+            self._obs_builder = val;
+            self.auto_detect_spaces();
+        }
+        // the cached prediction is keyed by tick and the sim restarts from a kickoff
+        self.ball_prediction_cache = None;
+        let mut state = self.sim_wrapper.set_game_config(new_config, false).0;
+        self.apply_state_modifiers(&mut state);
+        state
     }
 
-    fn _auto_detech_obs_space(&mut self) {
+    /// Sets the gravity and/or boost consumption of the game config and the running sim without resetting anything, 
+    /// values that are None are kept
+    pub fn set_mutators(&mut self, gravity: Option<f32>, boost_consumption: Option<f32>) {
+        self.game_config.gravity = gravity.unwrap_or(self.game_config.gravity);
+        self.game_config.boost_consumption = boost_consumption.unwrap_or(self.game_config.boost_consumption);
+        self.sim_wrapper.set_mutators(self.game_config.gravity, self.game_config.boost_consumption);
+    }
+
+    /// sets the observation space from the first obs builder and the action space from the action parser
+    fn auto_detect_spaces(&mut self) {
         self.observation_space = self._obs_builder[0].get_obs_space();
+        self.action_space = self._action_parser.get_action_space();
     }
 }
 
@@ -267,3 +590,117 @@ impl GameMatch {
 //     // }
 //     return rewards
 // }
+
+/// splitmix64 of the seed offset by the stream so that each component gets an unrelated (but deterministic) seed
+pub(crate) fn sub_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_mul(0x9E3779B97F4A7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// checks that there is one parsed action of length 8 for each player
+fn check_parsed_actions(parsed_actions: &[Vec<f32>], players_len: usize) -> Result<(), GymError> {
+    if parsed_actions.len() != players_len {
+        return Err(GymError::ActionCountMismatch { actions: parsed_actions.len(), players: players_len });
+    }
+    if let Some((index, action)) = parsed_actions.iter().enumerate().find(|(_, action)| action.len() != 8) {
+        return Err(GymError::ActionLengthMismatch { index, len: action.len(), expected: 8 });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsed_action_mismatches() {
+        assert_eq!(check_parsed_actions(&[vec![0.; 8], vec![0.; 8]], 2), Ok(()));
+        assert_eq!(
+            check_parsed_actions(&[vec![0.; 8]], 2),
+            Err(GymError::ActionCountMismatch { actions: 1, players: 2 })
+        );
+        assert_eq!(
+            check_parsed_actions(&[vec![0.; 8], vec![0.; 5]], 2),
+            Err(GymError::ActionLengthMismatch { index: 1, len: 5, expected: 8 })
+        );
+    }
+
+    #[test]
+    fn sub_seeds_are_deterministic_and_distinct() {
+        assert_eq!(sub_seed(42, 1), sub_seed(42, 1));
+        assert_ne!(sub_seed(42, 1), sub_seed(42, 2));
+        assert_ne!(sub_seed(42, 1), sub_seed(43, 1));
+        // nearby seeds don't share streams (eg. the envs of a VecGym)
+        assert_ne!(sub_seed(42, 1), sub_seed(43, 0));
+    }
+
+    #[test]
+    fn team_counts() {
+        let mut config = GameConfig { team_size: 2, ..Default::default() };
+        assert_eq!((config.blue_count(), config.orange_count(), config.num_cars()), (2, 2, 4));
+        config.spawn_opponents = false;
+        assert_eq!((config.blue_count(), config.orange_count(), config.num_cars()), (2, 0, 2));
+        config.blue_size = Some(1);
+        config.orange_size = Some(2);
+        assert_eq!((config.blue_count(), config.orange_count(), config.num_cars()), (1, 2, 3));
+    }
+
+    #[test]
+    fn car_config_per_car() {
+        let config = GameConfig { car_configs: vec![CarConfig::dominus(), CarConfig::plank()], ..Default::default() };
+        assert!(std::ptr::eq(config.car_config_for(0), CarConfig::dominus()));
+        assert!(std::ptr::eq(config.car_config_for(1), CarConfig::plank()));
+        // falls back to the single config
+        assert!(std::ptr::eq(config.car_config_for(2), CarConfig::octane()));
+    }
+
+    #[test]
+    fn car_config_names() {
+        assert_eq!(car_config_name(CarConfig::dominus()), Some("dominus"));
+        assert!(std::ptr::eq(car_config_from_name("merc").unwrap(), CarConfig::merc()));
+        assert!(car_config_from_name("batmobile").is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn game_config_json_round_trip() {
+        let config = GameConfig {
+            gravity: 0.5,
+            boost_consumption: 2.,
+            team_size: 3,
+            tick_skip: 4,
+            spawn_opponents: false,
+            car_config: CarConfig::breakout(),
+            game_mode: GameMode::Heatseeker,
+            blue_size: None,
+            orange_size: None,
+            car_configs: vec![CarConfig::dominus()],
+            shuffle_cars: false,
+            pad_opponent_obs: false,
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"car_config\":\"breakout\""), "car config was not serialized by name: {json}");
+
+        let decoded: GameConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.gravity, 0.5);
+        assert_eq!(decoded.boost_consumption, 2.);
+        assert_eq!(decoded.team_size, 3);
+        assert_eq!(decoded.tick_skip, 4);
+        assert!(!decoded.spawn_opponents);
+        assert!(std::ptr::eq(decoded.car_config, CarConfig::breakout()));
+        assert_eq!(decoded.game_mode, GameMode::Heatseeker);
+        assert_eq!(decoded.car_configs.len(), 1);
+        assert!(std::ptr::eq(decoded.car_configs[0], CarConfig::dominus()));
+
+        // configs serialized before the newer fields existed still load
+        let mut value = serde_json::to_value(&config).unwrap();
+        for field in ["car_configs", "shuffle_cars", "pad_opponent_obs"] {
+            value.as_object_mut().unwrap().remove(field);
+        }
+        let decoded: GameConfig = serde_json::from_value(value).unwrap();
+        assert!(decoded.car_configs.is_empty());
+        assert_eq!(decoded.team_size, 3);
+    }
+}
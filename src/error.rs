@@ -0,0 +1,42 @@
+//! Errors returned by the non-panicking (`try_`) variants of the gym functions
+
+use std::{error::Error, fmt};
+
+/// Error for problems with the inputs given to the gym, eg. from `Gym::try_step`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GymError {
+    /// the number of actions (after parsing) did not match the number of players
+    ActionCountMismatch { actions: usize, players: usize },
+    /// a parsed action did not have the length that the sim expects
+    ActionLengthMismatch { index: usize, len: usize, expected: usize },
+    /// there was not one obs builder per agent while `use_single_obs` is false
+    ObsBuilderCountMismatch { obs_builders: usize, agents: usize },
+    /// no built-in component of this kind (eg. "obs builder") has the name, see the `registry` module
+    UnknownComponent { kind: &'static str, name: String },
+    /// a parameter given to a component in the `registry` module was missing, unknown or had the wrong type
+    InvalidParam { component: String, param: String, reason: String },
+}
+
+impl fmt::Display for GymError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GymError::ActionCountMismatch { actions, players } => {
+                write!(f, "parsed actions was not the same length (len: {actions}) as player count (len: {players})")
+            }
+            GymError::ActionLengthMismatch { index, len, expected } => {
+                write!(f, "parsed action {index} was not of length {expected} (len: {len})")
+            }
+            GymError::ObsBuilderCountMismatch { obs_builders, agents } => {
+                write!(f, "number of obs builders (len: {obs_builders}) did not match the number of agents (len: {agents}) without use_single_obs")
+            }
+            GymError::UnknownComponent { kind, name } => {
+                write!(f, "unknown {kind}: \"{name}\"")
+            }
+            GymError::InvalidParam { component, param, reason } => {
+                write!(f, "invalid param \"{param}\" for \"{component}\": {reason}")
+            }
+        }
+    }
+}
+
+impl Error for GymError {}
@@ -2,30 +2,64 @@
 
 // use rayon::prelude::*;
 
-use rocketsim_rs::{sim::{BallHitInfo, CarControls}, BoostPad};
+use rocketsim_rs::{math::Vec3, sim::{BallHitInfo, BoostPadState, CarControls}, BoostPad};
+#[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
 
 use crate::common_values::BLUE_TEAM;
 use crate::gamestates::physics_object::PhysicsObject;
 use crate::gamestates::player_data::PlayerData;
 
-use super::physics_object::{Position, Velocity};
+use super::physics_object::{EulerAngle, Position, Quaternion, RotationMatrix, Velocity};
+use crate::IntoArray;
+
+/// version of the layout used by `GameState::to_vec`, bump this whenever the layout changes
+pub const STATE_VEC_VERSION: f32 = 4.;
+/// version, total length, player count
+const STATE_VEC_HEADER_LEN: usize = 3;
+/// game type, blue score, orange score, last touch, tick num, last touch car id, last touch location (3), tick count, seconds elapsed
+const STATE_VEC_INFO_LEN: usize = 11;
+/// position (3), quaternion (4, w first), linear velocity (3), angular velocity (3), euler angles (3, pitch/yaw/roll),
+/// rotation matrix (9, row major), has computed rot mtx, has computed euler angles
+const PHYSICS_VEC_LEN: usize = 27;
+/// is big, position (3), is active, cooldown
+const PAD_VEC_LEN: usize = 6;
+/// car id, team num, goals, saves, shots, demolishes, boost pickups, is demoed, last bumped by, last bumpee, bumps, been bumped, 
+/// on ground, ball touched, has jump, has flip, boost amount, last ball touch tick, last actions (8), hitbox size (3), car data, inverted car data
+const PLAYER_VEC_LEN: usize = 29 + 2 * PHYSICS_VEC_LEN;
 
 /// Struct that holds the current state of the game using objects like PhysicsObject and PlayerData
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GameState {
     pub game_type: i32,
     pub blue_score: i32,
     pub orange_score: i32,
     pub last_touch: i32,
+    /// id of the car that touched the ball last (None if no car has touched it since the state was set), 
+    /// `PlayerData.ball_touched` of that player tells if the touch happened this step
+    pub last_touch_car_id: Option<i32>,
+    /// contact point of the last touch (zeros if there was none)
+    pub last_touch_location: [f32; 3],
+    /// (demolisher, victim) car ids of the demos that happened since the previous state, see also `PlayerData.is_demoed`
+    pub demo_events: Vec<(i32, i32)>,
     pub players: Vec<PlayerData>,
     pub ball: PhysicsObject,
     pub inverted_ball: PhysicsObject,
-    #[serde(with = "serde_arrays")]
+    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
     pub boost_pads: [BoostPad; 34],
-    #[serde(with = "serde_arrays")]
+    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
     pub inverted_boost_pads: [BoostPad; 34],
+    /// seconds until each boost pad is active again (0 if it is active), in the same order as `boost_pads`
+    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
+    pub boost_pads_timers: [f32; 34],
+    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
+    pub inverted_boost_pads_timers: [f32; 34],
     pub tick_num: u64,
+    /// physics ticks since the episode was reset (the state was set), advances by `tick_skip` every step (the state of a step is taken after its last tick)
+    pub tick_count: u64,
+    /// `tick_count` in seconds of game time
+    pub seconds_elapsed: f32,
 }
 
 // const BOOST_PAD_LENGTH: usize = 34;
@@ -34,6 +68,27 @@ pub struct GameState {
 // const PLAYER_TERTIARY_INFO_LENGTH: usize = 11;
 // const PLAYER_INFO_LENGTH: usize = 2 + 2 * PLAYER_CAR_STATE_LENGTH + PLAYER_TERTIARY_INFO_LENGTH;
 
+impl Clone for GameState {
+    fn clone(&self) -> Self {
+        GameState {
+            demo_events: self.demo_events.clone(),
+            players: self.players.clone(),
+            ..*self
+        }
+    }
+
+    /// reuses the allocations of the players and demo events (the gym keeps its previous state this way)
+    fn clone_from(&mut self, source: &Self) {
+        self.demo_events.clone_from(&source.demo_events);
+        self.players.clone_from(&source.players);
+        *self = GameState {
+            demo_events: std::mem::take(&mut self.demo_events),
+            players: std::mem::take(&mut self.players),
+            ..*source
+        };
+    }
+}
+
 impl Default for GameState {
     fn default() -> Self {
         GameState {
@@ -41,12 +96,19 @@ impl Default for GameState {
             blue_score: 0,
             orange_score: 0,
             last_touch: 0,
+            last_touch_car_id: None,
+            last_touch_location: [0.; 3],
+            demo_events: Vec::new(),
             players: Vec::new(),
             ball: PhysicsObject::default(),
             inverted_ball: PhysicsObject::default(),
             boost_pads: [BoostPad::default(); 34],
             inverted_boost_pads: [BoostPad::default(); 34],
+            boost_pads_timers: [0.; 34],
+            inverted_boost_pads_timers: [0.; 34],
             tick_num: 0,
+            tick_count: 0,
+            seconds_elapsed: 0.,
         }
     }
 }
@@ -56,6 +118,199 @@ impl GameState {
         GameState::default()
     }
 
+    /// Serializes the state into a flat vec with a stable layout:
+    /// 
+    /// - header: version (`STATE_VEC_VERSION`), total length of the vec, player count
+    /// - game type, blue score, orange score, last touch, tick num, last touch car id (-1 if None), last touch location (3), tick count, seconds elapsed
+    /// - ball and inverted ball (physics layout, see below)
+    /// - 34 boost pads: is big, position (3), is active, cooldown
+    /// - each player: car id, team num, goals, saves, shots, demolishes, boost pickups, is demoed, last bumped by, last bumpee, bumps, been bumped,
+    ///   on ground, ball touched, has jump, has flip, boost amount, last ball touch tick, last actions (8, same order as the action parsers), hitbox size (3), 
+    ///   car data, inverted car data
+    /// 
+    /// Physics layout: position (3), quaternion (4, w first), linear velocity (3), angular velocity (3), euler angles (3, pitch/yaw/roll), 
+    /// rotation matrix (9, row major), has computed rot mtx, has computed euler angles.
+    /// 
+    /// Bools are 0/1. Integers are stored as f32 so they are only exact up to 2^24 (eg. about 39 hours of ticks).
+    /// The ball hit info of the players and the demo events are not included, the inverted pads and pad timers are rebuilt from the pads.
+    pub fn to_vec(&self) -> Vec<f32> {
+        let total_len = STATE_VEC_HEADER_LEN + STATE_VEC_INFO_LEN + 2 * PHYSICS_VEC_LEN + PAD_VEC_LEN * self.boost_pads.len() + PLAYER_VEC_LEN * self.players.len();
+        let mut vec = Vec::<f32>::with_capacity(total_len);
+
+        vec.extend([STATE_VEC_VERSION, total_len as f32, self.players.len() as f32]);
+        vec.extend([self.game_type as f32, self.blue_score as f32, self.orange_score as f32, self.last_touch as f32, self.tick_num as f32]);
+        vec.push(self.last_touch_car_id.unwrap_or(-1) as f32);
+        vec.extend(self.last_touch_location);
+        vec.extend([self.tick_count as f32, self.seconds_elapsed]);
+        encode_physics(&self.ball, &mut vec);
+        encode_physics(&self.inverted_ball, &mut vec);
+        for pad in &self.boost_pads {
+            vec.extend([pad.is_big as u8 as f32, pad.position.x, pad.position.y, pad.position.z, pad.state.is_active as u8 as f32, pad.state.cooldown]);
+        }
+        for player in &self.players {
+            vec.extend([
+                player.car_id as f32,
+                player.team_num as f32,
+                player.match_goals as f32,
+                player.match_saves as f32,
+                player.match_shots as f32,
+                player.match_demolishes as f32,
+                player.boost_pickups as f32,
+                player.is_demoed as u8 as f32,
+                player.last_bumped_by as f32,
+                player.last_bumpee as f32,
+                player.bumps as f32,
+                player.been_bumped as f32,
+                player.on_ground as u8 as f32,
+                player.ball_touched as u8 as f32,
+                player.has_jump as u8 as f32,
+                player.has_flip as u8 as f32,
+                player.boost_amount,
+                player.last_ball_touch_tick as f32,
+            ]);
+            vec.extend(player.last_actions.into_array());
+            vec.extend(player.hitbox_size);
+            encode_physics(&player.car_data, &mut vec);
+            encode_physics(&player.inverted_car_data, &mut vec);
+        }
+
+        vec
+    }
+
+    /// Compares this state to another and returns a description of every field that differs (eg. for finding where two sims desync).
+    /// 
+    /// Floats (and vectors of floats, compared as a whole) differ if any component differs by more than `tolerance`, everything else must be equal.
+    /// The scores, last touch, ball, boost pads and players (matched by index) are compared, 
+    /// the inverted data is skipped since it is derived from the rest.
+    pub fn diff(&self, other: &GameState, tolerance: f32) -> Vec<String> {
+        let mut diffs = Vec::new();
+        diff_eq("blue_score", self.blue_score, other.blue_score, &mut diffs);
+        diff_eq("orange_score", self.orange_score, other.orange_score, &mut diffs);
+        diff_eq("last_touch", self.last_touch, other.last_touch, &mut diffs);
+        diff_eq("last_touch_car_id", self.last_touch_car_id, other.last_touch_car_id, &mut diffs);
+        diff_vals("last_touch_location", &self.last_touch_location, &other.last_touch_location, tolerance, &mut diffs);
+        diff_eq("demo_events", &self.demo_events, &other.demo_events, &mut diffs);
+        diff_physics("ball", &self.ball, &other.ball, tolerance, &mut diffs);
+        for (i, (pad, other_pad)) in self.boost_pads.iter().zip(other.boost_pads.iter()).enumerate() {
+            diff_eq(&format!("boost_pads[{i}].is_active"), pad.state.is_active, other_pad.state.is_active, &mut diffs);
+        }
+        diff_vals("boost_pads_timers", &self.boost_pads_timers, &other.boost_pads_timers, tolerance, &mut diffs);
+
+        diff_eq("players.len()", self.players.len(), other.players.len(), &mut diffs);
+        for (i, (player, other_player)) in self.players.iter().zip(other.players.iter()).enumerate() {
+            let name = format!("players[{i}]");
+            diff_eq(&format!("{name}.car_id"), player.car_id, other_player.car_id, &mut diffs);
+            diff_eq(&format!("{name}.team_num"), player.team_num, other_player.team_num, &mut diffs);
+            diff_eq(&format!("{name}.match_goals"), player.match_goals, other_player.match_goals, &mut diffs);
+            diff_eq(&format!("{name}.is_demoed"), player.is_demoed, other_player.is_demoed, &mut diffs);
+            diff_eq(&format!("{name}.on_ground"), player.on_ground, other_player.on_ground, &mut diffs);
+            diff_eq(&format!("{name}.ball_touched"), player.ball_touched, other_player.ball_touched, &mut diffs);
+            diff_eq(&format!("{name}.has_jump"), player.has_jump, other_player.has_jump, &mut diffs);
+            diff_eq(&format!("{name}.has_flip"), player.has_flip, other_player.has_flip, &mut diffs);
+            diff_vals(&format!("{name}.boost_amount"), &[player.boost_amount], &[other_player.boost_amount], tolerance, &mut diffs);
+            diff_physics(&format!("{name}.car_data"), &player.car_data, &other_player.car_data, tolerance, &mut diffs);
+        }
+
+        diffs
+    }
+
+    /// Rebuilds a state from a vec made by `to_vec`, panics if the version or the length do not match
+    pub fn from_vec(vec: &[f32]) -> GameState {
+        assert!(vec.len() >= STATE_VEC_HEADER_LEN, "state vec was too short to contain the header (len: {})", vec.len());
+        let version = vec[0];
+        assert!(version == STATE_VEC_VERSION, "state vec version ({version}) did not match the current version ({STATE_VEC_VERSION})");
+        let total_len = vec[1] as usize;
+        let vec_len = vec.len();
+        assert!(total_len == vec_len, "state vec length (len: {vec_len}) did not match the length in its header (len: {total_len})");
+        let num_players = vec[2] as usize;
+
+        let mut start = STATE_VEC_HEADER_LEN;
+        let info = &vec[start..start + STATE_VEC_INFO_LEN];
+        start += STATE_VEC_INFO_LEN;
+        let ball = decode_physics(&vec[start..start + PHYSICS_VEC_LEN]);
+        start += PHYSICS_VEC_LEN;
+        let inverted_ball = decode_physics(&vec[start..start + PHYSICS_VEC_LEN]);
+        start += PHYSICS_VEC_LEN;
+
+        let mut boost_pads = [BoostPad::default(); 34];
+        let mut boost_pads_timers = [0.; 34];
+        for (pad, timer) in boost_pads.iter_mut().zip(&mut boost_pads_timers) {
+            let pad_vals = &vec[start..start + PAD_VEC_LEN];
+            *pad = BoostPad {
+                is_big: pad_vals[0] > 0.,
+                position: Vec3::new(pad_vals[1], pad_vals[2], pad_vals[3]),
+                state: BoostPadState { is_active: pad_vals[4] > 0., cooldown: pad_vals[5], ..Default::default() },
+            };
+            *timer = if pad.state.is_active { 0. } else { pad.state.cooldown };
+            start += PAD_VEC_LEN;
+        }
+        let mut inverted_boost_pads = boost_pads;
+        inverted_boost_pads.reverse();
+        let mut inverted_boost_pads_timers = boost_pads_timers;
+        inverted_boost_pads_timers.reverse();
+
+        let mut players = Vec::with_capacity(num_players);
+        for _ in 0..num_players {
+            let player_vals = &vec[start..start + PLAYER_VEC_LEN];
+            let actions = &player_vals[18..26];
+            players.push(PlayerData {
+                car_id: player_vals[0] as i32,
+                team_num: player_vals[1] as i32,
+                match_goals: player_vals[2] as i64,
+                match_saves: player_vals[3] as i64,
+                match_shots: player_vals[4] as i64,
+                match_demolishes: player_vals[5] as i64,
+                boost_pickups: player_vals[6] as i64,
+                is_demoed: player_vals[7] > 0.,
+                last_bumped_by: player_vals[8] as u32,
+                last_bumpee: player_vals[9] as u32,
+                bumps: player_vals[10] as u32,
+                been_bumped: player_vals[11] as u32,
+                on_ground: player_vals[12] > 0.,
+                ball_touched: player_vals[13] > 0.,
+                ball_info: BallHitInfo::default(),
+                has_jump: player_vals[14] > 0.,
+                has_flip: player_vals[15] > 0.,
+                boost_amount: player_vals[16],
+                last_ball_touch_tick: player_vals[17] as u64,
+                last_actions: CarControls {
+                    throttle: actions[0],
+                    steer: actions[1],
+                    pitch: actions[2],
+                    yaw: actions[3],
+                    roll: actions[4],
+                    jump: actions[5] > 0.,
+                    boost: actions[6] > 0.,
+                    handbrake: actions[7] > 0.,
+                },
+                hitbox_size: [player_vals[26], player_vals[27], player_vals[28]],
+                car_data: decode_physics(&player_vals[29..29 + PHYSICS_VEC_LEN]),
+                inverted_car_data: decode_physics(&player_vals[29 + PHYSICS_VEC_LEN..]),
+            });
+            start += PLAYER_VEC_LEN;
+        }
+
+        GameState {
+            game_type: info[0] as i32,
+            blue_score: info[1] as i32,
+            orange_score: info[2] as i32,
+            last_touch: info[3] as i32,
+            last_touch_car_id: if info[5] >= 0. { Some(info[5] as i32) } else { None },
+            last_touch_location: [info[6], info[7], info[8]],
+            demo_events: Vec::new(),
+            players,
+            ball,
+            inverted_ball,
+            boost_pads,
+            inverted_boost_pads,
+            boost_pads_timers,
+            inverted_boost_pads_timers,
+            tick_num: info[4] as u64,
+            tick_count: info[9] as u64,
+            seconds_elapsed: info[10],
+        }
+    }
+
     // pub fn decode(&mut self, state_vals: Vec<f32>) {
     //     let mut start = 3;
     //     let num_ball_packets = 1;
@@ -155,6 +410,52 @@ impl GameState {
     // }
 }
 
+fn diff_vals(name: &str, a: &[f32], b: &[f32], tolerance: f32, diffs: &mut Vec<String>) {
+    if std::iter::zip(a, b).any(|(x, y)| (x - y).abs() > tolerance) {
+        diffs.push(format!("{name}: {a:?} != {b:?}"));
+    }
+}
+
+fn diff_eq<T: PartialEq + std::fmt::Debug>(name: &str, a: T, b: T, diffs: &mut Vec<String>) {
+    if a != b {
+        diffs.push(format!("{name}: {a:?} != {b:?}"));
+    }
+}
+
+fn diff_physics(name: &str, a: &PhysicsObject, b: &PhysicsObject, tolerance: f32, diffs: &mut Vec<String>) {
+    diff_vals(&format!("{name}.position"), &a.position.into_array(), &b.position.into_array(), tolerance, diffs);
+    diff_vals(&format!("{name}.quaternion"), &a.quaternion.into_array(), &b.quaternion.into_array(), tolerance, diffs);
+    diff_vals(&format!("{name}.linear_velocity"), &a.linear_velocity.into_array(), &b.linear_velocity.into_array(), tolerance, diffs);
+    diff_vals(&format!("{name}.angular_velocity"), &a.angular_velocity.into_array(), &b.angular_velocity.into_array(), tolerance, diffs);
+}
+
+fn encode_physics(phys: &PhysicsObject, vec: &mut Vec<f32>) {
+    vec.extend(phys.position.into_array());
+    vec.extend(phys.quaternion.into_array());
+    vec.extend(phys.linear_velocity.into_array());
+    vec.extend(phys.angular_velocity.into_array());
+    vec.extend(phys.euler_angles.into_array());
+    vec.extend(phys.rotation_mtx.into_flat_array());
+    vec.extend([phys.has_computed_rot_mtx as u8 as f32, phys.has_computed_euler_angles as u8 as f32]);
+}
+
+fn decode_physics(vals: &[f32]) -> PhysicsObject {
+    let mut rotation_mtx = RotationMatrix::zeros();
+    for (i, row) in rotation_mtx.array.iter_mut().enumerate() {
+        row.copy_from_slice(&vals[16 + i * 3..19 + i * 3]);
+    }
+    PhysicsObject {
+        position: Position { x: vals[0], y: vals[1], z: vals[2] },
+        quaternion: Quaternion { w: vals[3], x: vals[4], y: vals[5], z: vals[6] },
+        linear_velocity: Velocity { x: vals[7], y: vals[8], z: vals[9] },
+        angular_velocity: Velocity { x: vals[10], y: vals[11], z: vals[12] },
+        euler_angles: EulerAngle { pitch: vals[13], yaw: vals[14], roll: vals[15] },
+        rotation_mtx,
+        has_computed_rot_mtx: vals[25] > 0.,
+        has_computed_euler_angles: vals[26] > 0.,
+    }
+}
+
 // #[derive(Clone)]
 // pub struct FakeGameState {
 //     pub game_type: i32,
@@ -187,6 +488,9 @@ impl GameState {
             blue_score: 0,
             orange_score: 0,
             last_touch: 0,
+            last_touch_car_id: None,
+            last_touch_location: [0.; 3],
+            demo_events: Vec::new(),
             players: vec![
                 PlayerData {
                     car_id: 1,
@@ -211,6 +515,7 @@ impl GameState {
                     inverted_car_data: PhysicsObject::new(),
                     last_ball_touch_tick: 0,
                     last_actions: CarControls::default(),
+                    hitbox_size: [120.507, 86.6994, 38.6591],
                 },
                 PlayerData {
                     car_id: 2,
@@ -235,13 +540,18 @@ impl GameState {
                     inverted_car_data: PhysicsObject::new(),
                     last_ball_touch_tick: 0,
                     last_actions: CarControls::default(),
+                    hitbox_size: [120.507, 86.6994, 38.6591],
                 },
             ],
             ball,
             inverted_ball: PhysicsObject::new(),
             boost_pads: [BoostPad::default(); 34],
             inverted_boost_pads: [BoostPad::default(); 34],
+            boost_pads_timers: [0.; 34],
+            inverted_boost_pads_timers: [0.; 34],
             tick_num: 0,
+            tick_count: 0,
+            seconds_elapsed: 0.,
         }
     }
 
@@ -308,3 +618,116 @@ impl GameState {
     //     return player_data
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_values::ORANGE_TEAM;
+
+    #[test]
+    fn state_vec_round_trip() {
+        // 2v2 with everything set to something other than the default
+        let mut state = GameState::new_test();
+        state.blue_score = 2;
+        state.orange_score = 1;
+        state.last_touch = 3;
+        state.last_touch_car_id = Some(2);
+        state.last_touch_location = [10., -20., 150.];
+        state.tick_num = 12345;
+        state.tick_count = 240;
+        state.seconds_elapsed = 2.;
+        state.boost_pads[3].state.is_active = false;
+        state.boost_pads[3].state.cooldown = 4.5;
+        for (i, car_id) in [3, 4].into_iter().enumerate() {
+            let mut player = state.players[i];
+            player.car_id = car_id;
+            player.team_num = ORANGE_TEAM;
+            state.players.push(player);
+        }
+        for (i, player) in state.players.iter_mut().enumerate() {
+            player.match_goals = i as i64;
+            player.match_saves = 2;
+            player.bumps = 7;
+            player.is_demoed = i == 3;
+            player.last_ball_touch_tick = 100 + i as u64;
+            player.last_actions = CarControls { throttle: 1., steer: -0.5, pitch: 0.25, yaw: 0., roll: -1., jump: true, boost: false, handbrake: true };
+            player.car_data.quaternion = Quaternion { w: 0.5, x: 0.5, y: -0.5, z: 0.5 };
+            player.car_data.rotation_mtx.array = [[0., 1., 0.], [0., 0., 1.], [1., 0., 0.]];
+            player.car_data.has_computed_rot_mtx = true;
+            player.inverted_car_data.position = Position { x: -1., y: -2., z: 3. };
+        }
+
+        let vec = state.to_vec();
+        assert_eq!(vec[0], STATE_VEC_VERSION);
+        assert_eq!(vec[1] as usize, vec.len());
+        assert_eq!(vec.len(), 3 + 11 + 2 * 27 + 34 * 6 + 4 * 83);
+
+        let decoded = GameState::from_vec(&vec);
+        assert_eq!(decoded.to_vec(), vec);
+        assert_eq!(decoded.players.len(), 4);
+        assert_eq!(decoded.blue_score, 2);
+        assert_eq!(decoded.tick_num, 12345);
+        assert_eq!(decoded.tick_count, 240);
+        assert_eq!(decoded.seconds_elapsed, 2.);
+        assert_eq!(decoded.last_touch_car_id, Some(2));
+        assert_eq!(decoded.last_touch_location, [10., -20., 150.]);
+        assert_eq!(GameState::from_vec(&GameState::new_test().to_vec()).last_touch_car_id, None);
+        assert_eq!(decoded.players[2].car_id, 3);
+        assert_eq!(decoded.players[2].team_num, ORANGE_TEAM);
+        assert!(decoded.players[3].is_demoed);
+        assert!(decoded.players[1].last_actions.handbrake);
+        assert_eq!(decoded.players[0].car_data.rotation_mtx.array, state.players[0].car_data.rotation_mtx.array);
+        assert!(!decoded.boost_pads[3].state.is_active);
+        assert_eq!(decoded.boost_pads_timers[3], 4.5);
+        assert_eq!(decoded.inverted_boost_pads_timers[30], 4.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn state_vec_wrong_version() {
+        let mut vec = GameState::new_test().to_vec();
+        vec[0] = STATE_VEC_VERSION + 1.;
+        GameState::from_vec(&vec);
+    }
+
+    #[test]
+    fn diff_reports_changed_fields() {
+        let state = GameState::new_test();
+        assert!(state.diff(&state.clone(), 1e-4).is_empty());
+
+        let mut other = state.clone();
+        other.ball.position.x += 1.;
+        let diffs = state.diff(&other, 1e-4);
+        assert_eq!(diffs.len(), 1, "diffs were {diffs:?}");
+        assert!(diffs[0].starts_with("ball.position"));
+        // below the tolerance
+        assert!(state.diff(&other, 2.).is_empty());
+
+        other.players[1].on_ground = !other.players[1].on_ground;
+        other.orange_score += 1;
+        assert_eq!(state.diff(&other, 1e-4).len(), 3);
+    }
+
+    #[test]
+    fn clone_from_reuses_buffers() {
+        let mut source = GameState::new_test();
+        let mut target = GameState::new();
+        target.clone_from(&source);
+        let players_ptr = target.players.as_ptr();
+
+        for i in 0..100 {
+            source.tick_num = i;
+            source.ball.position.z = i as f32;
+            source.players[1].boost_amount = i as f32 / 100.;
+            source.demo_events = if i % 2 == 0 { vec![(1, 2)] } else { Vec::new() };
+            target.clone_from(&source);
+            assert_eq!(target.players.as_ptr(), players_ptr, "players were reallocated");
+        }
+        assert_eq!(target.tick_num, 99);
+        assert_eq!(target.ball.position.z, 99.);
+        assert_eq!(target.players[1].boost_amount, 0.99);
+        assert!(target.demo_events.is_empty());
+        assert!(target.demo_events.capacity() >= 1);
+        assert!(target.diff(&source, 0.).is_empty());
+    }
+}
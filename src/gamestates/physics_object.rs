@@ -1,13 +1,17 @@
 use std::f32::consts::PI;
 use std::ops;
 
+#[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
 
+use crate::math::Vec3;
+
 // use ndarray::*;
 
 // start of helper structs
 
-#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Position {
     pub x: f32,
     pub y: f32,
@@ -240,7 +244,8 @@ impl From<Velocity> for Position {
     }
 }
 
-#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Velocity {
     pub x: f32,
     pub y: f32,
@@ -481,7 +486,8 @@ impl From<Position> for Velocity {
     }
 }
 
-#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Quaternion {
     pub w: f32,
     pub x: f32,
@@ -583,7 +589,8 @@ impl Quaternion {
     }
 }
 
-#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EulerAngle {
     pub pitch: f32,
     pub yaw: f32,
@@ -631,7 +638,8 @@ impl EulerAngle {
     }
 }
 
-#[derive(Default, Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RotationMatrix {
     pub array: [[f32; 3]; 3],
 }
@@ -656,6 +664,24 @@ impl RotationMatrix {
         [val1, val2, val3]
     }
 
+    /// rotates a vector from car-local (forward, right, up) to world coordinates
+    pub fn dot_vec3(&self, vec: Vec3) -> Vec3 {
+        Vec3::new(
+            Vec3::from(self.row(0)).dot(vec),
+            Vec3::from(self.row(1)).dot(vec),
+            Vec3::from(self.row(2)).dot(vec),
+        )
+    }
+
+    /// rotates a vector from world to car-local (forward, right, up) coordinates
+    pub fn to_local(&self, vec: Vec3) -> Vec3 {
+        Vec3::new(
+            Vec3::from(self.column(0)).dot(vec),
+            Vec3::from(self.column(1)).dot(vec),
+            Vec3::from(self.column(2)).dot(vec),
+        )
+    }
+
     pub fn zeros() -> RotationMatrix {
         RotationMatrix { array: [[0.; 3]; 3] }
     }
@@ -746,7 +772,8 @@ impl RotationMatrix {
 // start of PhysicsObject struct
 
 /// Struct that holds any kind of physics data for car/ball
-#[derive(Default, Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PhysicsObject {
     pub position: Position,
     pub quaternion: Quaternion,
@@ -785,6 +812,18 @@ impl PhysicsObject {
         self.angular_velocity.set_vals(Some(ball_data[6]), Some(ball_data[7]), Some(ball_data[8]));
     }
 
+    pub fn pos(&self) -> Vec3 {
+        self.position.into()
+    }
+
+    pub fn vel(&self) -> Vec3 {
+        self.linear_velocity.into()
+    }
+
+    pub fn ang_vel(&self) -> Vec3 {
+        self.angular_velocity.into()
+    }
+
     pub fn forward(&self) -> [f32; 3] {
         // let arr = &self.rotation_mtx();
         // arr.column(0)
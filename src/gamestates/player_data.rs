@@ -1,10 +1,13 @@
 use rocketsim_rs::sim::{BallHitInfo, CarControls};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::gamestates::physics_object::PhysicsObject;
+use crate::gamestates::physics_object::{EulerAngle, PhysicsObject, RotationMatrix};
+use crate::math::Vec3;
 
 /// Struct which holds extra data for agents/players aside from just the PhysicsObjects
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PlayerData {
     pub car_id: i32,
     pub team_num: i32,
@@ -20,7 +23,7 @@ pub struct PlayerData {
     pub been_bumped: u32,
     pub on_ground: bool,
     pub ball_touched: bool,
-    #[serde(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub ball_info: BallHitInfo,
     pub has_jump: bool,
     pub has_flip: bool,
@@ -28,8 +31,10 @@ pub struct PlayerData {
     pub car_data: PhysicsObject,
     pub inverted_car_data: PhysicsObject,
     pub last_ball_touch_tick: u64,
-    #[serde(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub last_actions: CarControls,
+    /// hitbox size (length, width, height) of the car's config, see `GameConfig.car_configs`
+    pub hitbox_size: [f32; 3],
 }
 
 impl PlayerData {
@@ -58,6 +63,54 @@ impl PlayerData {
             inverted_car_data: PhysicsObject::new(),
             last_ball_touch_tick: 0,
             last_actions: CarControls::default(),
+            hitbox_size: [0.; 3],
+        }
+    }
+}
+
+impl PlayerData {
+    /// normalized forward direction of the car (column 0 of the rotation matrix), +x for the identity orientation
+    pub fn forward(&self) -> [f32; 3] {
+        Vec3::from(self.rotation_mtx().column(0)).normalized().into()
+    }
+
+    /// normalized right direction of the car (column 1 of the rotation matrix, same as `PhysicsObject::right`), +y for the identity orientation
+    pub fn right(&self) -> [f32; 3] {
+        Vec3::from(self.rotation_mtx().column(1)).normalized().into()
+    }
+
+    /// normalized up direction of the car (column 2 of the rotation matrix), +z for the identity orientation
+    pub fn up(&self) -> [f32; 3] {
+        Vec3::from(self.rotation_mtx().column(2)).normalized().into()
+    }
+
+    pub fn pitch(&self) -> f32 {
+        self.euler_angles().pitch
+    }
+
+    pub fn yaw(&self) -> f32 {
+        self.euler_angles().yaw
+    }
+
+    pub fn roll(&self) -> f32 {
+        self.euler_angles().roll
+    }
+
+    /// the stored rotation matrix of the car, computed from the quaternion if it hasn't been
+    fn rotation_mtx(&self) -> RotationMatrix {
+        if self.car_data.has_computed_rot_mtx {
+            self.car_data.rotation_mtx
+        } else {
+            self.car_data.quaternion.quat_to_rot_mtx()
+        }
+    }
+
+    /// the stored euler angles of the car, computed from the quaternion if they haven't been
+    fn euler_angles(&self) -> EulerAngle {
+        if self.car_data.has_computed_euler_angles {
+            self.car_data.euler_angles
+        } else {
+            self.car_data.quaternion.quat_to_euler()
         }
     }
 }
@@ -67,3 +120,44 @@ impl Default for PlayerData {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::gamestates::physics_object::Quaternion;
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        assert!(actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-5), "got {actual:?}, expected {expected:?}");
+    }
+
+    #[test]
+    fn identity_basis() {
+        let mut player = PlayerData::new();
+        player.car_data.quaternion = Quaternion { w: 1., x: 0., y: 0., z: 0. };
+        assert_close(player.forward(), [1., 0., 0.]);
+        assert_close(player.right(), [0., 1., 0.]);
+        assert_close(player.up(), [0., 0., 1.]);
+        assert_close([player.pitch(), player.yaw(), player.roll()], [0., 0., 0.]);
+    }
+
+    #[test]
+    fn yawed_basis_from_rotation_mtx() {
+        let mut player = PlayerData::new();
+        player.car_data.rotation_mtx = EulerAngle { pitch: 0., yaw: FRAC_PI_2, roll: 0. }.euler_to_rotation();
+        player.car_data.has_computed_rot_mtx = true;
+        player.car_data.euler_angles = EulerAngle { pitch: 0., yaw: FRAC_PI_2, roll: 0. };
+        player.car_data.has_computed_euler_angles = true;
+        assert_close(player.forward(), [0., 1., 0.]);
+        assert_close(player.right(), [-1., 0., 0.]);
+        assert_close(player.up(), [0., 0., 1.]);
+        assert!((player.yaw() - FRAC_PI_2).abs() < 1e-6);
+
+        // the same rotation from the quaternion
+        let mut player = PlayerData::new();
+        player.car_data.quaternion = Quaternion { w: (FRAC_PI_2 / 2.).cos(), x: 0., y: 0., z: (FRAC_PI_2 / 2.).sin() };
+        assert_close(player.forward(), [0., 1., 0.]);
+        assert!((player.yaw() - FRAC_PI_2).abs() < 1e-5, "yaw was {}", player.yaw());
+    }
+}
@@ -1,8 +1,12 @@
 use crate::gamestates::game_state::GameState;
 use crate::envs::game_match::{GameMatch, GameConfig};
+use crate::error::GymError;
 use crate::make::RenderConfig;
+use crate::math::{obs_to_f64, to_f64};
 use crate::obs_builders::obs_builder::ObsBuilder;
 use crate::render::renderer::Renderer;
+use crate::sim_wrapper::tick_observer::TickObserver;
+use crate::state_setters::wrappers::state_wrapper::StateWrapper;
 
 // use subprocess::Popen;
 
@@ -12,6 +16,16 @@ use std::io;
 // use std::thread;
 // use std::time::Duration;
 
+/// (obs, rewards, done, info, state) as returned by `Gym::step`
+pub type StepResult = (Vec<Vec<f32>>, Vec<f32>, bool, HashMap<String, f32>, GameState);
+/// (obs, rewards, terminated, truncated, info, state) as returned by `Gym::step_gymnasium`
+pub type GymnasiumStepResult = (Vec<Vec<f32>>, Vec<f32>, bool, bool, HashMap<String, f32>, GameState);
+
+/// `StepResult` with the obs and rewards upcast to f64, as returned by `Gym::step_f64`
+pub type StepResultF64 = (Vec<Vec<f64>>, Vec<f64>, bool, HashMap<String, f32>, GameState);
+/// `GymnasiumStepResult` with the obs and rewards upcast to f64, as returned by `Gym::step_gymnasium_f64`
+pub type GymnasiumStepResultF64 = (Vec<Vec<f64>>, Vec<f64>, bool, bool, HashMap<String, f32>, GameState);
+
 /// Base Gym struct for RLGym-Rust.
 /// 
 /// See 
@@ -19,9 +33,13 @@ pub struct Gym {
     pub _game_match: GameMatch,
     pub observation_space: Vec<usize>,
     pub action_space: Vec<usize>,
+    /// state from the last reset/step/set_state, copied in with `clone_from` so its buffers are reused across episodes. 
+    /// The states returned by those calls are still decoded from the sim into new allocations every time.
     pub _prev_state: GameState,
     renderer: Option<Renderer>,
     use_truncation: bool,
+    /// steps since the last reset
+    episode_steps: u64,
 }
 
 impl Gym {
@@ -51,6 +69,7 @@ impl Gym {
             _prev_state: GameState::new(),
             renderer,
             use_truncation,
+            episode_steps: 0,
         };
 
         gym._prev_state = gym.receive_state();
@@ -59,25 +78,77 @@ impl Gym {
         gym
     }
 
-    pub fn reset(&mut self, _return_info: Option<bool>, seed: Option<u64>, reward_stage: Option<usize>) -> (Vec<Vec<f32>>, GameState) {
-        // let _return_info = match _return_info {
-        //     Some(return_info) => return_info,
-        //     None => false
-        // };
-        if let Some(seed) = seed { self._game_match.set_seeds(seed) };
+    /// Resets the episode and returns the observations, an info map and the new state.
+    /// 
+    /// The info map is always returned so the signature stays the same regardless of `return_info`, 
+    /// it is only filled (with the same keys that `step` uses) if `return_info` is `Some(true)` and is empty otherwise.
+    pub fn reset(&mut self, return_info: Option<bool>, seed: Option<u64>, reward_stage: Option<usize>) -> (Vec<Vec<f32>>, HashMap<String, f32>, GameState) {
+        let return_info = return_info.unwrap_or(false);
+        let gym_state = self.reset_episode(seed, reward_stage);
+
+        let mut info = HashMap::<String, f32>::new();
+        if return_info {
+            info.insert("result".to_string(), self._game_match.get_result(&gym_state) as f32);
+            info.insert("episode_steps".to_string(), 0.);
+            if self.use_truncation {
+                info.insert("truncated".to_string(), 0.);
+            }
+        }
+
+        (self._game_match.build_observations(&gym_state), info, gym_state)
+    }
+
+    /// Same as `reset` but the observations are upcast to f64
+    pub fn reset_f64(&mut self, return_info: Option<bool>, seed: Option<u64>, reward_stage: Option<usize>) -> (Vec<Vec<f64>>, HashMap<String, f32>, GameState) {
+        let (obs, info, gym_state) = self.reset(return_info, seed, reward_stage);
+        (obs_to_f64(&obs), info, gym_state)
+    }
+
+    /// Resets the episode like `reset` but only returns the new state, the observations are not built 
+    /// (the obs builders are still reset so stepping afterwards works as usual). 
+    /// 
+    /// Useful when resetting many times for only the states (eg. generating datasets).
+    pub fn reset_state_only(&mut self, seed: Option<u64>) -> GameState {
+        self.reset_episode(seed, None)
+    }
+
+    /// gets a state from the state setter, sets it in the sim and resets the episode
+    fn reset_episode(&mut self, seed: Option<u64>, reward_stage: Option<usize>) -> GameState {
+        if let Some(seed) = seed { self.seed(seed) };
 
+        self._game_match.shuffle_agents();
         let state_wrapper = self._game_match.get_reset_state(&self._prev_state);
+        let gym_state = self.set_sim_state(state_wrapper);
 
-        // set the sim state and get the state from the sim
-        let gym_state = if self.renderer.is_some() {
+        self._game_match.episode_reset(&gym_state, reward_stage);
+        self._prev_state.clone_from(&gym_state);
+        self.episode_steps = 0;
+        gym_state
+    }
+
+    /// Sets the state in the middle of an episode and returns the new observations and state. 
+    /// 
+    /// Unlike `reset` the terminal condition, reward function and obs builders are not reset (and no reward is given), 
+    /// the observations are built as usual so the obs builders' `pre_step` is still called with the new state. 
+    /// The previous actions given to the obs builders are kept as well.
+    pub fn set_state(&mut self, state_wrapper: StateWrapper) -> (Vec<Vec<f32>>, GameState) {
+        let gym_state = self.set_sim_state(state_wrapper);
+        self._prev_state.clone_from(&gym_state);
+
+        (self._game_match.build_observations(&gym_state), gym_state)
+    }
+
+    /// sets the sim state (rendering it if there is a renderer) and returns the state from the sim after the state modifiers
+    fn set_sim_state(&mut self, state_wrapper: StateWrapper) -> GameState {
+        let mut gym_state = if let Some(renderer) = self.renderer.as_mut() {
             let (gym_state, sim_state) = self._game_match.sim_wrapper.set_state(state_wrapper, true);
 
-            let render_op = self.renderer.as_mut().unwrap().step(vec![sim_state.unwrap()]);
+            let render_op = renderer.step(vec![sim_state.unwrap()]);
             match render_op {
                 Ok(_) => (),
                 Err(e) => {
                     println!("Unable to do rendering in reset due to error: {e}, attempting to close renderer");
-                    let close_op = self.renderer.as_mut().unwrap().close();
+                    let close_op = renderer.close();
                     match close_op {
                         Ok(_) => (),
                         Err(e) => {
@@ -94,23 +165,104 @@ impl Gym {
             gym_state
         };
 
-        self._game_match.episode_reset(&gym_state, reward_stage);
-        self._prev_state = gym_state.clone();
+        self._game_match.apply_state_modifiers(&mut gym_state);
+        gym_state
+    }
 
-        (self._game_match.build_observations(&gym_state), gym_state)
-        // TODO return Option except that state and get_result don't match
-        // if _return_info {
-        //     let mut h_m = HashMap::<&str,f64>::new();
-        //     h_m.insert("result", self._game_match.get_result(state) as f64);
-        // }
+    /// Same as `reset` but passes the reward stage (eg. for a curriculum) on to the reward function's `reset`, the info map is empty
+    pub fn reset_with_stage(&mut self, stage: usize, seed: Option<u64>) -> (Vec<Vec<f32>>, HashMap<String, f32>, GameState) {
+        self.reset(None, seed, Some(stage))
+    }
+
+    /// The number of steps since the last reset
+    pub fn episode_steps(&self) -> u64 {
+        self.episode_steps
+    }
+
+    /// The state from the last `step`/`reset`/`set_state` (the same as the state they returned), without cloning it
+    pub fn last_state(&self) -> &GameState {
+        &self._prev_state
     }
 
-    pub fn step(&mut self, actions: Vec<Vec<f32>>) -> (Vec<Vec<f32>>, Vec<f32>, bool, HashMap<String, f32>, GameState) {
+    /// Seeds the state setter, action parser, reward function and obs builders (see `GameMatch::set_seeds`). 
+    /// 
+    /// The sim itself is deterministic so a gym that is seeded with the same seed and then given the same actions 
+    /// will produce the same trajectory (as long as every random component gets its randomness from `set_seed`).
+    /// The seed takes effect on the next `reset`, which is the same as passing the seed to `reset`.
+    pub fn seed(&mut self, seed: u64) {
+        self._game_match.set_seeds(seed);
+    }
+
+    /// Steps the gym and returns (obs, rewards, done, info, state). 
+    /// 
+    /// The info contains the keys of `step_gymnasium` and `done` mirroring the returned flag.
+    /// 
+    /// If the gym uses truncation then `done` only reflects termination and the truncation is put in the info under "truncated",
+    /// otherwise `done` is true if the episode was either terminated or truncated. See `step_gymnasium` for the two flags separately.
+    pub fn step(&mut self, actions: Vec<Vec<f32>>) -> StepResult {
+        let step_result = self.step_gymnasium(actions);
+        self.to_step_result(step_result)
+    }
+
+    /// Same as `step` but the observations and rewards are upcast to f64 (the simulation and the obs/reward functions still use f32)
+    pub fn step_f64(&mut self, actions: Vec<Vec<f32>>) -> StepResultF64 {
+        let (obs, reward, done, info, gym_state) = self.step(actions);
+        (obs_to_f64(&obs), to_f64(&reward), done, info, gym_state)
+    }
+
+    /// Same as `step` but returns an error instead of panicking if the actions don't match the players (see `GameMatch::try_parse_actions`), 
+    /// the gym is not stepped in that case
+    pub fn try_step(&mut self, actions: Vec<Vec<f32>>) -> Result<StepResult, GymError> {
+        let step_result = self.try_step_gymnasium(actions)?;
+        Ok(self.to_step_result(step_result))
+    }
+
+    fn to_step_result(&self, step_result: GymnasiumStepResult) -> StepResult {
+        let (obs, reward, terminated, truncated, mut info, gym_state) = step_result;
+        let mut done = terminated;
+        if self.use_truncation{
+            info.insert("truncated".to_string(), truncated as u8 as f32);
+        }
+        else{
+            done = done || truncated;
+        }
+        info.insert("done".to_string(), done as u8 as f32);
+        (obs, reward, done, info, gym_state)
+    }
+
+    /// Gymnasium style step, returns (obs, rewards, terminated, truncated, info, state).
+    /// 
+    /// The info has the match `result`, the reward of each agent under `reward_{i}` (same order as the rewards) 
+    /// and the number of steps since the reset (including this one) under `episode_steps`. 
+    /// When the episode ends (terminated or truncated) the step count is also added as `episode_length` 
+    /// along with the stats logged by the reward function (see `RewardLogger`) for the episode.
+    /// 
+    /// `terminated` comes from `is_terminal` of the terminal condition and means the episode ended in a terminal state (eg. a goal),
+    /// only then are the rewards from `get_final_reward`. `truncated` comes from `is_truncated` (eg. a timeout) and means the episode was cut short, 
+    /// so the value of the final state should still be bootstrapped.
+    pub fn step_gymnasium(&mut self, actions: Vec<Vec<f32>>) -> GymnasiumStepResult {
         let actions = self._game_match.parse_actions(actions, &self._prev_state);
+        self.step_parsed(actions)
+    }
+
+    /// Same as `step_gymnasium` but the observations and rewards are upcast to f64
+    pub fn step_gymnasium_f64(&mut self, actions: Vec<Vec<f32>>) -> GymnasiumStepResultF64 {
+        let (obs, reward, terminated, truncated, info, gym_state) = self.step_gymnasium(actions);
+        (obs_to_f64(&obs), to_f64(&reward), terminated, truncated, info, gym_state)
+    }
 
+    /// Same as `step_gymnasium` but returns an error instead of panicking if the actions don't match the players, 
+    /// the gym is not stepped in that case
+    pub fn try_step_gymnasium(&mut self, actions: Vec<Vec<f32>>) -> Result<GymnasiumStepResult, GymError> {
+        let actions = self._game_match.try_parse_actions(actions, &self._prev_state)?;
+        Ok(self.step_parsed(actions))
+    }
+
+    fn step_parsed(&mut self, actions: Vec<Vec<f32>>) -> GymnasiumStepResult {
         // set the sim state and get the state from the sim
         let gym_state = if self.renderer.is_some() {
-            let (mut gym_state, sim_state) = self._game_match.sim_wrapper.step(actions, true);
+            let (mut gym_state, sim_state) = self._game_match.step_sim(actions, true);
+            self._game_match.apply_state_modifiers(&mut gym_state);
             
             let render_op = self.renderer.as_mut().unwrap().step(sim_state.unwrap());
             match render_op {
@@ -119,7 +271,9 @@ impl Gym {
                     if let Some(val) = val {
                         // irregular reset process (no state setters from the gym involved basically)
                         gym_state = self._game_match.sim_wrapper.set_state_sim(val);
+                        self._game_match.apply_state_modifiers(&mut gym_state);
                         self._game_match.episode_reset(&gym_state, None);
+                        self.episode_steps = 0;
                     }
                 },
                 Err(e) => {
@@ -140,26 +294,30 @@ impl Gym {
 
             gym_state
         } else {
-            let (gym_state, _) = self._game_match.sim_wrapper.step(actions, false);
+            let (mut gym_state, _) = self._game_match.step_sim(actions, false);
+            self._game_match.apply_state_modifiers(&mut gym_state);
 
             gym_state
         };
 
+        self.episode_steps += 1;
         let obs = self._game_match.build_observations(&gym_state);
-        let mut done = self._game_match.is_done(&gym_state);
+        let terminated = self._game_match.is_done(&gym_state);
         let truncated = self._game_match.is_truncated(&gym_state);
         
-        self._prev_state = gym_state.clone();
-        let reward = self._game_match.get_rewards(&gym_state, done);
+        self._prev_state.clone_from(&gym_state);
+        let reward = self._game_match.get_rewards(&gym_state, terminated);
         let mut info = HashMap::<String, f32>::new();
         info.insert("result".to_string(), self._game_match.get_result(&gym_state) as f32);
-        if self.use_truncation{
-            info.insert("truncated".to_string(), truncated as u8 as f32);
+        for (i, val) in reward.iter().enumerate() {
+            info.insert(format!("reward_{i}"), *val);
         }
-        else{
-            done = done || truncated;
+        info.insert("episode_steps".to_string(), self.episode_steps as f32);
+        if terminated || truncated {
+            info.insert("episode_length".to_string(), self.episode_steps as f32);
+            info.extend(self._game_match.drain_reward_stats());
         }
-        (obs, reward, done, info, gym_state)
+        (obs, reward, terminated, truncated, info, gym_state)
     }
 
     pub fn close_renderer(&mut self) {
@@ -197,6 +355,19 @@ impl Gym {
 
     pub fn update_config(&mut self, new_config: GameConfig, new_obs: Option<Vec<Box<dyn ObsBuilder>>>) {
         self._prev_state = self._game_match.update_settings(new_config, new_obs);
+        self.observation_space.clone_from(&self._game_match.observation_space);
+        self.action_space.clone_from(&self._game_match.action_space);
+    }
+
+    /// Changes the gravity and/or boost consumption (multipliers of the defaults, like in `GameConfig`) in the middle of an episode, 
+    /// values that are None are kept. `update_config` would also do this but it restarts the sim from a kickoff.
+    pub fn set_mutators(&mut self, gravity: Option<f32>, boost_consumption: Option<f32>) {
+        self._game_match.set_mutators(gravity, boost_consumption);
+    }
+
+    /// Sets (or removes with None) an observer that is called after every physics tick of `step`, see `TickObserver`
+    pub fn set_tick_observer(&mut self, observer: Option<Box<dyn TickObserver>>) {
+        self._game_match.sim_wrapper.set_tick_observer(observer);
     }
 
     // pub fn close(&mut self) {
@@ -27,20 +27,30 @@ pub mod action_parsers;
 pub mod common_values;
 pub mod conditionals;
 pub mod envs;
+pub mod error;
 pub mod gamestates;
 pub mod gym;
 pub mod make;
 pub mod math;
 pub mod obs_builders;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "registry")]
+pub mod registry;
 pub mod reward_functions;
 pub mod sim_wrapper;
 pub mod state_setters;
 pub mod state_generator;
+pub mod testing;
 pub mod render;
+pub mod vec_env;
 
-pub use gym::Gym;
+pub use error::GymError;
+pub use gym::{Gym, StepResultF64, GymnasiumStepResultF64};
+pub use vec_env::VecGym;
 pub use make::{
     MakeConfig,
+    MatchBuilder,
     RenderConfig,
     make,
 };
@@ -48,20 +58,34 @@ pub use action_parsers::{
     action_parser::ActionParser, 
     // default_act::default_action, 
     discrete_act::DiscreteAction, 
+    kbm_act::KBMAction,
+    lookup_act::LookupTableAction,
+    rlbot_act::RLBotAction,
     continous_act::ContinuousAction,
+    interpolated_act::InterpolatedContinuousAction,
+    sticky_act::StickyActionParser,
+    logits_act::LogitsAction,
 };
 pub use state_setters::{
     default_state::DefaultState, 
+    kickoff_like_state::KickoffLikeState,
     state_setter::StateSetter, 
     random_state::RandomState, 
+    shot_state::ShotState,
     weighted_state_setter::WeightedSampleSetter,
 };
 pub use obs_builders::{
+    action_stack_obs::ActionStackObs,
     advanced_obs::AdvancedObs, 
+    advanced_padder_obs::AdvancedPadderObs,
+    default_obs::DefaultObs,
+    frame_stack_obs::FrameStackObs,
     obs_builder::ObsBuilder,
+    padded_obs::PaddedObs,
+    predictive_obs::PredictiveObs,
 };
 pub use conditionals::{
-    common_conditions::{GoalScoredCondition, TimeoutCondition, NoTouchTimeoutCondition}, 
+    common_conditions::{GoalScoredCondition, TimeoutCondition, NoTouchTimeoutCondition, BallTouchedCondition, MercyRuleCondition, BallInRegionCondition, StuckTruncationCondition, BallStillTimeout}, 
     extra_conditions::{NoTouchKickoffTimeoutCondition, CombinedTerminalConditions}, 
     terminal_condition::TerminalCondition,
 };
@@ -82,10 +106,16 @@ pub use math::{
     trace,
     unitvec,
     squared_vecmag,
+    to_f64,
+    obs_to_f64,
+    Vec3,
 };
 pub use reward_functions::{
     combined_reward::CombinedReward, 
+    normalize_reward::{NormalizeReward, RunningMeanStd},
     reward_fn::RewardFn, 
+    sequential_reward::SequentialReward,
+    zero_sum_reward::ZeroSumReward,
     common_rewards::{ball_goal_rewards, player_ball_rewards, misc_rewards, conditional_rewards},
 };
 pub use state_generator::{
@@ -1,11 +1,17 @@
 use crate::{
-    action_parsers::action_parser::ActionParser, 
-    conditionals::terminal_condition::TerminalCondition, 
+    action_parsers::{action_parser::ActionParser, discrete_act::DiscreteAction}, 
+    conditionals::{
+        common_conditions::{GoalScoredCondition, TimeoutCondition},
+        extra_conditions::CombinedTerminalConditions,
+        terminal_condition::TerminalCondition,
+    }, 
     envs::game_match::{GameMatch, GameConfig}, 
+    error::GymError,
     gym::Gym,
-    obs_builders::obs_builder::ObsBuilder, 
-    reward_functions::reward_fn::RewardFn, 
-    state_setters::state_setter::StateSetter,
+    obs_builders::{default_obs::DefaultObs, obs_builder::ObsBuilder}, 
+    reward_functions::{common_rewards::misc_rewards::ConstantReward, reward_fn::RewardFn}, 
+    state_setters::{default_state::DefaultState, state_setter::StateSetter},
+    state_generator::state_modifier::StateModifier,
 };
 
 /// General configuration struct for the gym which is inputted through the `make` function.
@@ -23,7 +29,7 @@ use crate::{
 ///     make,
 /// };
 ///
-/// use rocketsim_rs::sim::CarConfig;
+/// use rocketsim_rs::sim::{CarConfig, GameMode};
 ///
 /// 
 /// rocketsim_rs::init(None);
@@ -35,6 +41,12 @@ use crate::{
 ///     gravity: 1.,
 ///     boost_consumption: 1.,
 ///     car_config: CarConfig::octane(),
+///     game_mode: GameMode::Soccar,
+///     blue_size: None,
+///     orange_size: None,
+///     car_configs: vec![],
+///     shuffle_cars: false,
+///     pad_opponent_obs: false,
 /// };
 /// 
 /// let game_config = make::MakeConfig {
@@ -45,6 +57,7 @@ use crate::{
 ///     use_single_obs: true,
 ///     action_parser: Box::new(TestAction::new()),
 ///     state_setter: Box::new(DefaultState::new(None)), 
+///     state_modifiers: vec![],
 /// };
 /// 
 /// let mut gym = make::make(game_config, None, None);
@@ -57,6 +70,9 @@ pub struct MakeConfig {
     pub use_single_obs: bool,
     pub action_parser: Box<dyn ActionParser>,
     pub state_setter: Box<dyn StateSetter>, 
+    /// modifiers that are applied in order to every state that the sim produces (on reset and on every step), 
+    /// before it is used for the terminal conditions, rewards and observations. Leave empty to use the sim state as is.
+    pub state_modifiers: Vec<Box<dyn StateModifier>>,
 }
 
 /// Render configuration struct for the `make` function. 
@@ -96,7 +112,7 @@ impl Default for RenderConfig {
 ///     make,
 /// };
 ///
-/// use rocketsim_rs::sim::CarConfig;
+/// use rocketsim_rs::sim::{CarConfig, GameMode};
 /// 
 /// rocketsim_rs::init(None);
 /// 
@@ -107,6 +123,12 @@ impl Default for RenderConfig {
 ///     gravity: 1.,
 ///     boost_consumption: 1.,
 ///     car_config: CarConfig::octane(),
+///     game_mode: GameMode::Soccar,
+///     blue_size: None,
+///     orange_size: None,
+///     car_configs: vec![],
+///     shuffle_cars: false,
+///     pad_opponent_obs: false,
 /// };
 /// 
 /// let game_config = make::MakeConfig {
@@ -117,6 +139,7 @@ impl Default for RenderConfig {
 ///     use_single_obs: true,
 ///     action_parser: Box::new(TestAction::new()),
 ///     state_setter: Box::new(DefaultState::new(None)), 
+///     state_modifiers: vec![],
 /// };
 /// 
 /// let mut gym = make::make(game_config, None, None);
@@ -145,3 +168,224 @@ pub fn make(mut config: MakeConfig, render_config: Option<RenderConfig>, use_tru
 
     Gym::new(game_match, render_config.unwrap_or_default(), use_truncation)
 }
+
+/// Fluent builder for a gym where every component has a default, as an alternative to filling out a `MakeConfig` by hand. 
+/// 
+/// Defaults: `GameConfig::default()` (1v1, tick skip 8), `DefaultObs` for all agents (sized for the larger team, with uneven teams 
+/// `GameConfig.pad_opponent_obs` is turned on so every obs has that size), `DiscreteAction`, 
+/// a timeout (of `timeout_steps`, 225 by default) combined with `GoalScoredCondition`, `ConstantReward` of 0 and `DefaultState`.
+/// 
+/// # Example
+/// 
+/// ```no_run
+/// use rlgym_sim_rs::{make::MatchBuilder, reward_functions::common_rewards::misc_rewards::EventReward};
+/// 
+/// rocketsim_rs::init(None);
+/// 
+/// let mut gym = MatchBuilder::new()
+///     .team_size(2)
+///     .reward_fn(Box::new(EventReward::new(Some(1.), None, Some(-1.), None, None, None, None, None)))
+///     .build()
+///     .unwrap();
+/// ```
+pub struct MatchBuilder {
+    game_config: GameConfig,
+    terminal_condition: Option<Box<dyn TerminalCondition>>,
+    timeout_steps: i64,
+    reward_fn: Option<Box<dyn RewardFn>>,
+    obs_builders: Vec<Box<dyn ObsBuilder>>,
+    use_single_obs: bool,
+    action_parser: Option<Box<dyn ActionParser>>,
+    state_setter: Option<Box<dyn StateSetter>>,
+    state_modifiers: Vec<Box<dyn StateModifier>>,
+    render_config: Option<RenderConfig>,
+    use_truncation: bool,
+}
+
+impl MatchBuilder {
+    pub fn new() -> Self {
+        MatchBuilder {
+            game_config: GameConfig::default(),
+            terminal_condition: None,
+            timeout_steps: 225,
+            reward_fn: None,
+            obs_builders: Vec::new(),
+            use_single_obs: true,
+            action_parser: None,
+            state_setter: None,
+            state_modifiers: Vec::new(),
+            render_config: None,
+            use_truncation: false,
+        }
+    }
+
+    pub fn game_config(mut self, game_config: GameConfig) -> Self {
+        self.game_config = game_config;
+        self
+    }
+
+    pub fn team_size(mut self, team_size: usize) -> Self {
+        self.game_config.team_size = team_size;
+        self
+    }
+
+    pub fn spawn_opponents(mut self, spawn_opponents: bool) -> Self {
+        self.game_config.spawn_opponents = spawn_opponents;
+        self
+    }
+
+    /// see `GameConfig.pad_opponent_obs`
+    pub fn pad_opponent_obs(mut self, pad_opponent_obs: bool) -> Self {
+        self.game_config.pad_opponent_obs = pad_opponent_obs;
+        self
+    }
+
+    pub fn tick_skip(mut self, tick_skip: usize) -> Self {
+        self.game_config.tick_skip = tick_skip;
+        self
+    }
+
+    /// steps of the default timeout, not used if a terminal condition is set
+    pub fn timeout_steps(mut self, timeout_steps: i64) -> Self {
+        self.timeout_steps = timeout_steps;
+        self
+    }
+
+    pub fn terminal_condition(mut self, terminal_condition: Box<dyn TerminalCondition>) -> Self {
+        self.terminal_condition = Some(terminal_condition);
+        self
+    }
+
+    pub fn reward_fn(mut self, reward_fn: Box<dyn RewardFn>) -> Self {
+        self.reward_fn = Some(reward_fn);
+        self
+    }
+
+    /// one obs builder that is used for all agents
+    pub fn obs_builder(mut self, obs_builder: Box<dyn ObsBuilder>) -> Self {
+        self.obs_builders = vec![obs_builder];
+        self.use_single_obs = true;
+        self
+    }
+
+    /// one obs builder per agent (blue cars first), `build` checks that the count matches the agents
+    pub fn obs_builders(mut self, obs_builders: Vec<Box<dyn ObsBuilder>>) -> Self {
+        self.obs_builders = obs_builders;
+        self.use_single_obs = false;
+        self
+    }
+
+    pub fn action_parser(mut self, action_parser: Box<dyn ActionParser>) -> Self {
+        self.action_parser = Some(action_parser);
+        self
+    }
+
+    pub fn state_setter(mut self, state_setter: Box<dyn StateSetter>) -> Self {
+        self.state_setter = Some(state_setter);
+        self
+    }
+
+    /// adds a state modifier, they are applied in the order they were added
+    pub fn state_modifier(mut self, state_modifier: Box<dyn StateModifier>) -> Self {
+        self.state_modifiers.push(state_modifier);
+        self
+    }
+
+    pub fn render_config(mut self, render_config: RenderConfig) -> Self {
+        self.render_config = Some(render_config);
+        self
+    }
+
+    pub fn use_truncation(mut self, use_truncation: bool) -> Self {
+        self.use_truncation = use_truncation;
+        self
+    }
+
+    /// fills in the defaults and validates the obs builder count, see `build` to get the gym directly
+    pub fn into_make_config(mut self) -> Result<MakeConfig, GymError> {
+        let agents = self.game_config.num_cars();
+        let obs_builders = if !self.use_single_obs && self.obs_builders.len() != agents {
+            return Err(GymError::ObsBuilderCountMismatch { obs_builders: self.obs_builders.len(), agents });
+        } else if self.obs_builders.is_empty() {
+            let config = &mut self.game_config;
+            // the obs space assumes both teams have the size of the larger one, so uneven teams are padded to it
+            let opponents = config.orange_count() > 0;
+            if opponents && config.blue_count() != config.orange_count() {
+                config.pad_opponent_obs = true;
+            }
+            vec![Box::new(DefaultObs::new(Some(config.max_team_size()), Some(opponents || config.pad_opponent_obs), None, None, None, None)) as Box<dyn ObsBuilder>]
+        } else {
+            self.obs_builders
+        };
+        let timeout_steps = self.timeout_steps;
+        let terminal_condition = self.terminal_condition.unwrap_or_else(|| {
+            Box::new(CombinedTerminalConditions::new(vec![
+                Box::new(TimeoutCondition::new(timeout_steps)),
+                Box::new(GoalScoredCondition::new()),
+            ]))
+        });
+
+        Ok(MakeConfig {
+            game_config: self.game_config,
+            terminal_condition,
+            reward_fn: self.reward_fn.unwrap_or_else(|| Box::new(ConstantReward::new(Some(0.)))),
+            obs_builder: obs_builders,
+            use_single_obs: self.use_single_obs,
+            action_parser: self.action_parser.unwrap_or_else(|| Box::new(DiscreteAction::new(None, None))),
+            state_setter: self.state_setter.unwrap_or_else(|| Box::new(DefaultState::new(None))),
+            state_modifiers: self.state_modifiers,
+        })
+    }
+
+    /// builds the gym (with `make`) or returns an error if the config is invalid
+    pub fn build(mut self) -> Result<Gym, GymError> {
+        let render_config = self.render_config.take();
+        let use_truncation = self.use_truncation;
+        Ok(make(self.into_make_config()?, render_config, Some(use_truncation)))
+    }
+}
+
+impl Default for MatchBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common_values::ORANGE_TEAM, gamestates::game_state::GameState};
+
+    #[test]
+    fn match_builder_validates_obs_builders() {
+        let config = MatchBuilder::new().team_size(2).into_make_config().unwrap();
+        assert_eq!(config.game_config.num_cars(), 4);
+        assert_eq!(config.obs_builder.len(), 1);
+        assert!(config.use_single_obs);
+
+        let obs_builders = |n: usize| (0..n).map(|_| Box::new(DefaultObs::default()) as Box<dyn ObsBuilder>).collect();
+        let err = MatchBuilder::new().obs_builders(obs_builders(1)).into_make_config().err();
+        assert_eq!(err, Some(GymError::ObsBuilderCountMismatch { obs_builders: 1, agents: 2 }));
+        let config = MatchBuilder::new().obs_builders(obs_builders(2)).into_make_config().unwrap();
+        assert!(!config.use_single_obs);
+    }
+
+    #[test]
+    fn default_obs_space_uses_larger_team() {
+        // 1v2
+        let mut state = GameState::new_test();
+        state.players[1].team_num = ORANGE_TEAM;
+        let mut opponent = state.players[1];
+        opponent.car_id = 3;
+        state.players.push(opponent);
+        let game_config = GameConfig { blue_size: Some(1), orange_size: Some(2), ..GameConfig::default() };
+
+        let mut config = MatchBuilder::new().game_config(game_config).into_make_config().unwrap();
+        assert!(config.game_config.pad_opponent_obs);
+        let obs_space = config.obs_builder[0].get_obs_space();
+        for player in &state.players {
+            let obs = config.obs_builder[0].build_obs(player, &state, &config.game_config, &[0.; 8]);
+            assert_eq!(obs.len(), obs_space[0], "obs of car {} did not match the obs space", player.car_id);
+        }
+    }
+}
@@ -7,9 +7,12 @@
 
 // use numpy::*;
 // use ndarray::*;
+use std::ops;
+
 use rand::{rngs::SmallRng, thread_rng, Rng};
+use rocketsim_rs::math::{RotMat, Vec3 as SimVec3};
 
-use crate::gamestates::physics_object::RotationMatrix;
+use crate::gamestates::physics_object::{Position, RotationMatrix, Velocity};
 
 // use crate::gamestates::physics_object::Quaternion;
 
@@ -312,3 +315,270 @@ pub fn rand_vec3(max_norm: f32, rng: &mut SmallRng) -> Vec<f32> {
     }
     res
 }
+
+/// upcasts the values to f64, eg. for rewards that are passed to code expecting f64
+pub fn to_f64(vals: &[f32]) -> Vec<f64> {
+    vals.iter().map(|&val| val as f64).collect()
+}
+
+/// upcasts each observation to f64
+pub fn obs_to_f64(obs: &[Vec<f32>]) -> Vec<Vec<f64>> {
+    obs.iter().map(|vals| to_f64(vals)).collect()
+}
+
+/// Plain 3 float vector for the car/ball math, converts to and from `Position`, `Velocity`, `[f32; 3]` and the RocketSim vector
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3 { x: 0., y: 0., z: 0. };
+
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn dot(&self, other: Vec3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn scale(&self, val: f32) -> Vec3 {
+        Vec3 { x: self.x * val, y: self.y * val, z: self.z * val }
+    }
+
+    pub fn norm_squared(&self) -> f32 {
+        self.dot(*self)
+    }
+
+    pub fn norm(&self) -> f32 {
+        self.norm_squared().sqrt()
+    }
+
+    /// the unit vector in the same direction, the zero vector stays zero
+    pub fn normalized(&self) -> Vec3 {
+        let norm = self.norm();
+        if norm == 0. {
+            *self
+        } else {
+            self.scale(1. / norm)
+        }
+    }
+
+    pub fn dist(&self, other: Vec3) -> f32 {
+        (*self - other).norm()
+    }
+
+    pub fn into_array(self) -> [f32; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl ops::Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: Vec3) -> Vec3 {
+        Vec3 { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z }
+    }
+}
+
+impl ops::Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Vec3) -> Vec3 {
+        Vec3 { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z }
+    }
+}
+
+impl ops::Mul<f32> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: f32) -> Vec3 {
+        self.scale(rhs)
+    }
+}
+
+impl ops::Div<f32> for Vec3 {
+    type Output = Vec3;
+
+    fn div(self, rhs: f32) -> Vec3 {
+        Vec3 { x: self.x / rhs, y: self.y / rhs, z: self.z / rhs }
+    }
+}
+
+impl ops::Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3 { x: -self.x, y: -self.y, z: -self.z }
+    }
+}
+
+impl From<[f32; 3]> for Vec3 {
+    fn from(arr: [f32; 3]) -> Self {
+        Vec3 { x: arr[0], y: arr[1], z: arr[2] }
+    }
+}
+
+impl From<Vec3> for [f32; 3] {
+    fn from(vec: Vec3) -> Self {
+        vec.into_array()
+    }
+}
+
+impl From<Position> for Vec3 {
+    fn from(pos: Position) -> Self {
+        Vec3 { x: pos.x, y: pos.y, z: pos.z }
+    }
+}
+
+impl From<Vec3> for Position {
+    fn from(vec: Vec3) -> Self {
+        Position { x: vec.x, y: vec.y, z: vec.z }
+    }
+}
+
+impl From<Velocity> for Vec3 {
+    fn from(vel: Velocity) -> Self {
+        Vec3 { x: vel.x, y: vel.y, z: vel.z }
+    }
+}
+
+impl From<Vec3> for Velocity {
+    fn from(vec: Vec3) -> Self {
+        Velocity { x: vec.x, y: vec.y, z: vec.z }
+    }
+}
+
+impl From<SimVec3> for Vec3 {
+    fn from(vec: SimVec3) -> Self {
+        Vec3 { x: vec.x, y: vec.y, z: vec.z }
+    }
+}
+
+impl From<Vec3> for SimVec3 {
+    fn from(vec: Vec3) -> Self {
+        SimVec3::new(vec.x, vec.y, vec.z)
+    }
+}
+
+// direct conversions between the gamestate and RocketSim types, used by the sim wrapper
+
+impl From<SimVec3> for Position {
+    fn from(vec: SimVec3) -> Self {
+        Position { x: vec.x, y: vec.y, z: vec.z }
+    }
+}
+
+impl From<Position> for SimVec3 {
+    fn from(pos: Position) -> Self {
+        SimVec3::new(pos.x, pos.y, pos.z)
+    }
+}
+
+impl From<SimVec3> for Velocity {
+    fn from(vec: SimVec3) -> Self {
+        Velocity { x: vec.x, y: vec.y, z: vec.z }
+    }
+}
+
+impl From<Velocity> for SimVec3 {
+    fn from(vel: Velocity) -> Self {
+        SimVec3::new(vel.x, vel.y, vel.z)
+    }
+}
+
+/// the columns of the matrix are the forward, right and up vectors
+impl From<RotMat> for RotationMatrix {
+    fn from(rot_mat: RotMat) -> Self {
+        RotationMatrix {
+            array: [
+                [rot_mat.forward.x, rot_mat.right.x, rot_mat.up.x],
+                [rot_mat.forward.y, rot_mat.right.y, rot_mat.up.y],
+                [rot_mat.forward.z, rot_mat.right.z, rot_mat.up.z],
+            ],
+        }
+    }
+}
+
+impl From<RotationMatrix> for RotMat {
+    fn from(rot_mtx: RotationMatrix) -> Self {
+        RotMat {
+            forward: Vec3::from(rot_mtx.column(0)).into(),
+            right: Vec3::from(rot_mtx.column(1)).into(),
+            up: Vec3::from(rot_mtx.column(2)).into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f64_upcast_is_exact() {
+        let obs = vec![vec![0.1, -2.5e-7, 1e30, f32::MIN_POSITIVE], vec![], vec![3.]];
+        let obs_f64 = obs_to_f64(&obs);
+        assert_eq!(obs_f64.len(), obs.len());
+        for (vals, vals_f64) in obs.iter().zip(&obs_f64) {
+            assert_eq!(vals.len(), vals_f64.len());
+            for (val, val_f64) in vals.iter().zip(vals_f64) {
+                assert_eq!(*val_f64 as f32, *val);
+                assert_eq!(*val_f64, *val as f64);
+            }
+        }
+        assert_eq!(to_f64(&[0.5, -1.]), vec![0.5, -1.]);
+    }
+
+    #[test]
+    fn vec3_dot_cross() {
+        let a = Vec3::new(1., 2., 3.);
+        let b = Vec3::new(4., -5., 6.);
+        assert_eq!(a.dot(b), 12.);
+        assert_eq!(Vec3::new(1., 0., 0.).cross(Vec3::new(0., 1., 0.)), Vec3::new(0., 0., 1.));
+        assert_eq!(a.cross(b), Vec3::new(27., 6., -13.));
+        // the cross product is orthogonal to both inputs
+        assert_eq!(a.cross(b).dot(a), 0.);
+        assert_eq!(a.cross(b).dot(b), 0.);
+        assert_eq!(a + b - b, a);
+        assert_eq!(-a * 2., Vec3::new(-2., -4., -6.));
+    }
+
+    #[test]
+    fn vec3_normalized() {
+        let vec = Vec3::new(3., 0., -4.);
+        assert_eq!(vec.norm(), 5.);
+        assert_eq!(vec.normalized(), Vec3::new(0.6, 0., -0.8));
+        assert!((vec.normalized().norm() - 1.).abs() < 1e-6);
+        assert_eq!(Vec3::ZERO.normalized(), Vec3::ZERO);
+        assert_eq!(Vec3::new(1., 2., 2.).dist(Vec3::ZERO), 3.);
+    }
+
+    #[test]
+    fn vec3_conversions() {
+        let vec = Vec3::new(1., -2., 3.);
+        let sim_vec: SimVec3 = vec.into();
+        assert_eq!((sim_vec.x, sim_vec.y, sim_vec.z), (1., -2., 3.));
+        assert_eq!(Vec3::from(sim_vec), vec);
+        assert_eq!(Vec3::from(Position::from(vec)), vec);
+        assert_eq!(Vec3::from(Velocity::from(vec)), vec);
+        assert_eq!(Vec3::from(vec.into_array()), vec);
+
+        let rot_mtx = RotationMatrix { array: [[0., -1., 0.], [1., 0., 0.], [0., 0., 1.]] };
+        let rot_mat = RotMat::from(rot_mtx);
+        assert_eq!(Vec3::from(rot_mat.forward), Vec3::new(0., 1., 0.));
+        assert_eq!(RotationMatrix::from(rot_mat).array, rot_mtx.array);
+        assert_eq!(rot_mtx.dot_vec3(Vec3::new(1., 0., 0.)), Vec3::new(0., 1., 0.));
+        assert_eq!(rot_mtx.to_local(Vec3::new(0., 1., 0.)), Vec3::new(1., 0., 0.));
+    }
+}
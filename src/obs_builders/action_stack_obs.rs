@@ -0,0 +1,94 @@
+use rocketsim_rs::sim::BallState;
+
+use crate::envs::game_match::GameConfig;
+use crate::gamestates::game_state::GameState;
+use crate::gamestates::player_data::PlayerData;
+
+use super::obs_builder::ObsBuilder;
+
+/// Wraps another observation builder and appends the player's previous action to the end of its observation
+pub struct ActionStackObs {
+    obs_builder: Box<dyn ObsBuilder>,
+    action_len: usize,
+}
+
+impl ActionStackObs {
+    /// action_len is the length of the parsed actions and defaults to 8
+    pub fn new(obs_builder: Box<dyn ObsBuilder>, action_len: Option<usize>) -> Self {
+        ActionStackObs {
+            obs_builder,
+            action_len: action_len.unwrap_or(8),
+        }
+    }
+}
+
+impl ObsBuilder for ActionStackObs {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.obs_builder.reset(initial_state);
+    }
+
+    fn get_obs_space(&mut self) -> Vec<usize> {
+        let mut obs_space = self.obs_builder.get_obs_space();
+        match obs_space.last_mut() {
+            Some(last) => *last += self.action_len,
+            None => obs_space.push(self.action_len),
+        }
+        obs_space
+    }
+
+    fn ball_prediction_request(&self) -> Option<(usize, f32)> {
+        self.obs_builder.ball_prediction_request()
+    }
+
+    fn set_ball_prediction(&mut self, prediction: &[BallState]) {
+        self.obs_builder.set_ball_prediction(prediction);
+    }
+
+    fn pre_step(&mut self, state: &GameState, config: &GameConfig) {
+        self.obs_builder.pre_step(state, config);
+    }
+
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let act_len = previous_action.len();
+        assert!(act_len == self.action_len, "previous action (len: {act_len}) was not the expected length (len: {})", self.action_len);
+
+        let mut obs = self.obs_builder.build_obs(player, state, config, previous_action);
+        obs.extend(previous_action);
+        obs
+    }
+
+    fn build_obs_into(&mut self, buf: &mut Vec<f32>, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) {
+        let act_len = previous_action.len();
+        assert!(act_len == self.action_len, "previous action (len: {act_len}) was not the expected length (len: {})", self.action_len);
+
+        self.obs_builder.build_obs_into(buf, player, state, config, previous_action);
+        buf.extend(previous_action);
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.obs_builder.set_seed(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obs_builders::default_obs::DefaultObs;
+
+    #[test]
+    fn action_stack_appends_action() {
+        let state = GameState::new_test();
+        let config = GameConfig::default();
+        let mut obs_builder = ActionStackObs::new(Box::new(DefaultObs::new(Some(2), Some(false), None, None, None, None)), None);
+        let obs_space = obs_builder.get_obs_space();
+
+        let action = [1., -1., 0.5, 0., 0., 1., 0., 1.];
+        let obs = obs_builder.build_obs(&state.players[0], &state, &config, &action);
+        assert_eq!(vec![obs.len()], obs_space);
+        assert_eq!(obs[obs.len() - 8..], action);
+
+        let mut buf = vec![1.; 3];
+        obs_builder.build_obs_into(&mut buf, &state.players[0], &state, &config, &action);
+        assert_eq!(buf, obs);
+    }
+}
@@ -2,17 +2,34 @@
 // use std::collections::VecDeque;
 use std::f32::consts::PI;
 
-use crate::{common_values, IntoArray};
+use crate::common_values;
 use crate::gamestates::game_state::GameState;
 use crate::gamestates::physics_object::PhysicsObject;
 use crate::gamestates::player_data::PlayerData;
 
 use super::obs_builder::ObsBuilder;
 
-/// Matrix's observation builder, holds a stack of previous ball positions and shows the stack in the observation
+/// default position and angle scales (the obs are divided by them), same as RLGym's AdvancedObs
+pub(crate) const POS_STD: f32 = 2300.;
+pub(crate) const ANG_STD: f32 = PI;
+
+/// length of the ball, previous action and boost pad part of the obs
+const BASE_OBS_LEN: usize = 9 + 8 + 34;
+/// length of the current player's part of the obs
+const PLAYER_OBS_LEN: usize = 25;
+/// length of each other player's part of the obs (includes the position and velocity relative to the current player)
+const OTHER_PLAYER_OBS_LEN: usize = PLAYER_OBS_LEN + 6;
+
+/// RLGym's advanced observation builder. 
+/// 
+/// Contains the ball, previous action and the boost pads (active or not), followed by the current player, 
+/// their teammates and then their opponents. Each car has its position and velocity relative to the ball,
+/// its own physics, boost amount and on ground/has flip/demoed flags, other cars also have their position and velocity relative to the current player.
 pub struct AdvancedObs {
-    pos_std: f32,
-    ang_std: f32,
+    team_size: usize,
+    pos_coef: f32,
+    vel_coef: f32,
+    ang_coef: f32,
 }
 
 impl Default for AdvancedObs {
@@ -22,14 +39,22 @@ impl Default for AdvancedObs {
 }
 
 impl AdvancedObs {
-    // pub fn new(team_size: Option<usize>, expanding: Option<bool>, stack_size: Option<usize>) -> Self {
     pub fn new() -> Self {
-        // let expanding = match expanding {
-        //     Some(expanding) => expanding,
-        //     None => false
-        // };
+        Self::new_with_coefs(None, None, None, None)
+    }
 
-        AdvancedObs { pos_std: 2300., ang_std: PI }
+    /// team_size is only used for the obs space and defaults to 3 (the obs space then assumes opponents are spawned,
+    /// see `GameConfig.pad_opponent_obs` to train without them).
+    /// 
+    /// The coefs are multiplied with positions, velocities and angular velocities respectively, 
+    /// they default to 1 / 2300, 1 / 2300 and 1 / PI
+    pub fn new_with_coefs(team_size: Option<usize>, pos_coef: Option<f32>, vel_coef: Option<f32>, ang_coef: Option<f32>) -> Self {
+        AdvancedObs { 
+            team_size: team_size.unwrap_or(3),
+            pos_coef: pos_coef.unwrap_or(1. / POS_STD), 
+            vel_coef: vel_coef.unwrap_or(1. / common_values::CAR_MAX_SPEED), 
+            ang_coef: ang_coef.unwrap_or(1. / ANG_STD),
+        }
     }
 
     fn _add_player_to_obs(&self, obs: &mut Vec<f32>, car: &PlayerData, ball: &PhysicsObject, inverted: bool, player: Option<&PhysicsObject>) -> PhysicsObject {
@@ -39,23 +64,21 @@ impl AdvancedObs {
             car.car_data
         };
 
-        let mut rel_pos = ball.position - player_car.position;
-        rel_pos = rel_pos.divide_by_var(self.pos_std);
-        let mut rel_vel = ball.linear_velocity - player_car.linear_velocity;
-        rel_vel = rel_vel.divide_by_var(self.pos_std);
+        let rel_pos = (ball.position - player_car.position) * self.pos_coef;
+        let rel_vel = (ball.linear_velocity - player_car.linear_velocity) * self.vel_coef;
 
         obs.extend(rel_pos);
         obs.extend(rel_vel);
-        obs.extend(player_car.position.divide_by_var(self.pos_std));
+        obs.extend(player_car.position * self.pos_coef);
         obs.extend(player_car.forward());
         obs.extend(player_car.up());
-        obs.extend(player_car.linear_velocity.divide_by_var(self.pos_std));
-        obs.extend(player_car.angular_velocity.divide_by_var(self.ang_std));
+        obs.extend(player_car.linear_velocity * self.vel_coef);
+        obs.extend(player_car.angular_velocity * self.ang_coef);
         obs.extend(vec![car.boost_amount, car.on_ground as i32 as f32, car.has_flip as i32 as f32, car.is_demoed as i32 as f32]);
 
         if let Some(player) = player {
-            obs.extend((player_car.position - player.position).divide_by_var(self.pos_std));
-            obs.extend((player_car.linear_velocity - player.linear_velocity).divide_by_var(self.pos_std));
+            obs.extend((player_car.position - player.position) * self.pos_coef);
+            obs.extend((player_car.linear_velocity - player.linear_velocity) * self.vel_coef);
         }
 
         player_car
@@ -66,10 +89,10 @@ impl ObsBuilder for AdvancedObs {
     fn reset(&mut self, _initial_state: &GameState) {}
 
     fn get_obs_space(&mut self) -> Vec<usize> {
-        vec![276]
+        vec![BASE_OBS_LEN + PLAYER_OBS_LEN + OTHER_PLAYER_OBS_LEN * (self.team_size * 2 - 1)]
     }
 
-    fn build_obs(&mut self, player: &PlayerData, state: &GameState, _config: &crate::envs::game_match::GameConfig) -> Vec<f32> {
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &crate::envs::game_match::GameConfig, previous_action: &[f32]) -> Vec<f32> {
         let inverted: bool;
         let ball: &PhysicsObject;
         let pads;
@@ -87,19 +110,16 @@ impl ObsBuilder for AdvancedObs {
         let lin = &ball.linear_velocity;
         let ang = &ball.angular_velocity;
 
-        // let pos_std = vec_div_variable(pos, &self.pos_std);
-        // let lin_std = vec_div_variable(lin, &self.pos_std);
-        // let ang_std = vec_div_variable(ang, &self.ang_std);
-        let pos_std = pos.divide_by_var(self.pos_std);
-        let lin_std = lin.divide_by_var(self.pos_std);
-        let ang_std = ang.divide_by_var(self.ang_std);
+        let pos_std = *pos * self.pos_coef;
+        let lin_std = *lin * self.vel_coef;
+        let ang_std = *ang * self.ang_coef;
 
-        let mut obs = Vec::<f32>::with_capacity(276);
+        let mut obs = Vec::<f32>::with_capacity(BASE_OBS_LEN + PLAYER_OBS_LEN + OTHER_PLAYER_OBS_LEN * (state.players.len() - 1));
 
         obs.extend(pos_std);
         obs.extend(lin_std);
         obs.extend(ang_std);
-        obs.extend(player.last_actions.into_array());
+        obs.extend(previous_action);
         obs.extend(pads.iter().map(|pad| pad.state.is_active as i32 as f32));
 
         // self.add_ball_to_stack(pos_std, lin_std, ang_std, player.car_id as usize);
@@ -109,14 +129,80 @@ impl ObsBuilder for AdvancedObs {
 
         let player_car = self._add_player_to_obs(&mut obs, player, ball, inverted, None);
 
+        let mut allies = Vec::<f32>::new();
+        let mut enemies = Vec::<f32>::new();
+        let mut ally_count = 0;
+        let mut enemy_count = 0;
         for other in &state.players {
             if other.car_id == player.car_id {
                 continue;
             }
 
-            self._add_player_to_obs(&mut obs, other, ball, inverted, Some(&player_car));
+            if other.team_num == player.team_num {
+                self._add_player_to_obs(&mut allies, other, ball, inverted, Some(&player_car));
+                ally_count += 1;
+            } else {
+                self._add_player_to_obs(&mut enemies, other, ball, inverted, Some(&player_car));
+                enemy_count += 1;
+            }
+        }
+        if config.pad_opponent_obs {
+            let max_team_size = config.max_team_size();
+            // the current player is one of the allies
+            allies.resize(allies.len() + OTHER_PLAYER_OBS_LEN * max_team_size.saturating_sub(1).saturating_sub(ally_count), 0.);
+            enemies.resize(enemies.len() + OTHER_PLAYER_OBS_LEN * max_team_size.saturating_sub(enemy_count), 0.);
         }
+        obs.extend(allies);
+        obs.extend(enemies);
 
         obs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common_values::ORANGE_TEAM, envs::game_match::GameConfig};
+
+    fn make_state(team_size: usize) -> GameState {
+        let mut state = GameState::new_test();
+        let base_player = state.players[0];
+        state.players.clear();
+        for i in 0..team_size * 2 {
+            let mut player = base_player;
+            player.car_id = i as i32 + 1;
+            player.team_num = if i % 2 == 0 { common_values::BLUE_TEAM } else { ORANGE_TEAM };
+            state.players.push(player);
+        }
+        state
+    }
+
+    #[test]
+    fn advanced_obs_dims() {
+        let config = GameConfig::default();
+        for (team_size, expected) in [(1, 107), (3, 231)] {
+            let state = make_state(team_size);
+            let mut obs_builder = AdvancedObs::new_with_coefs(Some(team_size), None, None, None);
+            assert_eq!(obs_builder.get_obs_space(), vec![expected]);
+            for player in &state.players {
+                assert_eq!(obs_builder.build_obs(player, &state, &config, &[0.; 8]).len(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn padded_opponents_match_spawned_len() {
+        let config = GameConfig { team_size: 2, ..GameConfig::default() };
+        let mut state = make_state(2);
+        let mut obs_builder = AdvancedObs::new_with_coefs(Some(2), None, None, None);
+        let spawned_len = obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]).len();
+        assert_eq!(spawned_len, obs_builder.get_obs_space()[0]);
+
+        state.players.retain(|player| player.team_num == common_values::BLUE_TEAM);
+        let padded_config = GameConfig { spawn_opponents: false, pad_opponent_obs: true, ..config.clone() };
+        let padded_obs = obs_builder.build_obs(&state.players[0], &state, &padded_config, &[0.; 8]);
+        assert_eq!(padded_obs.len(), spawned_len);
+        assert!(padded_obs[spawned_len - 2 * OTHER_PLAYER_OBS_LEN..].iter().all(|&x| x == 0.));
+        assert!(obs_builder.build_obs(&state.players[0], &state, &GameConfig { spawn_opponents: false, ..config }, &[0.; 8]).len() < spawned_len);
+    }
+}
@@ -0,0 +1,138 @@
+use rocketsim_rs::sim::BallState;
+
+use crate::common_values::{BOOST_LOCATIONS, ORANGE_TEAM};
+use crate::envs::game_match::GameConfig;
+use crate::gamestates::game_state::GameState;
+use crate::gamestates::player_data::PlayerData;
+
+use super::advanced_obs::{AdvancedObs, POS_STD};
+use super::obs_builder::ObsBuilder;
+
+/// seconds a big pad takes to respawn
+const BIG_PAD_COOLDOWN: f32 = 10.;
+/// seconds a small pad takes to respawn
+const SMALL_PAD_COOLDOWN: f32 = 4.;
+/// active, cooldown fraction, position relative to the car (3)
+const PAD_OBS_LEN: usize = 5;
+
+/// `AdvancedObs` followed by the exact boost pad information, 
+/// for each of the 34 pads (in the order of `BOOST_LOCATIONS`, inverted for orange like the rest of the obs): 
+/// whether it is active, the fraction of its cooldown left (0 when active) and its position relative to the current car.
+pub struct AdvancedPadderObs {
+    advanced_obs: AdvancedObs,
+    pos_coef: f32,
+}
+
+impl Default for AdvancedPadderObs {
+    fn default() -> Self {
+        Self::new(None, None, None, None)
+    }
+}
+
+impl AdvancedPadderObs {
+    /// same arguments as `AdvancedObs::new_with_coefs`, the relative pad positions are multiplied by pos_coef as well
+    pub fn new(team_size: Option<usize>, pos_coef: Option<f32>, vel_coef: Option<f32>, ang_coef: Option<f32>) -> Self {
+        let pos_coef = pos_coef.unwrap_or(1. / POS_STD);
+        AdvancedPadderObs {
+            advanced_obs: AdvancedObs::new_with_coefs(team_size, Some(pos_coef), vel_coef, ang_coef),
+            pos_coef,
+        }
+    }
+}
+
+impl ObsBuilder for AdvancedPadderObs {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.advanced_obs.reset(initial_state);
+    }
+
+    fn get_obs_space(&mut self) -> Vec<usize> {
+        let mut obs_space = self.advanced_obs.get_obs_space();
+        obs_space[0] += PAD_OBS_LEN * BOOST_LOCATIONS.len();
+        obs_space
+    }
+
+    fn ball_prediction_request(&self) -> Option<(usize, f32)> {
+        self.advanced_obs.ball_prediction_request()
+    }
+
+    fn set_ball_prediction(&mut self, prediction: &[BallState]) {
+        self.advanced_obs.set_ball_prediction(prediction);
+    }
+
+    fn pre_step(&mut self, state: &GameState, config: &GameConfig) {
+        self.advanced_obs.pre_step(state, config);
+    }
+
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let mut obs = self.advanced_obs.build_obs(player, state, config, previous_action);
+        obs.reserve(PAD_OBS_LEN * BOOST_LOCATIONS.len());
+
+        let (pads, timers, car) = if player.team_num == ORANGE_TEAM {
+            (&state.inverted_boost_pads, &state.inverted_boost_pads_timers, &player.inverted_car_data)
+        } else {
+            (&state.boost_pads, &state.boost_pads_timers, &player.car_data)
+        };
+
+        // the pad layout is symmetric so the inverted position of the i-th inverted pad is the i-th location as well
+        for ((pad, timer), location) in pads.iter().zip(timers).zip(BOOST_LOCATIONS) {
+            let max_cooldown = if pad.is_big { BIG_PAD_COOLDOWN } else { SMALL_PAD_COOLDOWN };
+            obs.push(pad.state.is_active as u8 as f32);
+            obs.push((timer / max_cooldown).clamp(0., 1.));
+            obs.extend([
+                (location[0] - car.position.x) * self.pos_coef,
+                (location[1] - car.position.y) * self.pos_coef,
+                (location[2] - car.position.z) * self.pos_coef,
+            ]);
+        }
+
+        obs
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.advanced_obs.set_seed(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_values::BLUE_TEAM;
+    use crate::gamestates::physics_object::Position;
+
+    #[test]
+    fn advanced_padder_obs_dims() {
+        let config = GameConfig::default();
+        let mut state = GameState::new_test();
+        state.players[1].team_num = ORANGE_TEAM;
+        let mut obs_builder = AdvancedPadderObs::new(Some(1), None, None, None);
+        assert_eq!(obs_builder.get_obs_space(), vec![107 + 34 * 5]);
+        for player in &state.players {
+            assert_eq!(obs_builder.build_obs(player, &state, &config, &[0.; 8]).len(), 107 + 34 * 5);
+        }
+    }
+
+    #[test]
+    fn advanced_padder_obs_inactive_pad() {
+        let config = GameConfig::default();
+        let mut state = GameState::new_test();
+        state.players[0].team_num = BLUE_TEAM;
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: 17. };
+        for pad in &mut state.boost_pads {
+            pad.state.is_active = true;
+        }
+        state.boost_pads[3].is_big = true;
+        state.boost_pads[3].state.is_active = false;
+        state.boost_pads_timers[3] = 5.;
+
+        let mut obs_builder = AdvancedPadderObs::new(Some(1), Some(1.), None, None);
+        let obs = obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]);
+        let pads_start = obs.len() - 34 * PAD_OBS_LEN;
+        let pad_obs = |i: usize| &obs[pads_start + i * PAD_OBS_LEN..pads_start + (i + 1) * PAD_OBS_LEN];
+
+        assert_eq!(pad_obs(3)[0], 0.);
+        assert!(pad_obs(3)[1] > 0.);
+        assert_eq!(pad_obs(3)[1], 0.5);
+        assert_eq!(pad_obs(3)[2..], [-3072., -4096., 73. - 17.]);
+        assert_eq!(pad_obs(0)[..2], [1., 0.]);
+    }
+}
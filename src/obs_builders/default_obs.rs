@@ -1 +1,316 @@
+use crate::common_values::{BACK_NET_Y, CAR_MAX_ANG_VEL, CAR_MAX_SPEED, CEILING_Z, ORANGE_TEAM, SIDE_WALL_X};
+use crate::envs::game_match::GameConfig;
+use crate::gamestates::game_state::GameState;
+use crate::gamestates::physics_object::{Position, Velocity};
+use crate::gamestates::player_data::PlayerData;
 
+use super::obs_builder::ObsBuilder;
+#[cfg(feature = "parallel_obs")]
+use super::obs_builder::ParallelObsBuilder;
+
+/// length of the ball, previous action and boost pad part of the obs
+pub(crate) const BASE_OBS_LEN: usize = 9 + 8 + 34;
+/// length of a single player's part of the obs
+pub(crate) const PLAYER_OBS_LEN: usize = 19;
+
+/// RLGym's default observation builder, uses the same field order so that existing models can be loaded:
+/// 
+/// ball (pos, lin vel, ang vel), previous action, boost pads, then the current player, allies and lastly opponents
+/// where each player is (pos, forward, up, lin vel, ang vel, boost, on ground, has flip, is demoed). 
+/// 
+/// If invert is set (the default), everything is from the perspective of the current player's team: for orange players the x and y axes
+/// of the ball and every player are negated (the inverted physics data) and the boost pads are reversed, so both teams attack toward +y
+/// and can share one policy. Otherwise everything is in the absolute (blue) frame.
+pub struct DefaultObs {
+    team_size: usize,
+    invert: bool,
+    spawn_opponents: bool,
+    pos_coef: [f32; 3],
+    lin_vel_coef: f32,
+    ang_vel_coef: f32,
+}
+
+impl DefaultObs {
+    /// team_size defaults to 1 and spawn_opponents to true, these are only used for the obs space
+    /// (keep spawn_opponents true when `GameConfig.pad_opponent_obs` is set).
+    /// 
+    /// pos_coef defaults to the inverse of the field extents (side wall, back of the net, ceiling), lin_vel_coef to 1 / CAR_MAX_SPEED 
+    /// and ang_vel_coef to 1 / CAR_MAX_ANG_VEL. invert defaults to true.
+    pub fn new(
+        team_size: Option<usize>,
+        spawn_opponents: Option<bool>,
+        pos_coef: Option<[f32; 3]>,
+        lin_vel_coef: Option<f32>,
+        ang_vel_coef: Option<f32>,
+        invert: Option<bool>,
+    ) -> Self {
+        DefaultObs {
+            team_size: team_size.unwrap_or(1),
+            invert: invert.unwrap_or(true),
+            spawn_opponents: spawn_opponents.unwrap_or(true),
+            pos_coef: pos_coef.unwrap_or([1. / SIDE_WALL_X, 1. / BACK_NET_Y, 1. / CEILING_Z]),
+            lin_vel_coef: lin_vel_coef.unwrap_or(1. / CAR_MAX_SPEED),
+            ang_vel_coef: ang_vel_coef.unwrap_or(1. / CAR_MAX_ANG_VEL),
+        }
+    }
+
+    fn scale_pos(&self, pos: &Position) -> [f32; 3] {
+        [pos.x * self.pos_coef[0], pos.y * self.pos_coef[1], pos.z * self.pos_coef[2]]
+    }
+
+    fn scale_vel(vel: &Velocity, coef: f32) -> [f32; 3] {
+        [vel.x * coef, vel.y * coef, vel.z * coef]
+    }
+
+    pub(crate) fn add_player_to_obs(&self, obs: &mut Vec<f32>, player: &PlayerData, inverted: bool) {
+        let player_car = if inverted { &player.inverted_car_data } else { &player.car_data };
+
+        obs.extend(self.scale_pos(&player_car.position));
+        obs.extend(player_car.forward());
+        obs.extend(player_car.up());
+        obs.extend(Self::scale_vel(&player_car.linear_velocity, self.lin_vel_coef));
+        obs.extend(Self::scale_vel(&player_car.angular_velocity, self.ang_vel_coef));
+        obs.extend([
+            player.boost_amount,
+            player.on_ground as i32 as f32,
+            player.has_flip as i32 as f32,
+            player.is_demoed as i32 as f32,
+        ]);
+    }
+
+    /// adds the ball, previous action, boost pads and the current player to the obs and returns whether the obs is inverted
+    pub(crate) fn add_base_to_obs(&self, obs: &mut Vec<f32>, player: &PlayerData, state: &GameState, previous_action: &[f32]) -> bool {
+        let inverted = self.invert && player.team_num == ORANGE_TEAM;
+        let (ball, pads) = if inverted {
+            (&state.inverted_ball, &state.inverted_boost_pads)
+        } else {
+            (&state.ball, &state.boost_pads)
+        };
+
+        obs.extend(self.scale_pos(&ball.position));
+        obs.extend(Self::scale_vel(&ball.linear_velocity, self.lin_vel_coef));
+        obs.extend(Self::scale_vel(&ball.angular_velocity, self.ang_vel_coef));
+        obs.extend(previous_action);
+        obs.extend(pads.iter().map(|pad| pad.state.is_active as i32 as f32));
+
+        self.add_player_to_obs(obs, player, inverted);
+        inverted
+    }
+
+    /// writes the whole obs into buf (cleared first)
+    fn write_obs(&self, buf: &mut Vec<f32>, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) {
+        buf.clear();
+        let inverted = self.add_base_to_obs(buf, player, state, previous_action);
+
+        // allies then opponents, in two passes so no temporary vecs are needed
+        for allies in [true, false] {
+            let mut count = 0;
+            for other in &state.players {
+                if other.car_id != player.car_id && (other.team_num == player.team_num) == allies {
+                    self.add_player_to_obs(buf, other, inverted);
+                    count += 1;
+                }
+            }
+            if config.pad_opponent_obs {
+                // the current player is one of the allies
+                let missing = config.max_team_size().saturating_sub(allies as usize).saturating_sub(count);
+                buf.resize(buf.len() + PLAYER_OBS_LEN * missing, 0.);
+            }
+        }
+    }
+}
+
+impl Default for DefaultObs {
+    fn default() -> Self {
+        Self::new(None, None, None, None, None, None)
+    }
+}
+
+impl ObsBuilder for DefaultObs {
+    fn reset(&mut self, _initial_state: &GameState) {}
+
+    fn get_obs_space(&mut self) -> Vec<usize> {
+        let n_players = if self.spawn_opponents { self.team_size * 2 } else { self.team_size };
+        vec![BASE_OBS_LEN + PLAYER_OBS_LEN * n_players]
+    }
+
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let mut obs = Vec::<f32>::with_capacity(BASE_OBS_LEN + PLAYER_OBS_LEN * state.players.len());
+        self.build_obs_into(&mut obs, player, state, config, previous_action);
+        obs
+    }
+
+    fn build_obs_into(&mut self, buf: &mut Vec<f32>, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) {
+        self.write_obs(buf, player, state, config, previous_action);
+    }
+
+    #[cfg(feature = "parallel_obs")]
+    fn as_parallel(&self) -> Option<&dyn ParallelObsBuilder> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "parallel_obs")]
+impl ParallelObsBuilder for DefaultObs {
+    fn par_build_obs(&self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let mut obs = Vec::<f32>::with_capacity(BASE_OBS_LEN + PLAYER_OBS_LEN * state.players.len());
+        self.write_obs(&mut obs, player, state, config, previous_action);
+        obs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_values::BLUE_TEAM;
+    use crate::gamestates::physics_object::Quaternion;
+
+    #[test]
+    fn default_obs_len_matches_space() {
+        let mut state = GameState::new_test();
+        for car_id in [3, 4] {
+            let mut opponent = state.players[0];
+            opponent.car_id = car_id;
+            opponent.team_num = ORANGE_TEAM;
+            state.players.push(opponent);
+        }
+        let config = GameConfig::default();
+
+        let mut obs_builder = DefaultObs::new(Some(2), Some(true), None, None, None, None);
+        let obs_space = obs_builder.get_obs_space();
+        assert_eq!(obs_space, vec![127]);
+        for player in &state.players {
+            let obs = obs_builder.build_obs(player, &state, &config, &[0.; 8]);
+            assert_eq!(obs.len(), obs_space[0]);
+        }
+
+        // same length as RLGym's DefaultObs in a 1v1
+        assert_eq!(DefaultObs::new(Some(1), Some(true), None, None, None, None).get_obs_space(), vec![89]);
+
+        // ally comes before the opponents
+        let obs = obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]);
+        assert_eq!(obs[BASE_OBS_LEN + 3..BASE_OBS_LEN + 6], state.players[0].car_data.forward());
+        assert_eq!(obs[BASE_OBS_LEN + 6..BASE_OBS_LEN + 9], state.players[0].car_data.up());
+        assert_eq!(state.players[1].team_num, BLUE_TEAM);
+        assert_eq!(obs[BASE_OBS_LEN + PLAYER_OBS_LEN], state.players[1].car_data.position.x / SIDE_WALL_X);
+    }
+
+    #[test]
+    fn padded_opponents_match_spawned_len() {
+        // 1v1 with the opponent spawned
+        let mut state = GameState::new_test();
+        state.players[1].team_num = ORANGE_TEAM;
+        let config = GameConfig::default();
+        let mut obs_builder = DefaultObs::new(Some(1), Some(true), None, None, None, None);
+        let spawned_obs = obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]);
+        assert_eq!(spawned_obs.len(), obs_builder.get_obs_space()[0]);
+
+        // 1v0 padded to the 1v1 shape
+        state.players.truncate(1);
+        let config = GameConfig { spawn_opponents: false, pad_opponent_obs: true, ..GameConfig::default() };
+        let padded_obs = obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]);
+        assert_eq!(padded_obs.len(), spawned_obs.len());
+        assert_eq!(padded_obs[..BASE_OBS_LEN + PLAYER_OBS_LEN], spawned_obs[..BASE_OBS_LEN + PLAYER_OBS_LEN]);
+        assert!(padded_obs[BASE_OBS_LEN + PLAYER_OBS_LEN..].iter().all(|&x| x == 0.));
+
+        // without the flag nothing is added
+        let config = GameConfig { spawn_opponents: false, ..GameConfig::default() };
+        assert_eq!(obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]).len(), BASE_OBS_LEN + PLAYER_OBS_LEN);
+    }
+
+    #[test]
+    fn mirrored_blue_matches_orange() {
+        let mut state = GameState::new_test();
+        state.players[1].team_num = ORANGE_TEAM;
+        state.players[1].car_data.quaternion = Quaternion { w: 0.9, x: 0.1, y: -0.3, z: 0.2 };
+        state.boost_pads[0].state.is_active = false;
+        state.inverted_boost_pads[33].state.is_active = false;
+        state.inverted_ball.position = state.ball.position.invert();
+        state.inverted_ball.linear_velocity = state.ball.linear_velocity.invert();
+        state.inverted_ball.angular_velocity = state.ball.angular_velocity.invert();
+        for player in &mut state.players {
+            player.inverted_car_data.position = player.car_data.position.invert();
+            player.inverted_car_data.quaternion = player.car_data.quaternion.invert();
+            player.car_data.rotation_mtx = player.car_data.quaternion.quat_to_rot_mtx();
+            player.inverted_car_data.rotation_mtx = player.inverted_car_data.quaternion.quat_to_rot_mtx();
+            player.inverted_car_data.linear_velocity = player.car_data.linear_velocity.invert();
+            player.inverted_car_data.angular_velocity = player.car_data.angular_velocity.invert();
+        }
+
+        // the same situation with the teams swapped and the field rotated
+        let mut mirrored = state.clone();
+        std::mem::swap(&mut mirrored.ball, &mut mirrored.inverted_ball);
+        std::mem::swap(&mut mirrored.boost_pads, &mut mirrored.inverted_boost_pads);
+        for player in &mut mirrored.players {
+            player.team_num = if player.team_num == ORANGE_TEAM { BLUE_TEAM } else { ORANGE_TEAM };
+            std::mem::swap(&mut player.car_data, &mut player.inverted_car_data);
+        }
+
+        let config = GameConfig::default();
+        let mut obs_builder = DefaultObs::default();
+        for (player, mirrored_player) in state.players.iter().zip(&mirrored.players) {
+            let obs = obs_builder.build_obs(player, &state, &config, &[0.; 8]);
+            let mirrored_obs = obs_builder.build_obs(mirrored_player, &mirrored, &config, &[0.; 8]);
+            assert_eq!(obs, mirrored_obs, "car {} did not observe the same in the mirrored state", player.car_id);
+        }
+
+        // without invert orange sees the raw positions
+        let mut obs_builder = DefaultObs::new(None, None, Some([1.; 3]), None, None, Some(false));
+        let obs = obs_builder.build_obs(&state.players[1], &state, &config, &[0.; 8]);
+        assert_eq!(obs[0], state.ball.position.x);
+        assert_eq!(obs[BASE_OBS_LEN], state.players[1].car_data.position.x);
+    }
+
+    #[test]
+    fn build_obs_into_matches_build_obs() {
+        let mut state = GameState::new_test();
+        for (i, car_id) in [3, 4, 5, 6].into_iter().enumerate() {
+            let mut player = state.players[i % 2];
+            player.car_id = car_id;
+            player.team_num = if i < 1 { BLUE_TEAM } else { ORANGE_TEAM };
+            player.car_data.position.x += 100. * i as f32;
+            player.inverted_car_data.position.x -= 100. * i as f32;
+            state.players.push(player);
+        }
+        let config = GameConfig::default();
+        let mut obs_builder = DefaultObs::new(Some(3), None, None, None, None, None);
+
+        let mut buf = Vec::new();
+        for _ in 0..100 {
+            for player in &state.players {
+                let obs = obs_builder.build_obs(player, &state, &config, &[0.5; 8]);
+                obs_builder.build_obs_into(&mut buf, player, &state, &config, &[0.5; 8]);
+                assert_eq!(obs.iter().map(|x| x.to_bits()).collect::<Vec<_>>(), buf.iter().map(|x| x.to_bits()).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel_obs")]
+    fn parallel_obs_matches_sequential() {
+        use crate::obs_builders::obs_builder::par_build_observations;
+
+        let mut state = GameState::new_test();
+        for (i, car_id) in [3, 4, 5, 6].into_iter().enumerate() {
+            let mut player = state.players[i % 2];
+            player.car_id = car_id;
+            player.team_num = if i < 1 { BLUE_TEAM } else { ORANGE_TEAM };
+            player.car_data.position.x += 100. * i as f32;
+            player.inverted_car_data.position.x -= 100. * i as f32;
+            state.players.push(player);
+        }
+        let config = GameConfig::default();
+        let prev_actions: Vec<Vec<f32>> = (0..state.players.len()).map(|i| vec![i as f32 / 10.; 8]).collect();
+        let mut obs_builder = DefaultObs::new(Some(3), None, None, None, None, None);
+        obs_builder.pre_step(&state, &config);
+
+        let sequential: Vec<Vec<f32>> = state
+            .players
+            .iter()
+            .zip(&prev_actions)
+            .map(|(player, prev_act)| obs_builder.build_obs(player, &state, &config, prev_act))
+            .collect();
+        let parallel = par_build_observations(obs_builder.as_parallel().unwrap(), &state, &config, &prev_actions);
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::{HashMap, VecDeque};
+
+use rocketsim_rs::sim::BallState;
+
+use crate::envs::game_match::GameConfig;
+use crate::gamestates::game_state::GameState;
+use crate::gamestates::player_data::PlayerData;
+
+use super::obs_builder::ObsBuilder;
+
+/// Wraps another observation builder and concatenates the last `stack_size` observations of each player (oldest to newest).
+///
+/// The history is kept per car id so it works the same with a single obs builder for every player or one builder per player.
+/// Until enough observations have been built since the last reset, the missing (oldest) frames are zeros.
+pub struct FrameStackObs {
+    obs_builder: Box<dyn ObsBuilder>,
+    stack_size: usize,
+    frames: HashMap<i32, VecDeque<Vec<f32>>>,
+}
+
+impl FrameStackObs {
+    /// stack_size is the number of observations to stack and defaults to 4
+    pub fn new(obs_builder: Box<dyn ObsBuilder>, stack_size: Option<usize>) -> Self {
+        let stack_size = stack_size.unwrap_or(4);
+        assert!(stack_size > 0, "stack size must be at least 1");
+        FrameStackObs {
+            obs_builder,
+            stack_size,
+            frames: HashMap::new(),
+        }
+    }
+}
+
+impl ObsBuilder for FrameStackObs {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.frames.clear();
+        self.obs_builder.reset(initial_state);
+    }
+
+    fn get_obs_space(&mut self) -> Vec<usize> {
+        let mut obs_space = self.obs_builder.get_obs_space();
+        if let Some(last) = obs_space.last_mut() {
+            *last *= self.stack_size;
+        }
+        obs_space
+    }
+
+    fn ball_prediction_request(&self) -> Option<(usize, f32)> {
+        self.obs_builder.ball_prediction_request()
+    }
+
+    fn set_ball_prediction(&mut self, prediction: &[BallState]) {
+        self.obs_builder.set_ball_prediction(prediction);
+    }
+
+    fn pre_step(&mut self, state: &GameState, config: &GameConfig) {
+        self.obs_builder.pre_step(state, config);
+    }
+
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let obs = self.obs_builder.build_obs(player, state, config, previous_action);
+        let obs_len = obs.len();
+
+        let frames = self.frames.entry(player.car_id).or_insert_with(|| VecDeque::with_capacity(self.stack_size));
+        if frames.len() == self.stack_size {
+            frames.pop_front();
+        }
+        frames.push_back(obs);
+
+        let mut stacked = Vec::<f32>::with_capacity(obs_len * self.stack_size);
+        stacked.resize(obs_len * (self.stack_size - frames.len()), 0.);
+        for frame in frames.iter() {
+            stacked.extend(frame);
+        }
+        stacked
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.obs_builder.set_seed(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// obs is the ball's x position and the player's car id
+    struct BallXObs;
+
+    impl ObsBuilder for BallXObs {
+        fn reset(&mut self, _initial_state: &GameState) {}
+
+        fn get_obs_space(&mut self) -> Vec<usize> {
+            vec![2]
+        }
+
+        fn build_obs(&mut self, player: &PlayerData, state: &GameState, _config: &GameConfig, _previous_action: &[f32]) -> Vec<f32> {
+            vec![state.ball.position.x, player.car_id as f32]
+        }
+    }
+
+    #[test]
+    fn frame_stack_order_and_padding() {
+        let mut state = GameState::new_test();
+        let config = GameConfig::default();
+        let mut obs_builder = FrameStackObs::new(Box::new(BallXObs), Some(3));
+        assert_eq!(obs_builder.get_obs_space(), vec![6]);
+        obs_builder.reset(&state);
+
+        let mut build = |state: &GameState, player: usize| obs_builder.build_obs(&state.players[player], state, &config, &[0.; 8]);
+
+        state.ball.position.x = 1.;
+        assert_eq!(build(&state, 0), vec![0., 0., 0., 0., 1., 1.]);
+        // the other player's history is separate
+        assert_eq!(build(&state, 1), vec![0., 0., 0., 0., 1., 2.]);
+        state.ball.position.x = 2.;
+        assert_eq!(build(&state, 0), vec![0., 0., 1., 1., 2., 1.]);
+        state.ball.position.x = 3.;
+        assert_eq!(build(&state, 0), vec![1., 1., 2., 1., 3., 1.]);
+        state.ball.position.x = 4.;
+        assert_eq!(build(&state, 0), vec![2., 1., 3., 1., 4., 1.]);
+        assert_eq!(build(&state, 1), vec![0., 0., 1., 2., 4., 2.]);
+
+        obs_builder.reset(&state);
+        assert_eq!(obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]), vec![0., 0., 0., 0., 4., 1.]);
+    }
+}
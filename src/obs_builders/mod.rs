@@ -1,4 +1,9 @@
+pub mod action_stack_obs;
 pub mod advanced_obs;
+pub mod advanced_padder_obs;
 pub mod default_obs;
+pub mod frame_stack_obs;
 pub mod obs_builder;
+pub mod padded_obs;
+pub mod predictive_obs;
 pub mod rhobot_obs;
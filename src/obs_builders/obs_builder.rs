@@ -1,3 +1,7 @@
+#[cfg(feature = "parallel_obs")]
+use rayon::prelude::*;
+use rocketsim_rs::sim::BallState;
+
 use crate::{
     envs::game_match::GameConfig,
     gamestates::{game_state::GameState, player_data::PlayerData},
@@ -6,6 +10,46 @@ use crate::{
 pub trait ObsBuilder {
     fn reset(&mut self, initial_state: &GameState);
     fn get_obs_space(&mut self) -> Vec<usize>;
+    /// (num steps, dt) of the ball prediction this obs builder needs, None (the default) if it doesn't need one
+    fn ball_prediction_request(&self) -> Option<(usize, f32)> {
+        None
+    }
+    /// called right before `pre_step` with the ball prediction if `ball_prediction_request` returns Some, see `GameMatch::predict_ball`
+    fn set_ball_prediction(&mut self, _prediction: &[BallState]) {}
     fn pre_step(&mut self, _state: &GameState, _config: &GameConfig) {}
-    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig) -> Vec<f32>;
+    /// previous_action is the parsed action that this player took on the previous step (all zeros after a reset)
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32>;
+    /// Same as `build_obs` but writes the obs into `buf` (which is cleared first) so that its allocation can be reused, 
+    /// see `GameMatch::build_observations_into`. The default calls `build_obs`, override it to avoid the allocation.
+    fn build_obs_into(&mut self, buf: &mut Vec<f32>, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) {
+        buf.clear();
+        buf.extend(self.build_obs(player, state, config, previous_action));
+    }
+    /// only needed for obs builders with randomness (eg. noise), see `Gym::seed`
+    fn set_seed(&mut self, _seed: u64) {}
+    /// Returns the builder if it can build the obs of several players at the same time (see `ParallelObsBuilder`), 
+    /// None (the default) builds them one by one with `build_obs`
+    #[cfg(feature = "parallel_obs")]
+    fn as_parallel(&self) -> Option<&dyn ParallelObsBuilder> {
+        None
+    }
+}
+
+/// Obs builder that builds the obs without mutating itself, so that `GameMatch::build_observations` can build the obs of 
+/// all of the players on the rayon thread pool when `use_single_obs` is set. Expose it through `ObsBuilder::as_parallel`.
+#[cfg(feature = "parallel_obs")]
+pub trait ParallelObsBuilder: Sync {
+    /// same as `ObsBuilder::build_obs`, `pre_step` has already been called for this step
+    fn par_build_obs(&self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32>;
+}
+
+/// builds the obs of every player in parallel, previous_actions are in the same order as the players
+#[cfg(feature = "parallel_obs")]
+pub fn par_build_observations(obs_builder: &dyn ParallelObsBuilder, state: &GameState, config: &GameConfig, previous_actions: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    state
+        .players
+        .par_iter()
+        .zip(previous_actions)
+        .map(|(player, prev_act)| obs_builder.par_build_obs(player, state, config, prev_act))
+        .collect()
 }
@@ -0,0 +1,161 @@
+use crate::envs::game_match::GameConfig;
+use crate::gamestates::game_state::GameState;
+use crate::gamestates::player_data::PlayerData;
+
+use super::default_obs::{DefaultObs, BASE_OBS_LEN, PLAYER_OBS_LEN};
+use super::obs_builder::ObsBuilder;
+#[cfg(feature = "parallel_obs")]
+use super::obs_builder::ParallelObsBuilder;
+
+/// length of a padded player slot, the presence flag and the player
+const SLOT_LEN: usize = 1 + PLAYER_OBS_LEN;
+
+/// `DefaultObs` with a fixed size for up to `max_players` per team, so one policy can be trained across 1s, 2s and 3s.
+/// 
+/// After the ball, previous action, boost pads and the current player there are `max_players - 1` ally slots and then `max_players` opponent slots
+/// (in the order of the players in the state), each slot is a presence flag (1 if the slot holds a player, 0 otherwise) followed by the player.
+/// Empty slots are all zeros.
+pub struct PaddedObs {
+    default_obs: DefaultObs,
+    max_players: usize,
+}
+
+impl PaddedObs {
+    /// max_players is the max number of players per team and defaults to 3, the rest are the same as `DefaultObs::new`
+    pub fn new(
+        max_players: Option<usize>,
+        pos_coef: Option<[f32; 3]>,
+        lin_vel_coef: Option<f32>,
+        ang_vel_coef: Option<f32>,
+        invert: Option<bool>,
+    ) -> Self {
+        let max_players = max_players.unwrap_or(3);
+        assert!(max_players > 0, "max_players must be at least 1");
+        PaddedObs {
+            default_obs: DefaultObs::new(Some(max_players), Some(true), pos_coef, lin_vel_coef, ang_vel_coef, invert),
+            max_players,
+        }
+    }
+
+    fn obs_len(&self) -> usize {
+        BASE_OBS_LEN + PLAYER_OBS_LEN + SLOT_LEN * (2 * self.max_players - 1)
+    }
+
+    /// writes the whole obs into buf (cleared first)
+    fn write_obs(&self, buf: &mut Vec<f32>, player: &PlayerData, state: &GameState, previous_action: &[f32]) {
+        buf.clear();
+        let inverted = self.default_obs.add_base_to_obs(buf, player, state, previous_action);
+
+        for (allies, slots) in [(true, self.max_players - 1), (false, self.max_players)] {
+            let mut filled = 0;
+            for other in &state.players {
+                if other.car_id != player.car_id && (other.team_num == player.team_num) == allies {
+                    filled += 1;
+                    assert!(filled <= slots, "more players on a team than max_players ({})", self.max_players);
+                    buf.push(1.);
+                    self.default_obs.add_player_to_obs(buf, other, inverted);
+                }
+            }
+            buf.resize(buf.len() + SLOT_LEN * (slots - filled), 0.);
+        }
+    }
+}
+
+impl Default for PaddedObs {
+    fn default() -> Self {
+        Self::new(None, None, None, None, None)
+    }
+}
+
+impl ObsBuilder for PaddedObs {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.default_obs.reset(initial_state);
+    }
+
+    fn get_obs_space(&mut self) -> Vec<usize> {
+        vec![self.obs_len()]
+    }
+
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let mut obs = Vec::<f32>::with_capacity(self.obs_len());
+        self.build_obs_into(&mut obs, player, state, config, previous_action);
+        obs
+    }
+
+    fn build_obs_into(&mut self, buf: &mut Vec<f32>, player: &PlayerData, state: &GameState, _config: &GameConfig, previous_action: &[f32]) {
+        self.write_obs(buf, player, state, previous_action);
+    }
+
+    #[cfg(feature = "parallel_obs")]
+    fn as_parallel(&self) -> Option<&dyn ParallelObsBuilder> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "parallel_obs")]
+impl ParallelObsBuilder for PaddedObs {
+    fn par_build_obs(&self, player: &PlayerData, state: &GameState, _config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let mut obs = Vec::<f32>::with_capacity(self.obs_len());
+        self.write_obs(&mut obs, player, state, previous_action);
+        obs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_values::{BLUE_TEAM, ORANGE_TEAM};
+
+    /// test state with the given number of players per team, blue first
+    fn state_with_teams(team_size: usize) -> GameState {
+        let mut state = GameState::new_test();
+        let template = state.players[0];
+        state.players.clear();
+        for i in 0..team_size * 2 {
+            let mut player = template;
+            player.car_id = i as i32 + 1;
+            player.team_num = if i < team_size { BLUE_TEAM } else { ORANGE_TEAM };
+            player.car_data.position.x = 100. * i as f32;
+            state.players.push(player);
+        }
+        state
+    }
+
+    #[test]
+    fn padded_obs_same_len_across_team_sizes() {
+        let config = GameConfig::default();
+        let mut obs_builder = PaddedObs::new(Some(3), None, None, None, Some(false));
+        let obs_len = obs_builder.get_obs_space()[0];
+        assert_eq!(obs_len, BASE_OBS_LEN + PLAYER_OBS_LEN + 5 * SLOT_LEN);
+
+        for team_size in [1, 2, 3] {
+            let state = state_with_teams(team_size);
+            for player in &state.players {
+                let obs = obs_builder.build_obs(player, &state, &config, &[0.; 8]);
+                assert_eq!(obs.len(), obs_len, "wrong obs length for {team_size}v{team_size}");
+            }
+        }
+
+        // 2v2 from the first blue player: ally, empty ally slot, 2 opponents, empty opponent slot
+        let state = state_with_teams(2);
+        let obs = obs_builder.build_obs(&state.players[0], &state, &config, &[0.; 8]);
+        let slots: Vec<&[f32]> = obs[BASE_OBS_LEN + PLAYER_OBS_LEN..].chunks(SLOT_LEN).collect();
+        let flags: Vec<f32> = slots.iter().map(|slot| slot[0]).collect();
+        assert_eq!(flags, vec![1., 0., 1., 1., 0.]);
+        assert_eq!(slots[0][1], state.players[1].car_data.position.x / 4096.);
+        assert_eq!(slots[2][1], state.players[2].car_data.position.x / 4096.);
+        assert!(slots[1].iter().chain(slots[4]).all(|val| *val == 0.));
+
+        // the players are the same as in the DefaultObs
+        let mut default_obs = DefaultObs::new(Some(2), Some(true), None, None, None, Some(false));
+        let expected = default_obs.build_obs(&state.players[0], &state, &config, &[0.; 8]);
+        assert_eq!(obs[..BASE_OBS_LEN + PLAYER_OBS_LEN], expected[..BASE_OBS_LEN + PLAYER_OBS_LEN]);
+    }
+
+    #[test]
+    #[should_panic(expected = "more players on a team than max_players")]
+    fn padded_obs_too_many_players() {
+        let state = state_with_teams(3);
+        PaddedObs::new(Some(2), None, None, None, None).build_obs(&state.players[0], &state, &GameConfig::default(), &[0.; 8]);
+    }
+}
@@ -0,0 +1,220 @@
+use rocketsim_rs::sim::BallState;
+
+use crate::common_values::{CAR_MAX_SPEED, ORANGE_TEAM};
+use crate::envs::game_match::GameConfig;
+use crate::gamestates::game_state::GameState;
+use crate::gamestates::player_data::PlayerData;
+
+use super::advanced_obs::POS_STD;
+use super::obs_builder::ObsBuilder;
+
+/// time between two steps of the requested ball prediction (12 ticks at 120 tps), the horizons are rounded to it
+const PREDICTION_DT: f32 = 0.1;
+/// length of the appended features of a single horizon
+const HORIZON_OBS_LEN: usize = 6;
+
+/// Wraps another observation builder and appends the predicted ball at several time horizons (in seconds) to the end of its observation.
+///
+/// For each horizon it adds the predicted ball position and velocity relative to the current player's car,
+/// the prediction is requested from `GameMatch` (see `ObsBuilder::ball_prediction_request`) so it is shared with any other builder that needs it.
+/// If invert is set (the default), orange players get them in the inverted frame like `DefaultObs`.
+/// The wrapped builder's frame isn't known here, so invert has to match its own invert flag (eg. both left at the default),
+/// otherwise the appended features are in a different frame than the rest of the obs.
+/// Until a prediction was given the current ball is used for every horizon.
+pub struct PredictiveObs {
+    obs_builder: Box<dyn ObsBuilder>,
+    horizon_steps: Vec<usize>,
+    pos_coef: f32,
+    vel_coef: f32,
+    invert: bool,
+    prediction: Vec<BallState>,
+}
+
+impl PredictiveObs {
+    /// horizons default to [0.5, 1., 2.] (seconds, rounded to 0.1s), pos_coef to 1 / 2300, vel_coef to 1 / CAR_MAX_SPEED and invert to true
+    pub fn new(obs_builder: Box<dyn ObsBuilder>, horizons: Option<Vec<f32>>, pos_coef: Option<f32>, vel_coef: Option<f32>, invert: Option<bool>) -> Self {
+        let horizons = horizons.unwrap_or(vec![0.5, 1., 2.]);
+        assert!(horizons.iter().all(|&horizon| horizon > 0.), "prediction horizons must be positive, got {horizons:?}");
+        PredictiveObs {
+            obs_builder,
+            horizon_steps: horizons.iter().map(|horizon| ((horizon / PREDICTION_DT).round() as usize).max(1)).collect(),
+            pos_coef: pos_coef.unwrap_or(1. / POS_STD),
+            vel_coef: vel_coef.unwrap_or(1. / CAR_MAX_SPEED),
+            invert: invert.unwrap_or(true),
+            prediction: Vec::new(),
+        }
+    }
+
+    fn add_predictions_to_obs(&self, obs: &mut Vec<f32>, player: &PlayerData, state: &GameState) {
+        let inverted = self.invert && player.team_num == ORANGE_TEAM;
+        let car = if inverted { &player.inverted_car_data } else { &player.car_data };
+        // the inverted frame negates x and y
+        let sign = if inverted { -1. } else { 1. };
+        for &steps in &self.horizon_steps {
+            let (pos, vel) = match self.prediction.get(steps - 1) {
+                Some(ball) => ([ball.pos.x, ball.pos.y, ball.pos.z], [ball.vel.x, ball.vel.y, ball.vel.z]),
+                None => (state.ball.position.into_array(), state.ball.linear_velocity.into_array()),
+            };
+            obs.extend([
+                (pos[0] * sign - car.position.x) * self.pos_coef,
+                (pos[1] * sign - car.position.y) * self.pos_coef,
+                (pos[2] - car.position.z) * self.pos_coef,
+                (vel[0] * sign - car.linear_velocity.x) * self.vel_coef,
+                (vel[1] * sign - car.linear_velocity.y) * self.vel_coef,
+                (vel[2] - car.linear_velocity.z) * self.vel_coef,
+            ]);
+        }
+    }
+}
+
+impl ObsBuilder for PredictiveObs {
+    fn reset(&mut self, initial_state: &GameState) {
+        self.prediction.clear();
+        self.obs_builder.reset(initial_state);
+    }
+
+    fn get_obs_space(&mut self) -> Vec<usize> {
+        let added = HORIZON_OBS_LEN * self.horizon_steps.len();
+        let mut obs_space = self.obs_builder.get_obs_space();
+        match obs_space.last_mut() {
+            Some(last) => *last += added,
+            None => obs_space.push(added),
+        }
+        obs_space
+    }
+
+    fn ball_prediction_request(&self) -> Option<(usize, f32)> {
+        let num_steps = self.horizon_steps.iter().copied().max().unwrap_or(0);
+        let (inner_steps, inner_dt) = match self.obs_builder.ball_prediction_request() {
+            Some(request) => request,
+            None => return (num_steps > 0).then_some((num_steps, PREDICTION_DT)),
+        };
+        assert!(inner_dt == PREDICTION_DT, "the wrapped obs builder requested a ball prediction with dt {inner_dt}, PredictiveObs needs {PREDICTION_DT}");
+        Some((num_steps.max(inner_steps), PREDICTION_DT))
+    }
+
+    fn set_ball_prediction(&mut self, prediction: &[BallState]) {
+        if let Some((inner_steps, _)) = self.obs_builder.ball_prediction_request() {
+            self.obs_builder.set_ball_prediction(&prediction[..inner_steps.min(prediction.len())]);
+        }
+        self.prediction.clear();
+        self.prediction.extend_from_slice(prediction);
+    }
+
+    fn pre_step(&mut self, state: &GameState, config: &GameConfig) {
+        self.obs_builder.pre_step(state, config);
+    }
+
+    fn build_obs(&mut self, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) -> Vec<f32> {
+        let mut obs = self.obs_builder.build_obs(player, state, config, previous_action);
+        self.add_predictions_to_obs(&mut obs, player, state);
+        obs
+    }
+
+    fn build_obs_into(&mut self, buf: &mut Vec<f32>, player: &PlayerData, state: &GameState, config: &GameConfig, previous_action: &[f32]) {
+        self.obs_builder.build_obs_into(buf, player, state, config, previous_action);
+        self.add_predictions_to_obs(buf, player, state);
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.obs_builder.set_seed(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamestates::physics_object::Position;
+    use crate::obs_builders::default_obs::{DefaultObs, BASE_OBS_LEN};
+    use rocketsim_rs::math::Vec3;
+
+    /// ball rolling along +x at speed from x=0
+    fn rolling_prediction(speed: f32, num_steps: usize) -> Vec<BallState> {
+        (1..=num_steps)
+            .map(|step| BallState {
+                pos: Vec3::new(speed * PREDICTION_DT * step as f32, 0., 93.15),
+                vel: Vec3::new(speed, 0., 0.),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn predictive_obs_follows_ball_trajectory() {
+        let state = GameState::new_test();
+        let config = GameConfig::default();
+        let mut obs_builder = PredictiveObs::new(
+            Box::new(DefaultObs::new(Some(2), Some(false), None, None, None, None)),
+            None,
+            Some(1.),
+            Some(1.),
+            None,
+        );
+        let base_len = DefaultObs::new(Some(2), Some(false), None, None, None, None).get_obs_space()[0];
+        assert_eq!(obs_builder.get_obs_space(), vec![base_len + 3 * HORIZON_OBS_LEN]);
+
+        let (num_steps, dt) = obs_builder.ball_prediction_request().unwrap();
+        assert_eq!((num_steps, dt), (20, PREDICTION_DT));
+
+        let player = &state.players[0];
+        let predicted_x = |obs_builder: &mut PredictiveObs, speed: f32| {
+            obs_builder.set_ball_prediction(&rolling_prediction(speed, num_steps));
+            obs_builder.pre_step(&state, &config);
+            let obs = obs_builder.build_obs(player, &state, &config, &[0.; 8]);
+            assert_eq!(obs.len(), base_len + 3 * HORIZON_OBS_LEN);
+            (0..3).map(|i| obs[base_len + i * HORIZON_OBS_LEN] + player.car_data.position.x).collect::<Vec<f32>>()
+        };
+
+        let slow = predicted_x(&mut obs_builder, 500.);
+        let fast = predicted_x(&mut obs_builder, 1000.);
+        for (x, expected) in slow.iter().zip([250., 500., 1000.]) {
+            assert!((x - expected).abs() < 1e-2, "predicted x was {x}, expected {expected}");
+        }
+        for (x, expected) in fast.iter().zip([500., 1000., 2000.]) {
+            assert!((x - expected).abs() < 1e-2, "predicted x was {x}, expected {expected}");
+        }
+
+        let mut buf = Vec::new();
+        obs_builder.build_obs_into(&mut buf, player, &state, &config, &[0.; 8]);
+        assert_eq!(buf, obs_builder.build_obs(player, &state, &config, &[0.; 8]));
+    }
+
+    #[test]
+    fn predictive_obs_inverts_orange() {
+        let mut state = GameState::new_test();
+        state.players[1].team_num = ORANGE_TEAM;
+        state.players[1].inverted_car_data.position = state.players[1].car_data.position.invert();
+        let config = GameConfig::default();
+        let mut obs_builder = PredictiveObs::new(Box::new(DefaultObs::default()), Some(vec![1.]), Some(1.), Some(1.), None);
+        obs_builder.set_ball_prediction(&rolling_prediction(1000., 10));
+
+        let orange = &state.players[1];
+        let obs = obs_builder.build_obs(orange, &state, &config, &[0.; 8]);
+        let features = &obs[obs.len() - HORIZON_OBS_LEN..];
+        assert!((features[0] + orange.inverted_car_data.position.x + 1000.).abs() < 1e-2);
+        assert!((features[3] + orange.inverted_car_data.linear_velocity.x + 1000.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn predictive_obs_matches_inner_frame() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 500., y: -1000., z: 200. };
+        state.inverted_ball.position = state.ball.position.invert();
+        state.players[1].team_num = ORANGE_TEAM;
+        state.players[1].inverted_car_data.position = state.players[1].car_data.position.invert();
+        let config = GameConfig::default();
+        let orange = &state.players[1];
+
+        // with the same flag on both, the appended ball is relative to the car in the frame of the wrapped obs
+        for invert in [true, false] {
+            let inner = DefaultObs::new(None, None, Some([1.; 3]), None, None, Some(invert));
+            let mut obs_builder = PredictiveObs::new(Box::new(inner), Some(vec![1.]), Some(1.), None, Some(invert));
+            let obs = obs_builder.build_obs(orange, &state, &config, &[0.; 8]);
+            let features = &obs[obs.len() - HORIZON_OBS_LEN..];
+            for i in 0..3 {
+                let expected = obs[i] - obs[BASE_OBS_LEN + i];
+                assert!((features[i] - expected).abs() < 1e-2, "invert {invert}: feature {i} was {}, expected {expected}", features[i]);
+            }
+        }
+    }
+}
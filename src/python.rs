@@ -0,0 +1,200 @@
+//! PyO3 bindings (behind the `python` feature) that expose a `Gym` to Python.
+//! 
+//! Python can't pass Rust trait objects so the components are picked by name in `MakeConfig`,
+//! observations/rewards/info are returned as lists, floats and a dict.
+//! 
+//! ```python
+//! import rlgym_sim_rs
+//! 
+//! gym = rlgym_sim_rs.Gym(rlgym_sim_rs.MakeConfig(team_size=1, timeout_steps=300))
+//! obs = gym.reset(seed=0)
+//! obs, rewards, done, info = gym.step([[1., 0., 0., 0., 0., 0., 0., 0.]] * len(obs))
+//! ```
+
+use std::{collections::HashMap, sync::Once};
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use rocketsim_rs::sim::{CarConfig, GameMode};
+
+use crate::{
+    action_parsers::{action_parser::ActionParser, continous_act::ContinuousAction, lookup_act::LookupTableAction},
+    conditionals::{
+        common_conditions::{GoalScoredCondition, TimeoutCondition},
+        extra_conditions::CombinedTerminalConditions,
+        terminal_condition::TerminalCondition,
+    },
+    envs::game_match::GameConfig,
+    gym::Gym,
+    make::{self, MakeConfig},
+    obs_builders::{advanced_obs::AdvancedObs, default_obs::DefaultObs, obs_builder::ObsBuilder},
+    reward_functions::common_rewards::misc_rewards::EventReward,
+    state_setters::default_state::DefaultState,
+};
+
+static INIT_SIM: Once = Once::new();
+
+/// (obs, rewards, done, info) as returned to Python by `Gym.step`
+type PyStepResult = (Vec<Vec<f32>>, Vec<f32>, bool, HashMap<String, f32>);
+
+/// Python side config for the gym, see `MakeConfig` and `GameConfig`. 
+/// 
+/// The obs builder is either "default" or "advanced" and the action parser is either "continuous" or "lookup". 
+/// The episode ends on a goal or after `timeout_steps` steps, the reward is an `EventReward` with the given weights.
+#[pyclass(name = "MakeConfig")]
+#[derive(Clone)]
+pub struct PyMakeConfig {
+    #[pyo3(get, set)]
+    pub team_size: usize,
+    #[pyo3(get, set)]
+    pub spawn_opponents: bool,
+    #[pyo3(get, set)]
+    pub tick_skip: usize,
+    #[pyo3(get, set)]
+    pub gravity: f32,
+    #[pyo3(get, set)]
+    pub boost_consumption: f32,
+    #[pyo3(get, set)]
+    pub timeout_steps: i64,
+    #[pyo3(get, set)]
+    pub goal_reward: f32,
+    #[pyo3(get, set)]
+    pub concede_reward: f32,
+    #[pyo3(get, set)]
+    pub touch_reward: f32,
+    #[pyo3(get, set)]
+    pub obs_builder: String,
+    #[pyo3(get, set)]
+    pub action_parser: String,
+    #[pyo3(get, set)]
+    pub use_truncation: bool,
+}
+
+#[pymethods]
+impl PyMakeConfig {
+    #[new]
+    #[pyo3(signature = (
+        team_size=1, spawn_opponents=true, tick_skip=8, gravity=1., boost_consumption=1., timeout_steps=4500, 
+        goal_reward=1., concede_reward=-1., touch_reward=0., obs_builder="default".to_string(), action_parser="continuous".to_string(), 
+        use_truncation=false
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        team_size: usize,
+        spawn_opponents: bool,
+        tick_skip: usize,
+        gravity: f32,
+        boost_consumption: f32,
+        timeout_steps: i64,
+        goal_reward: f32,
+        concede_reward: f32,
+        touch_reward: f32,
+        obs_builder: String,
+        action_parser: String,
+        use_truncation: bool,
+    ) -> Self {
+        PyMakeConfig {
+            team_size,
+            spawn_opponents,
+            tick_skip,
+            gravity,
+            boost_consumption,
+            timeout_steps,
+            goal_reward,
+            concede_reward,
+            touch_reward,
+            obs_builder,
+            action_parser,
+            use_truncation,
+        }
+    }
+}
+
+impl PyMakeConfig {
+    fn to_make_config(&self) -> PyResult<MakeConfig> {
+        let game_config = GameConfig {
+            tick_skip: self.tick_skip,
+            spawn_opponents: self.spawn_opponents,
+            team_size: self.team_size,
+            gravity: self.gravity,
+            boost_consumption: self.boost_consumption,
+            car_config: CarConfig::octane(),
+            game_mode: GameMode::Soccar,
+            blue_size: None,
+            orange_size: None,
+            car_configs: vec![],
+            shuffle_cars: false,
+            pad_opponent_obs: false,
+        };
+        let obs_builder: Box<dyn ObsBuilder> = match self.obs_builder.as_str() {
+            "default" => Box::new(DefaultObs::new(Some(self.team_size), Some(self.spawn_opponents), None, None, None, None)),
+            "advanced" => Box::new(AdvancedObs::new_with_coefs(Some(self.team_size), None, None, None)),
+            name => return Err(PyValueError::new_err(format!("unknown obs builder: {name}, expected \"default\" or \"advanced\""))),
+        };
+        let action_parser: Box<dyn ActionParser> = match self.action_parser.as_str() {
+            "continuous" => Box::new(ContinuousAction::new(None, None)),
+            "lookup" => Box::new(LookupTableAction::new()),
+            name => return Err(PyValueError::new_err(format!("unknown action parser: {name}, expected \"continuous\" or \"lookup\""))),
+        };
+        let terminal_conditions: Vec<Box<dyn TerminalCondition>> = vec![Box::new(GoalScoredCondition::new()), Box::new(TimeoutCondition::new(self.timeout_steps))];
+        Ok(MakeConfig {
+            game_config,
+            terminal_condition: Box::new(CombinedTerminalConditions::new(terminal_conditions)),
+            reward_fn: Box::new(EventReward::new(Some(self.goal_reward), None, Some(self.concede_reward), Some(self.touch_reward), None, None, None, None)),
+            obs_builder: vec![obs_builder],
+            use_single_obs: true,
+            action_parser,
+            state_setter: Box::new(DefaultState::new(None)),
+            state_modifiers: vec![],
+        })
+    }
+}
+
+/// Python wrapper of `Gym`
+#[pyclass(name = "Gym", unsendable)]
+pub struct PyGym {
+    gym: Gym,
+}
+
+#[pymethods]
+impl PyGym {
+    #[new]
+    fn new(config: PyMakeConfig) -> PyResult<Self> {
+        INIT_SIM.call_once(|| rocketsim_rs::init(None));
+        let make_config = config.to_make_config()?;
+        Ok(PyGym { gym: make::make(make_config, None, Some(config.use_truncation)) })
+    }
+
+    /// returns the observations of the new episode
+    #[pyo3(signature = (seed=None))]
+    fn reset(&mut self, seed: Option<u64>) -> Vec<Vec<f32>> {
+        self.gym.reset(None, seed, None).0
+    }
+
+    /// returns (obs, rewards, done, info), raises a ValueError if the actions don't match the agents
+    fn step(&mut self, actions: Vec<Vec<f32>>) -> PyResult<PyStepResult> {
+        let (obs, rewards, done, info, _) = self.gym.try_step(actions).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok((obs, rewards, done, info))
+    }
+
+    #[getter]
+    fn observation_space(&self) -> Vec<usize> {
+        self.gym.observation_space.clone()
+    }
+
+    #[getter]
+    fn action_space(&self) -> Vec<usize> {
+        self.gym.action_space.clone()
+    }
+}
+
+/// adds the classes to a Python module, used by the extension module and for embedding
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMakeConfig>()?;
+    m.add_class::<PyGym>()?;
+    Ok(())
+}
+
+#[pymodule]
+fn rlgym_sim_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    register(m)
+}
@@ -0,0 +1,467 @@
+//! Builds the built-in obs builders, action parsers, reward functions, terminal conditions and state setters by name,
+//! with their parameters given as a JSON object (eg. from a TOML/JSON config file), so an environment can be defined declaratively.
+//!
+//! The parameter names are the argument names of the constructors and any optional argument can be left out (or be null) to use its default.
+//! Components that wrap other components take them as nested specs of the form `{"name": "...", "params": {...}}` (`params` is optional).
+//! Unknown names, unknown params and params of the wrong type are errors, the values themselves are still checked by the constructors (which panic).
+//!
+//! ```no_run
+//! use rlgym_sim_rs::registry::{build_obs_builder, build_reward_fn};
+//! use serde_json::json;
+//!
+//! let obs_builder = build_obs_builder("default", &json!({"team_size": 2})).unwrap();
+//! let reward_fn = build_reward_fn("combined", &json!({
+//!     "reward_fns": [{"name": "event", "params": {"goal": 10.}}, {"name": "velocity_player_to_ball"}],
+//!     "weights": [1., 0.1],
+//! })).unwrap();
+//! ```
+
+use serde_json::{Map, Value};
+
+use crate::{
+    action_parsers::{
+        action_parser::ActionParser, continous_act::ContinuousAction, discrete_act::DiscreteAction, interpolated_act::InterpolatedContinuousAction, kbm_act::KBMAction,
+        logits_act::LogitsAction, lookup_act::LookupTableAction, rlbot_act::RLBotAction, sticky_act::StickyActionParser,
+    },
+    conditionals::{
+        common_conditions::{
+            BallInRegionCondition, BallStillTimeout, BallTouchedCondition, GoalScoredCondition, MercyRuleCondition, NoTouchTimeoutCondition, StuckTruncationCondition,
+            TimeoutCondition,
+        },
+        extra_conditions::{CombinedTerminalConditions, NoTouchKickoffTimeoutCondition},
+        terminal_condition::TerminalCondition,
+    },
+    common_values::{BLUE_TEAM, ORANGE_TEAM},
+    error::GymError,
+    obs_builders::{
+        action_stack_obs::ActionStackObs, advanced_obs::AdvancedObs, advanced_padder_obs::AdvancedPadderObs, default_obs::DefaultObs,
+        frame_stack_obs::FrameStackObs, obs_builder::ObsBuilder, padded_obs::PaddedObs, predictive_obs::PredictiveObs,
+    },
+    reward_functions::{
+        combined_reward::CombinedReward,
+        common_rewards::{
+            ball_goal_rewards::{BallInDefensiveHalfPenalty, GoalSpeedReward, LastTouchRewardOnGoal, LiuDistanceBallToGoalReward, VelocityBallToGoalReward},
+            misc_rewards::{
+                AirReward, AlignBallGoal, BoostPickupReward, ConstantReward, DemoReward, EventReward, ForwardVelocityReward, MaskDemoedReward, RewardLogger,
+                SaveBoostReward, ScaleReward, VelocityReward, WallProximityReward,
+            },
+            player_ball_rewards::{
+                AerialDistanceReward, DribbleReward, FaceBallReward, KickoffReward, FlipResetReward, JumpTouchReward, LiuDistancePlayerToBallReward, TouchBallReward, VelocityPlayerToBallReward,
+            },
+        },
+        normalize_reward::NormalizeReward,
+        reward_fn::RewardFn,
+        sequential_reward::SequentialReward,
+        zero_sum_reward::ZeroSumReward,
+    },
+    state_setters::{
+        default_state::DefaultState, kickoff_like_state::KickoffLikeState, random_state::RandomState, replay_setter::ReplaySetter,
+        shot_state::ShotState, state_setter::StateSetter, weighted_state_setter::WeightedSampleSetter,
+    },
+};
+
+/// names: "default", "padded", "advanced", "advanced_padder", "action_stack", "frame_stack", "predictive"
+pub fn build_obs_builder(name: &str, params: &Value) -> Result<Box<dyn ObsBuilder>, GymError> {
+    let mut p = Params::new(name, params)?;
+    let obs_builder: Box<dyn ObsBuilder> = match name {
+        "default" => Box::new(DefaultObs::new(
+            p.usize("team_size")?,
+            p.bool("spawn_opponents")?,
+            p.f32_array3("pos_coef")?,
+            p.f32("lin_vel_coef")?,
+            p.f32("ang_vel_coef")?,
+            p.bool("invert")?,
+        )),
+        "padded" => Box::new(PaddedObs::new(
+            p.usize("max_players")?,
+            p.f32_array3("pos_coef")?,
+            p.f32("lin_vel_coef")?,
+            p.f32("ang_vel_coef")?,
+            p.bool("invert")?,
+        )),
+        "advanced" => Box::new(AdvancedObs::new_with_coefs(p.usize("team_size")?, p.f32("pos_coef")?, p.f32("vel_coef")?, p.f32("ang_coef")?)),
+        "advanced_padder" => Box::new(AdvancedPadderObs::new(p.usize("team_size")?, p.f32("pos_coef")?, p.f32("vel_coef")?, p.f32("ang_coef")?)),
+        "action_stack" => {
+            let obs_builder = p.spec("obs_builder", build_obs_builder)?;
+            Box::new(ActionStackObs::new(obs_builder, p.usize("action_len")?))
+        }
+        "frame_stack" => {
+            let obs_builder = p.spec("obs_builder", build_obs_builder)?;
+            Box::new(FrameStackObs::new(obs_builder, p.usize("stack_size")?))
+        }
+        "predictive" => {
+            let obs_builder = p.spec("obs_builder", build_obs_builder)?;
+            Box::new(PredictiveObs::new(obs_builder, p.f32_vec("horizons")?, p.f32("pos_coef")?, p.f32("vel_coef")?, p.bool("invert")?))
+        }
+        _ => return Err(unknown("obs builder", name)),
+    };
+    p.finish()?;
+    Ok(obs_builder)
+}
+
+/// names: "discrete", "continuous", "interpolated_continuous", "kbm", "lookup", "rlbot", "sticky", "logits"
+pub fn build_action_parser(name: &str, params: &Value) -> Result<Box<dyn ActionParser>, GymError> {
+    let mut p = Params::new(name, params)?;
+    let action_parser: Box<dyn ActionParser> = match name {
+        "discrete" => Box::new(DiscreteAction::new(p.usize("n_bins")?, p.usize("hold_steps")?)),
+        "continuous" => Box::new(ContinuousAction::new(p.bool("squash")?, p.usize("hold_steps")?)),
+        "interpolated_continuous" => Box::new(InterpolatedContinuousAction::new(p.bool("squash")?)),
+        "kbm" => Box::new(KBMAction::new(p.usize("n_bins")?, p.bool("discrete")?)),
+        "lookup" => Box::new(LookupTableAction::new()),
+        "rlbot" => Box::new(RLBotAction::new()),
+        "sticky" => {
+            let action_parser = p.spec("action_parser", build_action_parser)?;
+            let sticky_action_prob = p.required("sticky_action_prob", Params::f32)?;
+            Box::new(StickyActionParser::new(action_parser, sticky_action_prob, p.u64("seed")?))
+        }
+        "logits" => Box::new(LogitsAction::new(p.spec("action_parser", build_action_parser)?)),
+        _ => return Err(unknown("action parser", name)),
+    };
+    p.finish()?;
+    Ok(action_parser)
+}
+
+/// names: "event", "velocity", "forward_velocity", "demo", "air", "save_boost", "boost_pickup", "wall_proximity", "align_ball_goal",
+/// "constant", "scale", "logger", "combined", "sequential", "zero_sum", "velocity_player_to_ball", "face_ball", "liu_distance_player_to_ball",
+/// "touch_ball", "jump_touch", "flip_reset", "aerial_distance", "dribble", "kickoff", "velocity_ball_to_goal", "liu_distance_ball_to_goal", "last_touch_on_goal",
+/// "goal_speed", "ball_in_defensive_half", "mask_demoed", "normalize"
+pub fn build_reward_fn(name: &str, params: &Value) -> Result<Box<dyn RewardFn>, GymError> {
+    let mut p = Params::new(name, params)?;
+    let reward_fn: Box<dyn RewardFn> = match name {
+        "event" => Box::new(EventReward::new(
+            p.f32("goal")?,
+            p.f32("team_goal")?,
+            p.f32("concede")?,
+            p.f32("touch")?,
+            p.f32("shot")?,
+            p.f32("save")?,
+            p.f32("demo")?,
+            p.f32("boost_pickup")?,
+        )),
+        "velocity" => Box::new(VelocityReward::new(p.bool("negative")?)),
+        "forward_velocity" => Box::new(ForwardVelocityReward::new()),
+        "demo" => Box::new(DemoReward::new(p.f32("demo_reward")?, p.f32("victim_penalty")?)),
+        "air" => Box::new(AirReward::new(p.bool("negate")?)),
+        "save_boost" => Box::new(SaveBoostReward::new()),
+        "boost_pickup" => Box::new(BoostPickupReward::new(p.f32("scale")?, p.f32("min_gain")?)),
+        "wall_proximity" => Box::new(WallProximityReward::new(p.f32("penalty")?, p.f32("activation_dist")?)),
+        "align_ball_goal" => Box::new(AlignBallGoal::new(p.f32("defense")?, p.f32("offense")?)),
+        "constant" => Box::new(ConstantReward::new(p.f32("value")?)),
+        "scale" => {
+            let reward_fn = p.spec("reward_fn", build_reward_fn)?;
+            Box::new(ScaleReward::new(reward_fn, p.required("factor", Params::f32)?))
+        }
+        "mask_demoed" => Box::new(MaskDemoedReward::new(p.spec("reward_fn", build_reward_fn)?)),
+        "logger" => {
+            let reward_fn = p.spec("reward_fn", build_reward_fn)?;
+            Box::new(RewardLogger::new(reward_fn, p.string("name")?))
+        }
+        "combined" => {
+            let reward_fns = p.specs("reward_fns", build_reward_fn)?;
+            let weights = p.required("weights", Params::f32_vec)?;
+            if weights.len() != reward_fns.len() {
+                return Err(p.invalid("weights", format!("expected {} weights (one per reward fn), got {}", reward_fns.len(), weights.len())));
+            }
+            Box::new(CombinedReward::new(reward_fns, weights))
+        }
+        "sequential" => Box::new(SequentialReward::new(p.specs("reward_fns", build_reward_fn)?)),
+        "zero_sum" => {
+            let reward_fn = p.spec("reward_fn", build_reward_fn)?;
+            let team_spirit = p.required("team_spirit", Params::f32)?;
+            let opp_scale = p.required("opp_scale", Params::f32)?;
+            Box::new(ZeroSumReward::new(reward_fn, team_spirit, opp_scale))
+        }
+        "normalize" => {
+            let reward_fn = p.spec("reward_fn", build_reward_fn)?;
+            let clip = p.required("clip", Params::f32)?;
+            if clip <= 0. {
+                return Err(p.invalid("clip", "expected a positive bound".to_string()));
+            }
+            Box::new(NormalizeReward::new(reward_fn, clip))
+        }
+        "velocity_player_to_ball" => Box::new(VelocityPlayerToBallReward::new(p.bool("use_scalar_projection")?)),
+        "face_ball" => Box::new(FaceBallReward::new()),
+        "liu_distance_player_to_ball" => Box::new(LiuDistancePlayerToBallReward::new(p.f32("dispersion")?, p.f32("exponent")?)),
+        "touch_ball" => Box::new(TouchBallReward::new(p.required("aerial_weight", Params::f32)?, p.f32("exponent")?)),
+        "jump_touch" => Box::new(JumpTouchReward::new(p.required("min_height", Params::f32)?)),
+        "flip_reset" => Box::new(FlipResetReward::new(p.f32("min_alignment")?)),
+        "kickoff" => Box::new(KickoffReward::new(p.f32("center_radius")?)),
+        "dribble" => Box::new(DribbleReward::new(p.f32("max_xy_dist")?, p.f32("min_height")?, p.f32("max_height")?)),
+        "aerial_distance" => Box::new(AerialDistanceReward::new(p.required("height_scale", Params::f32)?, p.required("distance_scale", Params::f32)?)),
+        "velocity_ball_to_goal" => Box::new(VelocityBallToGoalReward::new(p.bool("own_goal")?, p.bool("use_scalar_projection")?)),
+        "liu_distance_ball_to_goal" => Box::new(LiuDistanceBallToGoalReward::new(p.bool("own_goal")?, p.f32("dispersion")?, p.f32("exponent")?)),
+        "last_touch_on_goal" => Box::new(LastTouchRewardOnGoal::new(p.f32("goal_reward")?, p.f32("own_goal_reward")?)),
+        "goal_speed" => Box::new(GoalSpeedReward::new(p.f32("weight")?, p.f32("concede_weight")?)),
+        "ball_in_defensive_half" => Box::new(BallInDefensiveHalfPenalty::new(p.f32("penalty")?)),
+        _ => return Err(unknown("reward fn", name)),
+    };
+    p.finish()?;
+    Ok(reward_fn)
+}
+
+/// names: "timeout", "no_touch_timeout", "no_touch_kickoff_timeout", "goal_scored", "ball_touched", "mercy_rule", "ball_in_region",
+/// "stuck", "ball_still", "combined"
+pub fn build_terminal_condition(name: &str, params: &Value) -> Result<Box<dyn TerminalCondition>, GymError> {
+    let mut p = Params::new(name, params)?;
+    let condition: Box<dyn TerminalCondition> = match name {
+        "timeout" => Box::new(TimeoutCondition::new(p.required("max_steps", Params::i64)?)),
+        "no_touch_timeout" => Box::new(NoTouchTimeoutCondition::new(p.required("max_steps", Params::i64)?)),
+        "no_touch_kickoff_timeout" => Box::new(NoTouchKickoffTimeoutCondition::new(p.required("max_steps", Params::i64)?)),
+        "goal_scored" => Box::new(GoalScoredCondition::new()),
+        "ball_touched" => {
+            let team = match p.u64("team")? {
+                Some(team) => Some(u8::try_from(team).map_err(|_| p.invalid("team", "expected 0 or 1".to_string()))?),
+                None => None,
+            };
+            Box::new(BallTouchedCondition::new(team))
+        }
+        "mercy_rule" => {
+            let diff = p.required("diff", Params::i64)?;
+            let diff = i32::try_from(diff).ok().filter(|diff| *diff > 0).ok_or_else(|| p.invalid("diff", "expected a positive goal difference".to_string()))?;
+            Box::new(MercyRuleCondition::new(diff))
+        }
+        "ball_in_region" => {
+            let min = p.required("min", Params::f32_array3)?;
+            let max = p.required("max", Params::f32_array3)?;
+            if min.iter().zip(&max).any(|(min, max)| min > max) {
+                return Err(p.invalid("max", "expected every max bound to be at least the min bound".to_string()));
+            }
+            Box::new(BallInRegionCondition::new(min, max))
+        }
+        "stuck" => {
+            let steps = p.required("steps", Params::usize)?;
+            if steps == 0 {
+                return Err(p.invalid("steps", "expected at least 1 step".to_string()));
+            }
+            Box::new(StuckTruncationCondition::new(steps, p.f32("min_movement")?))
+        }
+        "ball_still" => {
+            let steps = p.required("steps", Params::usize)?;
+            if steps == 0 {
+                return Err(p.invalid("steps", "expected at least 1 step".to_string()));
+            }
+            Box::new(BallStillTimeout::new(steps, p.f32("radius")?))
+        }
+        "combined" => Box::new(CombinedTerminalConditions::new(p.specs("conditions", build_terminal_condition)?)),
+        _ => return Err(unknown("terminal condition", name)),
+    };
+    p.finish()?;
+    Ok(condition)
+}
+
+/// names: "default", "random", "kickoff_like", "shot", "replay", "weighted"
+pub fn build_state_setter(name: &str, params: &Value) -> Result<Box<dyn StateSetter>, GymError> {
+    let mut p = Params::new(name, params)?;
+    let state_setter: Box<dyn StateSetter> = match name {
+        "default" => Box::new(DefaultState::new(p.u64("seed")?)),
+        "random" => Box::new(RandomState::new(
+            p.bool("ball_rand_speed")?,
+            p.bool("cars_rand_speed")?,
+            p.bool("cars_on_ground")?,
+            p.bool("rand_boost")?,
+            p.u64("seed")?,
+        )),
+        "kickoff_like" => Box::new(KickoffLikeState::new(p.u64("seed")?)),
+        "shot" => {
+            let shooting_team = match p.i64("shooting_team")? {
+                Some(team) if team == BLUE_TEAM as i64 || team == ORANGE_TEAM as i64 => Some(team as i32),
+                Some(_) => return Err(p.invalid("shooting_team", format!("expected {BLUE_TEAM} (blue) or {ORANGE_TEAM} (orange)"))),
+                None => None,
+            };
+            Box::new(ShotState::new(shooting_team, p.f32("aim_noise")?, p.u64("seed")?))
+        }
+        "replay" => {
+            let file = p.required("file", Params::string)?;
+            Box::new(ReplaySetter::new(&file, p.bool("random_boost")?, p.bool("random_pads")?))
+        }
+        "weighted" => {
+            let state_setters = p.specs("state_setters", build_state_setter)?;
+            let weights: Vec<f64> = p.required("weights", Params::f32_vec)?.into_iter().map(f64::from).collect();
+            if weights.len() != state_setters.len() {
+                return Err(p.invalid("weights", format!("expected {} weights (one per state setter), got {}", state_setters.len(), weights.len())));
+            }
+            Box::new(WeightedSampleSetter::new(state_setters, weights, p.u64("seed")?))
+        }
+        _ => return Err(unknown("state setter", name)),
+    };
+    p.finish()?;
+    Ok(state_setter)
+}
+
+fn unknown(kind: &'static str, name: &str) -> GymError {
+    GymError::UnknownComponent { kind, name: name.to_string() }
+}
+
+/// the params of one component, remembers which keys were read so that unknown (eg. misspelled) keys are an error in `finish`
+struct Params<'a> {
+    component: &'a str,
+    map: Option<&'a Map<String, Value>>,
+    used: Vec<&'static str>,
+}
+
+impl<'a> Params<'a> {
+    fn new(component: &'a str, params: &'a Value) -> Result<Self, GymError> {
+        let map = match params {
+            Value::Null => None,
+            Value::Object(map) => Some(map),
+            _ => {
+                return Err(GymError::InvalidParam {
+                    component: component.to_string(),
+                    param: "params".to_string(),
+                    reason: "expected an object or null".to_string(),
+                })
+            }
+        };
+        Ok(Params { component, map, used: Vec::new() })
+    }
+
+    fn invalid(&self, param: &str, reason: String) -> GymError {
+        GymError::InvalidParam { component: self.component.to_string(), param: param.to_string(), reason }
+    }
+
+    /// the value of the key, a null value is the same as a missing key
+    fn get(&mut self, key: &'static str) -> Option<&'a Value> {
+        self.used.push(key);
+        self.map.and_then(|map| map.get(key)).filter(|val| !val.is_null())
+    }
+
+    fn parse<T>(&mut self, key: &'static str, expected: &str, parse_fn: impl FnOnce(&'a Value) -> Option<T>) -> Result<Option<T>, GymError> {
+        match self.get(key) {
+            Some(val) => parse_fn(val).map(Some).ok_or_else(|| self.invalid(key, format!("expected {expected}, got {val}"))),
+            None => Ok(None),
+        }
+    }
+
+    fn required<T>(&mut self, key: &'static str, getter: fn(&mut Self, &'static str) -> Result<Option<T>, GymError>) -> Result<T, GymError> {
+        getter(self, key)?.ok_or_else(|| self.invalid(key, "missing required param".to_string()))
+    }
+
+    fn f32(&mut self, key: &'static str) -> Result<Option<f32>, GymError> {
+        self.parse(key, "a number", |val| val.as_f64().map(|val| val as f32))
+    }
+
+    fn usize(&mut self, key: &'static str) -> Result<Option<usize>, GymError> {
+        self.parse(key, "a non-negative integer", |val| val.as_u64().and_then(|val| usize::try_from(val).ok()))
+    }
+
+    fn u64(&mut self, key: &'static str) -> Result<Option<u64>, GymError> {
+        self.parse(key, "a non-negative integer", Value::as_u64)
+    }
+
+    fn i64(&mut self, key: &'static str) -> Result<Option<i64>, GymError> {
+        self.parse(key, "an integer", Value::as_i64)
+    }
+
+    fn bool(&mut self, key: &'static str) -> Result<Option<bool>, GymError> {
+        self.parse(key, "a bool", Value::as_bool)
+    }
+
+    fn string(&mut self, key: &'static str) -> Result<Option<String>, GymError> {
+        self.parse(key, "a string", |val| val.as_str().map(str::to_string))
+    }
+
+    fn f32_vec(&mut self, key: &'static str) -> Result<Option<Vec<f32>>, GymError> {
+        self.parse(key, "an array of numbers", |val| {
+            val.as_array()?.iter().map(|val| val.as_f64().map(|val| val as f32)).collect()
+        })
+    }
+
+    fn f32_array3(&mut self, key: &'static str) -> Result<Option<[f32; 3]>, GymError> {
+        match self.f32_vec(key)? {
+            Some(vals) => vals.try_into().map(Some).map_err(|_| self.invalid(key, "expected an array of 3 numbers".to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// a required nested component spec
+    fn spec<T>(&mut self, key: &'static str, build_fn: fn(&str, &Value) -> Result<T, GymError>) -> Result<T, GymError> {
+        match self.get(key) {
+            Some(spec) => self.build_spec(key, spec, build_fn),
+            None => Err(self.invalid(key, "missing required param".to_string())),
+        }
+    }
+
+    /// a required array of nested component specs
+    fn specs<T>(&mut self, key: &'static str, build_fn: fn(&str, &Value) -> Result<T, GymError>) -> Result<Vec<T>, GymError> {
+        match self.get(key) {
+            Some(Value::Array(specs)) => specs.iter().map(|spec| self.build_spec(key, spec, build_fn)).collect(),
+            Some(_) => Err(self.invalid(key, "expected an array of component specs".to_string())),
+            None => Err(self.invalid(key, "missing required param".to_string())),
+        }
+    }
+
+    fn build_spec<T>(&self, key: &str, spec: &Value, build_fn: fn(&str, &Value) -> Result<T, GymError>) -> Result<T, GymError> {
+        let invalid_spec = || self.invalid(key, format!("expected a component spec ({{\"name\": ..., \"params\": ...}}), got {spec}"));
+        let spec = spec.as_object().ok_or_else(invalid_spec)?;
+        if spec.keys().any(|spec_key| spec_key != "name" && spec_key != "params") {
+            return Err(invalid_spec());
+        }
+        let name = spec.get("name").and_then(Value::as_str).ok_or_else(invalid_spec)?;
+        build_fn(name, spec.get("params").unwrap_or(&Value::Null))
+    }
+
+    /// errors on any key that was not read by the component
+    fn finish(self) -> Result<(), GymError> {
+        if let Some(map) = self.map {
+            if let Some(key) = map.keys().find(|key| !self.used.contains(&key.as_str())) {
+                return Err(self.invalid(key, "unknown param".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn default_obs_by_name() {
+        let mut obs_builder = build_obs_builder("default", &json!({"team_size": 2, "spawn_opponents": true})).unwrap();
+        let mut expected = DefaultObs::new(Some(2), Some(true), None, None, None, None);
+        assert_eq!(obs_builder.get_obs_space(), expected.get_obs_space());
+
+        let mut obs_builder = build_obs_builder("default", &Value::Null).unwrap();
+        assert_eq!(obs_builder.get_obs_space(), DefaultObs::default().get_obs_space());
+    }
+
+    #[test]
+    fn nested_specs() {
+        let mut reward_fn = build_reward_fn(
+            "combined",
+            &json!({
+                "reward_fns": [{"name": "constant", "params": {"value": 2.}}, {"name": "constant"}],
+                "weights": [0.5, 3.],
+            }),
+        )
+        .unwrap();
+        let state = crate::gamestates::game_state::GameState::new_test();
+        reward_fn.reset(&state, None);
+        // 0.5 * 2 + 3 * 1 (the default constant)
+        assert_eq!(reward_fn.get_reward(&state.players[0], &state), 4.);
+
+        let mut action_parser = build_action_parser("sticky", &json!({"action_parser": {"name": "discrete"}, "sticky_action_prob": 0.5})).unwrap();
+        assert_eq!(action_parser.get_action_space(), vec![3, 3, 3, 3, 3, 2, 2, 2]);
+        assert!(build_terminal_condition("combined", &json!({"conditions": [{"name": "goal_scored"}, {"name": "timeout", "params": {"max_steps": 10}}]})).is_ok());
+        assert!(build_state_setter("weighted", &json!({"state_setters": [{"name": "default"}, {"name": "random"}], "weights": [1., 1.]})).is_ok());
+    }
+
+    #[test]
+    fn registry_errors() {
+        assert_eq!(
+            build_obs_builder("nope", &Value::Null).err(),
+            Some(GymError::UnknownComponent { kind: "obs builder", name: "nope".to_string() })
+        );
+        let invalid_param = |result: Result<Box<dyn RewardFn>, GymError>| match result {
+            Err(GymError::InvalidParam { param, .. }) => param,
+            _ => panic!("expected an invalid param error"),
+        };
+        assert_eq!(invalid_param(build_reward_fn("constant", &json!({"valeu": 1.}))), "valeu");
+        assert_eq!(invalid_param(build_reward_fn("constant", &json!({"value": "1"}))), "value");
+        assert_eq!(invalid_param(build_reward_fn("scale", &json!({"reward_fn": {"name": "constant"}}))), "factor");
+        assert_eq!(invalid_param(build_reward_fn("combined", &json!({"reward_fns": [{"name": "constant"}], "weights": []}))), "weights");
+        assert!(build_terminal_condition("timeout", &json!({})).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::{
     gamestates::{game_state::GameState, player_data::PlayerData},
     math::element_mult_vec,
@@ -16,30 +18,106 @@ impl CombinedReward {
         assert!(reward_structs.len() == reward_weights.len(), "reward functions did not match reward weights");
         CombinedReward { reward_structs, reward_weights }
     }
+
+    /// convenience constructor for (reward function, weight) pairs
+    pub fn from_zipped(rewards_and_weights: Vec<(Box<dyn RewardFn>, f32)>) -> Self {
+        let (reward_structs, reward_weights) = rewards_and_weights.into_iter().unzip();
+        CombinedReward::new(reward_structs, reward_weights)
+    }
 }
 
 impl RewardFn for CombinedReward {
-    fn reset(&mut self, initial_state: &GameState, _: Option<usize>) {
+    fn reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
         for struc in &mut self.reward_structs {
-            struc.reset(initial_state, None);
+            struc.reset(initial_state, reward_stage);
+        }
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        for struc in &mut self.reward_structs {
+            struc.pre_step(state);
         }
     }
 
     fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
-        let mut rewards = Vec::<f32>::new();
+        let mut rewards = Vec::<f32>::with_capacity(self.reward_structs.len());
         for struc in &mut self.reward_structs {
             rewards.push(struc.get_reward(player, state));
         }
         let ret = element_mult_vec(&rewards, &self.reward_weights);
-        return ret.iter().sum();
+        ret.iter().sum()
     }
 
     fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
-        let mut rewards = Vec::<f32>::new();
+        let mut rewards = Vec::<f32>::with_capacity(self.reward_structs.len());
         for struc in &mut self.reward_structs {
-            rewards.push(struc.get_reward(player, state));
+            rewards.push(struc.get_final_reward(player, state));
         }
         let ret = element_mult_vec(&rewards, &self.reward_weights);
-        return ret.iter().sum();
+        ret.iter().sum()
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        for struc in &mut self.reward_structs {
+            struc.set_seed(seed);
+        }
+    }
+
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        let mut stats = HashMap::new();
+        for struc in &mut self.reward_structs {
+            stats.extend(struc.drain_stats());
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    struct CountingReward {
+        value: f32,
+        pre_steps: Rc<Cell<usize>>,
+    }
+
+    impl RewardFn for CountingReward {
+        fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+        fn pre_step(&mut self, _state: &GameState) {
+            self.pre_steps.set(self.pre_steps.get() + 1);
+        }
+
+        fn get_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+            self.value
+        }
+
+        fn get_final_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+            self.value * 10.
+        }
+    }
+
+    #[test]
+    fn combined_reward_weighted_sum() {
+        let pre_steps = Rc::new(Cell::new(0));
+        let mut reward = CombinedReward::from_zipped(vec![
+            (Box::new(CountingReward { value: 1., pre_steps: pre_steps.clone() }), 2.),
+            (Box::new(CountingReward { value: 3., pre_steps: pre_steps.clone() }), 0.5),
+        ]);
+        let state = GameState::new_test();
+
+        reward.reset(&state, None);
+        reward.pre_step(&state);
+        assert_eq!(pre_steps.get(), 2, "pre_step was not called exactly once per child");
+        assert_eq!(reward.get_reward(&state.players[0], &state), 3.5);
+        assert_eq!(reward.get_final_reward(&state.players[0], &state), 35.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn combined_reward_mismatched_lengths() {
+        CombinedReward::new(vec![], vec![1.]);
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    common_values::{BALL_MAX_SPEED, BLUE_GOAL_BACK, BLUE_TEAM, ORANGE_GOAL_BACK, ORANGE_TEAM},
+    common_values::{BACK_NET_Y, BACK_WALL_Y, BALL_MAX_SPEED, BALL_RADIUS, BLUE_GOAL_BACK, BLUE_TEAM, CAR_MAX_SPEED, ORANGE_GOAL_BACK, ORANGE_TEAM},
     gamestates::{game_state::GameState, physics_object::Position, player_data::PlayerData},
     reward_functions::reward_fn::RewardFn,
 };
@@ -50,3 +50,329 @@ impl RewardFn for VelocityBallToGoalReward {
         self.get_reward(player, state)
     }
 }
+
+/// Rewards the ball being close to the opponent goal with `exp(-dist / (CAR_MAX_SPEED * dispersion)) ^ exponent`, 
+/// where dist is the distance from the ball to the back of the net minus the depth of the net and the ball radius (so it is 1 when the ball reaches the goal line and above 1 past it).
+/// 
+/// If own_goal is set, the same kernel for the player's own goal is subtracted.
+pub struct LiuDistanceBallToGoalReward {
+    own_goal: bool,
+    dispersion: f32,
+    exponent: f32,
+}
+
+impl LiuDistanceBallToGoalReward {
+    /// default: own_goal=false, dispersion=1., exponent=1.
+    pub fn new(own_goal: Option<bool>, dispersion: Option<f32>, exponent: Option<f32>) -> Self {
+        let own_goal = own_goal.unwrap_or(false);
+        let dispersion = dispersion.unwrap_or(1.);
+        let exponent = exponent.unwrap_or(1.);
+        LiuDistanceBallToGoalReward { own_goal, dispersion, exponent }
+    }
+
+    fn goal_kernel(&self, ball: Position, goal_back: Position) -> f32 {
+        let dist = (ball - goal_back).norm() - (BACK_NET_Y - BACK_WALL_Y + BALL_RADIUS);
+        (-dist / (CAR_MAX_SPEED * self.dispersion)).exp().powf(self.exponent)
+    }
+}
+
+impl Default for LiuDistanceBallToGoalReward {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl RewardFn for LiuDistanceBallToGoalReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let (objective, own) = if player.team_num == BLUE_TEAM {
+            (ORANGE_GOAL_BACK, BLUE_GOAL_BACK)
+        } else {
+            (BLUE_GOAL_BACK, ORANGE_GOAL_BACK)
+        };
+
+        let rew = self.goal_kernel(state.ball.position, objective);
+        if self.own_goal {
+            rew - self.goal_kernel(state.ball.position, own)
+        } else {
+            rew
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Gives `goal_reward` to the player that last touched the ball (`GameState.last_touch_car_id`) on the step that their team scored, 
+/// everyone else gets 0. If the last touch was by a player of the conceding team (an own goal) that player gets `own_goal_reward` instead.
+pub struct LastTouchRewardOnGoal {
+    goal_reward: f32,
+    own_goal_reward: f32,
+    prev_scores: (i32, i32),
+    scoring_team: Option<i32>,
+}
+
+impl LastTouchRewardOnGoal {
+    /// default: goal_reward=1., own_goal_reward=-1.
+    pub fn new(goal_reward: Option<f32>, own_goal_reward: Option<f32>) -> Self {
+        LastTouchRewardOnGoal {
+            goal_reward: goal_reward.unwrap_or(1.),
+            own_goal_reward: own_goal_reward.unwrap_or(-1.),
+            prev_scores: (0, 0),
+            scoring_team: None,
+        }
+    }
+}
+
+impl Default for LastTouchRewardOnGoal {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RewardFn for LastTouchRewardOnGoal {
+    fn reset(&mut self, initial_state: &GameState, _: Option<usize>) {
+        self.prev_scores = (initial_state.blue_score, initial_state.orange_score);
+        self.scoring_team = None;
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        let (prev_blue, prev_orange) = self.prev_scores;
+        self.scoring_team = if state.blue_score > prev_blue {
+            Some(BLUE_TEAM)
+        } else if state.orange_score > prev_orange {
+            Some(ORANGE_TEAM)
+        } else {
+            None
+        };
+        self.prev_scores = (state.blue_score, state.orange_score);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        match self.scoring_team {
+            Some(team) if state.last_touch_car_id == Some(player.car_id) => {
+                if player.team_num == team {
+                    self.goal_reward
+                } else {
+                    self.own_goal_reward
+                }
+            }
+            _ => 0.,
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards goals by how hard they were hit, on the step a team scores its players get the ball speed normalized by `BALL_MAX_SPEED` times `weight`
+/// and the players of the conceding team get it times `concede_weight`.
+/// 
+/// The ball can already have been slowed down by the net on the step the goal is detected, so the speed used is the larger of
+/// the speed on that step and on the step before it.
+pub struct GoalSpeedReward {
+    weight: f32,
+    concede_weight: f32,
+    prev_scores: (i32, i32),
+    prev_ball_speed: f32,
+    scoring_team: Option<i32>,
+    goal_speed: f32,
+}
+
+impl GoalSpeedReward {
+    /// default: weight=1., concede_weight=-1.
+    pub fn new(weight: Option<f32>, concede_weight: Option<f32>) -> Self {
+        GoalSpeedReward {
+            weight: weight.unwrap_or(1.),
+            concede_weight: concede_weight.unwrap_or(-1.),
+            prev_scores: (0, 0),
+            prev_ball_speed: 0.,
+            scoring_team: None,
+            goal_speed: 0.,
+        }
+    }
+}
+
+impl Default for GoalSpeedReward {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RewardFn for GoalSpeedReward {
+    fn reset(&mut self, initial_state: &GameState, _: Option<usize>) {
+        self.prev_scores = (initial_state.blue_score, initial_state.orange_score);
+        self.prev_ball_speed = initial_state.ball.linear_velocity.norm();
+        self.scoring_team = None;
+        self.goal_speed = 0.;
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        let (prev_blue, prev_orange) = self.prev_scores;
+        let ball_speed = state.ball.linear_velocity.norm();
+        self.scoring_team = if state.blue_score > prev_blue {
+            Some(BLUE_TEAM)
+        } else if state.orange_score > prev_orange {
+            Some(ORANGE_TEAM)
+        } else {
+            None
+        };
+        self.goal_speed = ball_speed.max(self.prev_ball_speed) / BALL_MAX_SPEED;
+        self.prev_scores = (state.blue_score, state.orange_score);
+        self.prev_ball_speed = ball_speed;
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+        match self.scoring_team {
+            Some(team) if player.team_num == team => self.goal_speed * self.weight,
+            Some(_) => self.goal_speed * self.concede_weight,
+            None => 0.,
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Penalizes every step the ball spends in the player's defensive half (y < 0 for blue, y > 0 for orange) with `-penalty`,
+/// to push toward a more aggressive playstyle.
+pub struct BallInDefensiveHalfPenalty {
+    penalty: f32,
+}
+
+impl BallInDefensiveHalfPenalty {
+    /// default: penalty=0.01
+    pub fn new(penalty: Option<f32>) -> Self {
+        BallInDefensiveHalfPenalty { penalty: penalty.unwrap_or(0.01) }
+    }
+}
+
+impl Default for BallInDefensiveHalfPenalty {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl RewardFn for BallInDefensiveHalfPenalty {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let ball_y = state.ball.position.y;
+        let defensive = if player.team_num == BLUE_TEAM { ball_y < 0. } else { ball_y > 0. };
+        if defensive {
+            -self.penalty
+        } else {
+            0.
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_values::GOAL_HEIGHT;
+    use crate::gamestates::physics_object::Velocity;
+
+    #[test]
+    fn liu_distance_ball_near_opponent_goal() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 0., y: BACK_WALL_Y - BALL_RADIUS, z: GOAL_HEIGHT / 2. };
+
+        let mut reward = LiuDistanceBallToGoalReward::new(None, None, None);
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 1.).abs() < 1e-3, "ball on opponent goal line reward was {val}, expected ~1.0");
+
+        // the own goal is far away so subtracting it barely changes the reward
+        let mut reward = LiuDistanceBallToGoalReward::new(Some(true), None, None);
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!(val > 0.9 && val < 1., "own goal reward was {val}, expected just under 1.0");
+
+        // the orange player's opponent goal is on the other side
+        let mut orange = state.players[0];
+        orange.team_num = ORANGE_TEAM;
+        let val = reward.get_reward(&orange, &state);
+        assert!(val < -0.9, "orange reward was {val}, expected ~-1.0");
+    }
+
+    #[test]
+    fn last_touch_goal_credits_toucher() {
+        let mut state = GameState::new_test();
+        let mut reward = LastTouchRewardOnGoal::new(None, Some(-0.5));
+        reward.reset(&state, None);
+        let rewards = |reward: &mut LastTouchRewardOnGoal, state: &GameState| {
+            reward.pre_step(state);
+            state.players.iter().map(|p| reward.get_reward(p, state)).collect::<Vec<_>>()
+        };
+
+        // touch without a goal
+        state.last_touch_car_id = Some(1);
+        assert_eq!(rewards(&mut reward, &state), vec![0., 0.]);
+        // blue (car 1) scores
+        state.blue_score = 1;
+        assert_eq!(rewards(&mut reward, &state), vec![1., 0.]);
+        // only on the step of the goal
+        assert_eq!(rewards(&mut reward, &state), vec![0., 0.]);
+        // own goal by car 1 for orange
+        state.orange_score = 1;
+        assert_eq!(rewards(&mut reward, &state), vec![-0.5, 0.]);
+    }
+
+    #[test]
+    fn goal_speed_fast_goal_beats_slow_goal() {
+        let goal_rewards = |ball_speed: f32| {
+            let mut state = GameState::new_test();
+            state.players[1].team_num = ORANGE_TEAM;
+            let mut reward = GoalSpeedReward::new(None, Some(-0.5));
+            reward.reset(&state, None);
+            state.ball.linear_velocity = Velocity { x: 0., y: ball_speed, z: 0. };
+            reward.pre_step(&state);
+            assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+            // the net slowed the ball down on the step the goal was detected
+            state.blue_score += 1;
+            state.ball.linear_velocity = Velocity { x: 0., y: 100., z: 0. };
+            reward.pre_step(&state);
+            let rewards: Vec<f32> = state.players.iter().map(|p| reward.get_reward(p, &state)).collect();
+            // only on the step of the goal
+            reward.pre_step(&state);
+            assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+            rewards
+        };
+
+        let fast = goal_rewards(BALL_MAX_SPEED / 2.);
+        let slow = goal_rewards(BALL_MAX_SPEED / 4.);
+        assert_eq!(fast, vec![0.5, -0.25]);
+        assert_eq!(slow, vec![0.25, -0.125]);
+        assert!(fast[0] > slow[0]);
+        assert!(fast[1] < slow[1]);
+    }
+
+    #[test]
+    fn defensive_half_penalty_is_team_aware() {
+        let mut state = GameState::new_test();
+        state.players[1].team_num = ORANGE_TEAM;
+        let mut reward = BallInDefensiveHalfPenalty::new(Some(0.5));
+        reward.reset(&state, None);
+        let rewards = |reward: &mut BallInDefensiveHalfPenalty, state: &GameState| {
+            state.players.iter().map(|p| reward.get_reward(p, state)).collect::<Vec<_>>()
+        };
+
+        // ball on the blue side
+        state.ball.position = Position { x: 0., y: -2000., z: BALL_RADIUS };
+        assert_eq!(rewards(&mut reward, &state), vec![-0.5, 0.]);
+        // ball on the orange side
+        state.ball.position.y = 2000.;
+        assert_eq!(rewards(&mut reward, &state), vec![0., -0.5]);
+        // nobody is penalized on the center line
+        state.ball.position.y = 0.;
+        assert_eq!(rewards(&mut reward, &state), vec![0., 0.]);
+    }
+}
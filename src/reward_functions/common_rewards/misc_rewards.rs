@@ -1,17 +1,26 @@
 use crate::{
-    common_values::{BLUE_TEAM, CAR_MAX_SPEED},
+    common_values::{
+        BACK_WALL_Y, BLUE_GOAL_BACK, BLUE_TEAM, CAR_MAX_SPEED, CEILING_Z, GOAL_HALF_WIDTH, GOAL_HEIGHT, ORANGE_GOAL_BACK, ORANGE_TEAM, SIDE_WALL_X,
+    },
     gamestates::{game_state::GameState, player_data::PlayerData},
-    math::{element_mult_vec, element_sub_vec},
+    math::{cosine_similarity, element_mult_vec, element_sub_vec},
     reward_functions::reward_fn::RewardFn,
 };
 use std::collections::HashMap;
 
+/// Rewards events (goals, touches, shots, saves, demos, boost pickups, etc.) by the weight given for each event.
+/// 
+/// Per player counters (from the match stats, the score and the boost amount) are stored on reset and on every step 
+/// and the reward is the weighted sum of the increases since the last step, so each event is only rewarded once.
+/// The boost pickup event is the increase in boost amount (as a fraction of 100).
 pub struct EventReward {
     weights: Vec<f32>,
     last_registered_values: HashMap<i32, Vec<f32>>,
 }
 
 impl EventReward {
+    /// all weights default to 0
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         goal: Option<f32>,
         team_goal: Option<f32>,
@@ -89,6 +98,50 @@ impl RewardFn for EventReward {
     }
 }
 
+/// Penalizes being close to the side walls, the back walls or the ceiling (of the soccar arena) to discourage wall riding.
+/// 
+/// The penalty is -`penalty` * (1 - dist / `activation_dist`) for the closest of them, so it is -`penalty` when touching it 
+/// and 0 once the car is further away than `activation_dist`. The back wall is ignored in front of the goal.
+pub struct WallProximityReward {
+    penalty: f32,
+    activation_dist: f32,
+}
+
+impl WallProximityReward {
+    /// penalty defaults to 1., activation_dist defaults to 500. (uu)
+    pub fn new(penalty: Option<f32>, activation_dist: Option<f32>) -> Self {
+        let activation_dist = activation_dist.unwrap_or(500.);
+        assert!(activation_dist > 0., "activation_dist must be positive");
+        WallProximityReward { penalty: penalty.unwrap_or(1.), activation_dist }
+    }
+}
+
+impl Default for WallProximityReward {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RewardFn for WallProximityReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+        let pos = player.car_data.position;
+        let mut dist = (SIDE_WALL_X - pos.x.abs()).min(CEILING_Z - pos.z);
+        let in_goal_mouth = pos.x.abs() < GOAL_HALF_WIDTH && pos.z < GOAL_HEIGHT;
+        if !in_goal_mouth {
+            dist = dist.min(BACK_WALL_Y - pos.y.abs());
+        }
+        let closeness = (1. - dist / self.activation_dist).clamp(0., 1.);
+        -self.penalty * closeness
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards the player's speed normalized by the max car speed, or penalizes it if negative is set
 pub struct VelocityReward {
     negative: bool,
 }
@@ -100,6 +153,12 @@ impl VelocityReward {
     }
 }
 
+impl Default for VelocityReward {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
 impl RewardFn for VelocityReward {
     fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
 
@@ -114,6 +173,124 @@ impl RewardFn for VelocityReward {
     }
 }
 
+/// Rewards driving forward, the car's velocity projected onto its forward direction (`PlayerData::forward`) 
+/// divided by CAR_MAX_SPEED and clamped to [-1, 1], so reversing is negative and sideways velocity gives nothing
+pub struct ForwardVelocityReward {}
+
+impl ForwardVelocityReward {
+    pub fn new() -> Self {
+        ForwardVelocityReward {}
+    }
+}
+
+impl Default for ForwardVelocityReward {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RewardFn for ForwardVelocityReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+        let forward_vel: f32 = std::iter::zip(player.forward(), player.car_data.linear_velocity.into_array()).map(|(a, b)| a * b).sum();
+        (forward_vel / CAR_MAX_SPEED).clamp(-1., 1.)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards demoing other cars with `demo_reward` per demo and penalizes being demoed with `victim_penalty` per demo, 
+/// on the step the demo happened (from `GameState.demo_events`)
+pub struct DemoReward {
+    demo_reward: f32,
+    victim_penalty: f32,
+}
+
+impl DemoReward {
+    /// default: demo_reward=1., victim_penalty=0.
+    pub fn new(demo_reward: Option<f32>, victim_penalty: Option<f32>) -> Self {
+        DemoReward {
+            demo_reward: demo_reward.unwrap_or(1.),
+            victim_penalty: victim_penalty.unwrap_or(0.),
+        }
+    }
+}
+
+impl Default for DemoReward {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RewardFn for DemoReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let mut reward = 0.;
+        for (demolisher, victim) in &state.demo_events {
+            if *demolisher == player.car_id {
+                reward += self.demo_reward;
+            }
+            if *victim == player.car_id {
+                reward -= self.victim_penalty;
+            }
+        }
+        reward
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards being in the air (not on the ground and not demoed) with 1 per step, 
+/// or gives -1 instead if `negate` is true to penalize air time
+pub struct AirReward {
+    negate: bool,
+}
+
+impl AirReward {
+    /// default: negate=false
+    pub fn new(negate: Option<bool>) -> Self {
+        AirReward {
+            negate: negate.unwrap_or(false),
+        }
+    }
+
+    /// penalizes being in the air, same as `negate` = true
+    pub fn penalty() -> Self {
+        AirReward::new(Some(true))
+    }
+}
+
+impl Default for AirReward {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl RewardFn for AirReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+        if player.on_ground || player.is_demoed {
+            0.
+        } else if self.negate {
+            -1.
+        } else {
+            1.
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards having boost with sqrt(boost / 100), so the first boost used is "cheaper" than the last boost
 pub struct SaveBoostReward {}
 
 impl SaveBoostReward {
@@ -132,6 +309,7 @@ impl RewardFn for SaveBoostReward {
     fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
 
     fn get_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+        // boost_amount is already a fraction of 100
         player.boost_amount.sqrt()
     }
 
@@ -139,3 +317,561 @@ impl RewardFn for SaveBoostReward {
         self.get_reward(player, state)
     }
 }
+
+/// Rewards picking up boost pads with `scale` * the increase in boost amount (as a fraction of 100) since the last step.
+/// 
+/// Increases smaller than `min_gain` are ignored so that any natural boost regen (eg. from a mutator) is not rewarded,
+/// the smallest pad gives 0.12. Using boost is never rewarded (or penalized).
+pub struct BoostPickupReward {
+    scale: f32,
+    min_gain: f32,
+    last_boost: HashMap<i32, f32>,
+    boost_gain: HashMap<i32, f32>,
+}
+
+impl BoostPickupReward {
+    /// scale defaults to 1., min_gain defaults to 0.1
+    pub fn new(scale: Option<f32>, min_gain: Option<f32>) -> Self {
+        BoostPickupReward {
+            scale: scale.unwrap_or(1.),
+            min_gain: min_gain.unwrap_or(0.1),
+            last_boost: HashMap::new(),
+            boost_gain: HashMap::new(),
+        }
+    }
+}
+
+impl Default for BoostPickupReward {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RewardFn for BoostPickupReward {
+    fn reset(&mut self, initial_state: &GameState, _: Option<usize>) {
+        self.boost_gain.clear();
+        self.last_boost.clear();
+        for player in &initial_state.players {
+            self.last_boost.insert(player.car_id, player.boost_amount);
+        }
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.boost_gain.clear();
+        for player in &state.players {
+            let last_boost = self.last_boost.insert(player.car_id, player.boost_amount).unwrap_or(player.boost_amount);
+            let gain = player.boost_amount - last_boost;
+            if gain >= self.min_gain {
+                self.boost_gain.insert(player.car_id, gain);
+            }
+        }
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+        self.boost_gain.get(&player.car_id).map_or(0., |gain| gain * self.scale)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards the player for being lined up between the ball and its own goal (defense) and behind the ball towards the opponent goal (offense)
+pub struct AlignBallGoal {
+    defense: f32,
+    offense: f32,
+}
+
+impl AlignBallGoal {
+    /// default: defense=1., offense=1.
+    pub fn new(defense: Option<f32>, offense: Option<f32>) -> Self {
+        let defense = defense.unwrap_or(1.);
+        let offense = offense.unwrap_or(1.);
+        AlignBallGoal { defense, offense }
+    }
+}
+
+impl Default for AlignBallGoal {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RewardFn for AlignBallGoal {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let ball = state.ball.position;
+        let pos = player.car_data.position;
+
+        let (protecc, attacc) = if player.team_num == ORANGE_TEAM {
+            (ORANGE_GOAL_BACK, BLUE_GOAL_BACK)
+        } else {
+            (BLUE_GOAL_BACK, ORANGE_GOAL_BACK)
+        };
+
+        // align player->ball and net->player vectors
+        let defensive_reward = self.defense * cosine_similarity((ball - pos).to_vec(), (pos - protecc).to_vec());
+        // align player->ball and player->net vectors
+        let offensive_reward = self.offense * cosine_similarity((ball - pos).to_vec(), (attacc - pos).to_vec());
+
+        defensive_reward + offensive_reward
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Returns the same value every step, mostly useful for debugging reward pipelines
+pub struct ConstantReward {
+    value: f32,
+}
+
+impl ConstantReward {
+    /// default: value=1.
+    pub fn new(value: Option<f32>) -> Self {
+        let value = value.unwrap_or(1.);
+        ConstantReward { value }
+    }
+}
+
+impl Default for ConstantReward {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl RewardFn for ConstantReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+        self.value
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Wraps another reward function and multiplies its output by a factor
+pub struct ScaleReward {
+    reward_fn: Box<dyn RewardFn>,
+    factor: f32,
+}
+
+impl ScaleReward {
+    pub fn new(reward_fn: Box<dyn RewardFn>, factor: f32) -> Self {
+        ScaleReward { reward_fn, factor }
+    }
+
+    /// flips the sign of the wrapped reward function, same as a factor of -1
+    pub fn negative(reward_fn: Box<dyn RewardFn>) -> Self {
+        ScaleReward::new(reward_fn, -1.)
+    }
+}
+
+impl RewardFn for ScaleReward {
+    fn reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
+        self.reward_fn.reset(initial_state, reward_stage);
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.reward_fn.pre_step(state);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.reward_fn.get_reward(player, state) * self.factor
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.reward_fn.get_final_reward(player, state) * self.factor
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.reward_fn.set_seed(seed);
+    }
+
+    fn name(&self) -> String {
+        self.reward_fn.name()
+    }
+
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        self.reward_fn.drain_stats()
+    }
+}
+
+/// Wraps another reward function and gives 0 to demoed players without calling the wrapped reward for them, 
+/// `pre_step` (and reset) are still passed through every step so its state stays up to date.
+pub struct MaskDemoedReward {
+    reward_fn: Box<dyn RewardFn>,
+}
+
+impl MaskDemoedReward {
+    pub fn new(reward_fn: Box<dyn RewardFn>) -> Self {
+        MaskDemoedReward { reward_fn }
+    }
+}
+
+impl RewardFn for MaskDemoedReward {
+    fn reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
+        self.reward_fn.reset(initial_state, reward_stage);
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.reward_fn.pre_step(state);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        if player.is_demoed {
+            0.
+        } else {
+            self.reward_fn.get_reward(player, state)
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        if player.is_demoed {
+            0.
+        } else {
+            self.reward_fn.get_final_reward(player, state)
+        }
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.reward_fn.set_seed(seed);
+    }
+
+    fn name(&self) -> String {
+        self.reward_fn.name()
+    }
+
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        self.reward_fn.drain_stats()
+    }
+}
+
+/// Wraps another reward function and accumulates the sum and count of its rewards (over all players) for the episode. 
+/// 
+/// The stats are given by `drain_stats` as `"{name}_sum"` and `"{name}_count"` and are cleared on reset, 
+/// the name defaults to the wrapped reward function's `name()`.
+pub struct RewardLogger {
+    reward_fn: Box<dyn RewardFn>,
+    name: String,
+    sum: f32,
+    count: usize,
+}
+
+impl RewardLogger {
+    pub fn new(reward_fn: Box<dyn RewardFn>, name: Option<String>) -> Self {
+        let name = name.unwrap_or_else(|| reward_fn.name());
+        RewardLogger {
+            reward_fn,
+            name,
+            sum: 0.,
+            count: 0,
+        }
+    }
+
+    fn log(&mut self, reward: f32) -> f32 {
+        self.sum += reward;
+        self.count += 1;
+        reward
+    }
+}
+
+impl RewardFn for RewardLogger {
+    fn reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
+        self.sum = 0.;
+        self.count = 0;
+        self.reward_fn.reset(initial_state, reward_stage);
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.reward_fn.pre_step(state);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let reward = self.reward_fn.get_reward(player, state);
+        self.log(reward)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let reward = self.reward_fn.get_final_reward(player, state);
+        self.log(reward)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.reward_fn.set_seed(seed);
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        let mut stats = self.reward_fn.drain_stats();
+        stats.insert(format!("{}_sum", self.name), self.sum);
+        stats.insert(format!("{}_count", self.name), self.count as f32);
+        self.sum = 0.;
+        self.count = 0;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gamestates::physics_object::{EulerAngle, Position, Velocity};
+
+    #[test]
+    fn scale_reward_negates() {
+        let mut state = GameState::new_test();
+        state.players[0].car_data.linear_velocity = Velocity { x: 1234., y: -56., z: 7. };
+        let mut inner = VelocityReward::new(None);
+        let mut reward = ScaleReward::new(Box::new(VelocityReward::new(None)), -1.);
+        reward.reset(&state, None);
+        reward.pre_step(&state);
+        assert_eq!(reward.get_reward(&state.players[0], &state), -inner.get_reward(&state.players[0], &state));
+        assert_eq!(reward.get_final_reward(&state.players[0], &state), -inner.get_final_reward(&state.players[0], &state));
+
+        let mut reward = ScaleReward::negative(Box::new(ConstantReward::new(Some(2.5))));
+        assert_eq!(reward.get_reward(&state.players[0], &state), -2.5);
+        assert_eq!(reward.get_final_reward(&state.players[0], &state), -2.5);
+    }
+
+    #[test]
+    fn mask_demoed_reward_skips_demoed() {
+        use std::{cell::Cell, rc::Rc};
+
+        /// the reward is the number of pre steps so far, the calls to get_reward are counted
+        struct CountingReward {
+            pre_steps: usize,
+            calls: Rc<Cell<usize>>,
+        }
+
+        impl RewardFn for CountingReward {
+            fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+            fn pre_step(&mut self, _state: &GameState) {
+                self.pre_steps += 1;
+            }
+
+            fn get_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+                self.calls.set(self.calls.get() + 1);
+                self.pre_steps as f32
+            }
+
+            fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+                self.get_reward(player, state)
+            }
+        }
+
+        let mut state = GameState::new_test();
+        state.players[1].is_demoed = true;
+        let calls = Rc::new(Cell::new(0));
+        let mut reward = MaskDemoedReward::new(Box::new(CountingReward { pre_steps: 0, calls: calls.clone() }));
+        reward.reset(&state, None);
+        for step in 1..=3 {
+            reward.pre_step(&state);
+            assert_eq!(reward.get_reward(&state.players[0], &state), step as f32);
+            assert_eq!(reward.get_reward(&state.players[1], &state), 0.);
+        }
+        assert_eq!(reward.get_final_reward(&state.players[1], &state), 0.);
+        // the pre steps still reached the inner reward while the player was demoed
+        assert_eq!(reward.get_final_reward(&state.players[0], &state), 3.);
+        // the inner reward was only called for the alive player
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn event_reward_goal_once() {
+        let mut state = GameState::new_test();
+        let mut reward = EventReward::new(Some(1.), None, Some(-1.), None, None, None, None, None);
+        reward.reset(&state, None);
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        // player 0 scores
+        state.blue_score += 1;
+        state.players[0].match_goals += 1;
+        assert_eq!(reward.get_reward(&state.players[0], &state), 1.);
+        assert_eq!(reward.get_reward(&state.players[1], &state), 0.);
+        // the goal is only rewarded on the step it happened
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        // the other team scores, concede is negative
+        state.orange_score += 1;
+        assert_eq!(reward.get_reward(&state.players[0], &state), -1.);
+    }
+
+    #[test]
+    fn save_boost_full_boost() {
+        let mut state = GameState::new_test();
+        state.players[0].boost_amount = 1.;
+        state.players[1].boost_amount = 0.25;
+        let mut reward = SaveBoostReward::new();
+        assert!((reward.get_reward(&state.players[0], &state) - 1.).abs() < 1e-6);
+        assert!((reward.get_reward(&state.players[1], &state) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn boost_pickup_only_rewards_pads() {
+        let mut state = GameState::new_test();
+        state.players[0].boost_amount = 0.3;
+        state.players[1].boost_amount = 0.5;
+        let mut reward = BoostPickupReward::new(Some(2.), None);
+        reward.reset(&state, None);
+
+        // player 0 picks up a small pad, player 1 boosts
+        state.players[0].boost_amount = 0.42;
+        state.players[1].boost_amount = 0.45;
+        reward.pre_step(&state);
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 0.24).abs() < 1e-5, "pad pickup reward was {val}, expected ~0.24");
+        assert_eq!(reward.get_reward(&state.players[1], &state), 0.);
+
+        // no change and a tiny regen gain are not rewarded
+        state.players[1].boost_amount = 0.46;
+        reward.pre_step(&state);
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+        assert_eq!(reward.get_final_reward(&state.players[1], &state), 0.);
+    }
+
+    #[test]
+    fn wall_proximity_penalty() {
+        let mut state = GameState::new_test();
+        let mut reward = WallProximityReward::new(Some(2.), None);
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: 17. };
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        // pinned against the back wall next to the goal
+        state.players[0].car_data.position = Position { x: 2000., y: -(BACK_WALL_Y - 10.), z: 300. };
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val + 1.96).abs() < 1e-4, "back wall penalty was {val}, expected ~-1.96");
+
+        // in front of the goal the back wall is open
+        state.players[0].car_data.position = Position { x: 0., y: -(BACK_WALL_Y - 10.), z: 17. };
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        // halfway into the activation distance of the ceiling
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: CEILING_Z - 250. };
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val + 1.).abs() < 1e-4, "ceiling penalty was {val}, expected ~-1.0");
+    }
+
+    #[test]
+    fn velocity_max_speed() {
+        let mut state = GameState::new_test();
+        state.players[0].car_data.linear_velocity = Velocity { x: 0., y: CAR_MAX_SPEED, z: 0. };
+        let val = VelocityReward::new(None).get_reward(&state.players[0], &state);
+        assert!((val - 1.).abs() < 1e-6, "max speed reward was {val}, expected ~1.0");
+        let val = VelocityReward::new(Some(true)).get_reward(&state.players[0], &state);
+        assert!((val + 1.).abs() < 1e-6, "negative max speed reward was {val}, expected ~-1.0");
+    }
+
+    #[test]
+    fn align_ball_goal_lined_up() {
+        // keep everything at goal height so the vectors are exactly colinear
+        let z = BLUE_GOAL_BACK.z;
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 0., y: 0., z };
+        state.players[0].car_data.position = Position { x: 0., y: -2000., z };
+
+        let mut reward = AlignBallGoal::new(None, None);
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 2.).abs() < 1e-4, "lined up reward was {val}, expected ~2.0");
+
+        state.players[0].car_data.position = Position { x: 0., y: 2000., z };
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val + 2.).abs() < 1e-4, "wrong side reward was {val}, expected ~-2.0");
+    }
+
+    #[test]
+    fn reward_logger_sums_episode() {
+        let mut state = GameState::new_test();
+        let mut reward = RewardLogger::new(Box::new(ScaleReward::new(Box::new(VelocityReward::new(None)), 2.)), None);
+        assert_eq!(reward.name(), "reward");
+        reward.reset(&state, None);
+
+        let mut total = 0.;
+        let mut steps = 0;
+        for i in 0..10 {
+            state.players[0].car_data.linear_velocity = Velocity { x: 100. * i as f32, y: 0., z: 0. };
+            state.players[1].car_data.linear_velocity = Velocity { x: 0., y: -50. * i as f32, z: 0. };
+            reward.pre_step(&state);
+            for player in &state.players {
+                total += if i == 9 { reward.get_final_reward(player, &state) } else { reward.get_reward(player, &state) };
+                steps += 1;
+            }
+        }
+
+        let stats = reward.drain_stats();
+        assert!((stats["reward_sum"] - total).abs() < 1e-5, "logged sum was {}, expected {total}", stats["reward_sum"]);
+        assert_eq!(stats["reward_count"], steps as f32);
+        // draining clears the accumulators, as does reset
+        assert_eq!(reward.drain_stats()["reward_sum"], 0.);
+        reward.get_reward(&state.players[0], &state);
+        reward.reset(&state, None);
+        assert_eq!(reward.drain_stats()["reward_count"], 0.);
+
+        let mut reward = RewardLogger::new(Box::new(ConstantReward::new(None)), Some("constant".to_string()));
+        reward.get_reward(&state.players[0], &state);
+        assert_eq!(reward.drain_stats()["constant_sum"], 1.);
+    }
+
+    #[test]
+    fn forward_velocity_directions() {
+        let mut state = GameState::new_test();
+        let player = &mut state.players[0];
+        // facing +y
+        player.car_data.rotation_mtx = EulerAngle { pitch: 0., yaw: std::f32::consts::FRAC_PI_2, roll: 0. }.euler_to_rotation();
+        player.car_data.has_computed_rot_mtx = true;
+        let mut reward = ForwardVelocityReward::new();
+
+        let mut reward_for = |vel: Velocity| {
+            let mut player = state.players[0];
+            player.car_data.linear_velocity = vel;
+            reward.get_reward(&player, &state)
+        };
+        let forward = reward_for(Velocity { x: 0., y: CAR_MAX_SPEED, z: 0. });
+        assert!((forward - 1.).abs() < 1e-5, "forward reward was {forward}, expected ~1.0");
+        let reverse = reward_for(Velocity { x: 0., y: -CAR_MAX_SPEED, z: 0. });
+        assert!((reverse + 1.).abs() < 1e-5, "reversing reward was {reverse}, expected ~-1.0");
+        let sideways = reward_for(Velocity { x: CAR_MAX_SPEED, y: 0., z: 0. });
+        assert!(sideways.abs() < 1e-5, "sideways reward was {sideways}, expected ~0.0");
+        // clamped when faster than max car speed (eg. from a hit)
+        assert_eq!(reward_for(Velocity { x: 0., y: 2. * CAR_MAX_SPEED, z: 0. }), 1.);
+    }
+
+    #[test]
+    fn demo_reward_demolisher_and_victim() {
+        let mut state = GameState::new_test();
+        let mut reward = DemoReward::new(None, Some(0.5));
+        let rewards = |reward: &mut DemoReward, state: &GameState| state.players.iter().map(|p| reward.get_reward(p, state)).collect::<Vec<_>>();
+        assert_eq!(rewards(&mut reward, &state), vec![0., 0.]);
+
+        state.demo_events = vec![(1, 2)];
+        assert_eq!(rewards(&mut reward, &state), vec![1., -0.5]);
+        // no victim penalty by default
+        assert_eq!(rewards(&mut DemoReward::default(), &state), vec![1., 0.]);
+    }
+
+    #[test]
+    fn air_reward_airborne_and_grounded() {
+        let state = GameState::new_test();
+        let mut airborne = state.players[0];
+        airborne.on_ground = false;
+        let mut grounded = state.players[0];
+        grounded.on_ground = true;
+
+        let mut reward = AirReward::default();
+        assert_eq!(reward.get_reward(&airborne, &state), 1.);
+        assert_eq!(reward.get_reward(&grounded, &state), 0.);
+        let mut penalty = AirReward::penalty();
+        assert_eq!(penalty.get_reward(&airborne, &state), -1.);
+        assert_eq!(penalty.get_reward(&grounded, &state), 0.);
+
+        airborne.is_demoed = true;
+        assert_eq!(reward.get_reward(&airborne, &state), 0.);
+    }
+}
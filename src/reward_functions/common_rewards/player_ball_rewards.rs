@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use crate::{
-    common_values::CAR_MAX_SPEED,
+    common_values::{BACK_WALL_Y, BALL_RADIUS, CAR_MAX_SPEED, CEILING_Z, RAMP_HEIGHT},
     gamestates::{game_state::GameState, player_data::PlayerData},
+    math::Vec3,
     reward_functions::reward_fn::RewardFn,
 };
 
@@ -27,6 +30,9 @@ impl RewardFn for VelocityPlayerToBallReward {
             vel.scalar_projection(pos_diff)
         } else {
             let partial = pos_diff.norm();
+            if partial == 0. {
+                return 0.;
+            }
             let norm_pos_diff = pos_diff.divide_by_var(partial);
             let norm_vel = vel.divide_by_var(CAR_MAX_SPEED);
             (norm_pos_diff * norm_vel).into_array().iter().sum()
@@ -37,3 +43,565 @@ impl RewardFn for VelocityPlayerToBallReward {
         self.get_reward(player, state)
     }
 }
+
+/// Kickoff shaping, gives the `VelocityPlayerToBallReward` (velocity towards the ball as a fraction of `CAR_MAX_SPEED`) during the kickoff 
+/// and 0 afterwards. The kickoff lasts from the reset, if the ball starts horizontally within `center_radius` of the center of the field, 
+/// until the ball first leaves that radius.
+pub struct KickoffReward {
+    center_radius: f32,
+    velocity_reward: VelocityPlayerToBallReward,
+    kickoff_active: bool,
+}
+
+impl KickoffReward {
+    /// default: center_radius=100. (uu)
+    pub fn new(center_radius: Option<f32>) -> Self {
+        KickoffReward {
+            center_radius: center_radius.unwrap_or(100.),
+            velocity_reward: VelocityPlayerToBallReward::new(None),
+            kickoff_active: false,
+        }
+    }
+
+    fn ball_in_center(&self, state: &GameState) -> bool {
+        let ball = state.ball.position;
+        Vec3::new(ball.x, ball.y, 0.).norm() <= self.center_radius
+    }
+}
+
+impl Default for KickoffReward {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl RewardFn for KickoffReward {
+    fn reset(&mut self, initial_state: &GameState, _: Option<usize>) {
+        self.kickoff_active = self.ball_in_center(initial_state);
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.kickoff_active = self.kickoff_active && self.ball_in_center(state);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        if self.kickoff_active {
+            self.velocity_reward.get_reward(player, state)
+        } else {
+            0.
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards the player for pointing the nose of the car at the ball, the dot product of the forward vector and the car->ball direction
+pub struct FaceBallReward {}
+
+impl FaceBallReward {
+    pub fn new() -> Self {
+        FaceBallReward {}
+    }
+}
+
+impl Default for FaceBallReward {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RewardFn for FaceBallReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let pos_diff = state.ball.position - player.car_data.position;
+        let norm = pos_diff.norm();
+        if norm == 0. {
+            return 0.;
+        }
+        let norm_pos_diff = pos_diff.divide_by_var(norm);
+        std::iter::zip(player.car_data.forward(), norm_pos_diff).map(|(a, b)| a * b).sum()
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards the player being close to the ball with `exp(-dist / (CAR_MAX_SPEED * dispersion)) ^ exponent`, 
+/// where dist is the distance from the car to the surface of the ball
+pub struct LiuDistancePlayerToBallReward {
+    dispersion: f32,
+    exponent: f32,
+}
+
+impl LiuDistancePlayerToBallReward {
+    /// default: dispersion=1., exponent=1.
+    pub fn new(dispersion: Option<f32>, exponent: Option<f32>) -> Self {
+        let dispersion = dispersion.unwrap_or(1.);
+        let exponent = exponent.unwrap_or(1.);
+        LiuDistancePlayerToBallReward { dispersion, exponent }
+    }
+}
+
+impl Default for LiuDistancePlayerToBallReward {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl RewardFn for LiuDistancePlayerToBallReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let dist = (player.car_data.position - state.ball.position).norm() - BALL_RADIUS;
+        (-dist / (CAR_MAX_SPEED * self.dispersion)).exp().powf(self.exponent)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards the player on the step they touch the ball with `aerial_weight * (ball.z / BALL_RADIUS) ^ exponent`, 
+/// so a touch on the ground gives about `aerial_weight` and higher touches (aerials) give more
+pub struct TouchBallReward {
+    aerial_weight: f32,
+    exponent: f32,
+}
+
+impl TouchBallReward {
+    /// default: exponent=1.
+    pub fn new(aerial_weight: f32, exponent: Option<f32>) -> Self {
+        let exponent = exponent.unwrap_or(1.);
+        TouchBallReward { aerial_weight, exponent }
+    }
+}
+
+impl RewardFn for TouchBallReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        if player.ball_touched {
+            self.aerial_weight * (state.ball.position.z / BALL_RADIUS).powf(self.exponent)
+        } else {
+            0.
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards the player on the step they touch the ball while in the air and the ball is above `min_height` 
+/// with `(ball.z - min_height) / (CEILING_Z - BALL_RADIUS - min_height)`, so 0 at the threshold and 1 for a touch with the ball at the ceiling.
+/// Touches on the ground or below the threshold give 0.
+pub struct JumpTouchReward {
+    min_height: f32,
+    range: f32,
+}
+
+impl JumpTouchReward {
+    pub fn new(min_height: f32) -> Self {
+        let range = CEILING_Z - BALL_RADIUS - min_height;
+        assert!(range > 0., "min height must be below the highest the ball can be");
+        JumpTouchReward { min_height, range }
+    }
+}
+
+impl RewardFn for JumpTouchReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let height = state.ball.position.z;
+        if player.ball_touched && !player.on_ground && height > self.min_height {
+            (height - self.min_height) / self.range
+        } else {
+            0.
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards getting a flip reset off the ball with 1 on the step it happens: the car regains its flip (`has_flip` goes from false to true) 
+/// while in the air and touching the ball with its wheels, meaning that the direction from the car to the ball 
+/// has a dot product with the car's down vector (-up) of at least `min_alignment`
+pub struct FlipResetReward {
+    min_alignment: f32,
+    prev_has_flip: HashMap<i32, bool>,
+}
+
+impl FlipResetReward {
+    /// default: min_alignment=0.7
+    pub fn new(min_alignment: Option<f32>) -> Self {
+        FlipResetReward {
+            min_alignment: min_alignment.unwrap_or(0.7),
+            prev_has_flip: HashMap::new(),
+        }
+    }
+}
+
+impl Default for FlipResetReward {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl RewardFn for FlipResetReward {
+    fn reset(&mut self, initial_state: &GameState, _: Option<usize>) {
+        self.prev_has_flip = initial_state.players.iter().map(|player| (player.car_id, player.has_flip)).collect();
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let prev_has_flip = self.prev_has_flip.insert(player.car_id, player.has_flip).unwrap_or(player.has_flip);
+        if prev_has_flip || !player.has_flip || player.on_ground || !player.ball_touched {
+            return 0.;
+        }
+        let car_to_ball = (state.ball.pos() - player.car_data.pos()).normalized();
+        let alignment = -Vec3::from(player.up()).dot(car_to_ball);
+        if alignment >= self.min_alignment {
+            1.
+        } else {
+            0.
+        }
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Aerial shaping based on RLGym's AerialDistanceReward, rewards a player that is in the air (above `RAMP_HEIGHT`) going for a ball 
+/// that is also in the air with `height_scale` * the height of the car above the ramps (as a fraction of the ceiling height) 
+/// + `distance_scale` * the distance to the ball that the car has closed since it took off (as a fraction of 2 * BACK_WALL_Y).
+/// 
+/// The closed distance keeps growing while the car gets closer to the ball (moving away doesn't reduce it) 
+/// and is reset when the car lands, the ball drops below the ramps or the car touches the ball.
+pub struct AerialDistanceReward {
+    height_scale: f32,
+    distance_scale: f32,
+    /// car id -> (distance to the ball on the last step, distance closed since take off)
+    air_progress: HashMap<i32, (f32, f32)>,
+}
+
+impl AerialDistanceReward {
+    pub fn new(height_scale: f32, distance_scale: f32) -> Self {
+        AerialDistanceReward {
+            height_scale,
+            distance_scale,
+            air_progress: HashMap::new(),
+        }
+    }
+}
+
+impl RewardFn for AerialDistanceReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {
+        self.air_progress.clear();
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let car_height = player.car_data.position.z;
+        let airborne = !player.on_ground && car_height > RAMP_HEIGHT && state.ball.position.z > RAMP_HEIGHT;
+        if !airborne || player.ball_touched {
+            self.air_progress.remove(&player.car_id);
+            return 0.;
+        }
+
+        let dist = state.ball.pos().dist(player.car_data.pos());
+        let (prev_dist, closed) = self.air_progress.entry(player.car_id).or_insert((dist, 0.));
+        *closed += (*prev_dist - dist).max(0.);
+        *prev_dist = dist;
+
+        self.height_scale * (car_height - RAMP_HEIGHT) / CEILING_Z + self.distance_scale * *closed / (2. * BACK_WALL_Y)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+/// Rewards dribbling, while the ball rests on the roof of a grounded car (horizontally within `max_xy_dist` of the car 
+/// and between `min_height` and `max_height` above it) the reward is the forward speed of the car as a fraction of `CAR_MAX_SPEED`, 
+/// otherwise (or when the car isn't moving forward) it is 0.
+pub struct DribbleReward {
+    max_xy_dist: f32,
+    min_height: f32,
+    max_height: f32,
+}
+
+impl DribbleReward {
+    /// default: max_xy_dist=100., min_height=100., max_height=200. (uu)
+    pub fn new(max_xy_dist: Option<f32>, min_height: Option<f32>, max_height: Option<f32>) -> Self {
+        DribbleReward {
+            max_xy_dist: max_xy_dist.unwrap_or(100.),
+            min_height: min_height.unwrap_or(100.),
+            max_height: max_height.unwrap_or(200.),
+        }
+    }
+}
+
+impl Default for DribbleReward {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl RewardFn for DribbleReward {
+    fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        if !player.on_ground {
+            return 0.;
+        }
+        let offset = state.ball.pos() - player.car_data.pos();
+        let xy_dist = Vec3::new(offset.x, offset.y, 0.).norm();
+        if xy_dist > self.max_xy_dist || !(self.min_height..=self.max_height).contains(&offset.z) {
+            return 0.;
+        }
+        let forward_speed = player.car_data.vel().dot(player.car_data.forward().into());
+        forward_speed.max(0.) / CAR_MAX_SPEED
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use crate::gamestates::physics_object::{EulerAngle, Position, Velocity};
+
+    #[test]
+    fn velocity_player_to_ball_straight_at_ball() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 1000., y: 0., z: 92.75 };
+        state.ball.linear_velocity = Velocity::default();
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: 92.75 };
+        state.players[0].car_data.linear_velocity = Velocity { x: CAR_MAX_SPEED, y: 0., z: 0. };
+
+        let mut reward = VelocityPlayerToBallReward::new(None);
+        reward.reset(&state, None);
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 1.).abs() < 1e-5, "reward was {val}, expected ~1.0");
+
+        let mut reward = VelocityPlayerToBallReward::new(Some(true));
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - CAR_MAX_SPEED).abs() < 1e-2, "scalar projection was {val}, expected {CAR_MAX_SPEED}");
+    }
+
+    #[test]
+    fn liu_distance_player_to_ball() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 0., y: 0., z: BALL_RADIUS };
+        state.players[0].car_data.position = Position { x: BALL_RADIUS, y: 0., z: BALL_RADIUS };
+        let val = LiuDistancePlayerToBallReward::new(None, None).get_reward(&state.players[0], &state);
+        assert!((val - 1.).abs() < 1e-5, "touching ball reward was {val}, expected ~1.0");
+
+        state.players[0].car_data.position = Position { x: BALL_RADIUS + CAR_MAX_SPEED, y: 0., z: BALL_RADIUS };
+        let val = LiuDistancePlayerToBallReward::new(None, Some(2.)).get_reward(&state.players[0], &state);
+        assert!((val - (-2f32).exp()).abs() < 1e-5, "far reward was {val}, expected e^-2");
+    }
+
+    #[test]
+    fn face_ball_toward_and_away() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 1000., y: 0., z: 17. };
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: 17. };
+        state.players[0].car_data.rotation_mtx = EulerAngle { pitch: 0., yaw: 0., roll: 0. }.euler_to_rotation();
+
+        let mut reward = FaceBallReward::new();
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 1.).abs() < 1e-5, "facing ball reward was {val}, expected ~1.0");
+
+        state.players[0].car_data.rotation_mtx = EulerAngle { pitch: 0., yaw: PI, roll: 0. }.euler_to_rotation();
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val + 1.).abs() < 1e-5, "facing away reward was {val}, expected ~-1.0");
+    }
+
+    #[test]
+    fn touch_ball_aerial_bonus() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 0., y: 0., z: BALL_RADIUS };
+        let mut reward = TouchBallReward::new(2., None);
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        state.players[0].ball_touched = true;
+        let ground = reward.get_reward(&state.players[0], &state);
+        assert!((ground - 2.).abs() < 1e-5, "ground touch reward was {ground}, expected ~2.0");
+
+        state.ball.position.z = 10. * BALL_RADIUS;
+        let high = reward.get_reward(&state.players[0], &state);
+        assert!(high > ground, "high touch reward ({high}) was not larger than ground touch reward ({ground})");
+        assert!((TouchBallReward::new(2., Some(2.)).get_reward(&state.players[0], &state) - 200.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn jump_touch_threshold() {
+        let mut state = GameState::new_test();
+        let mut reward = JumpTouchReward::new(300.);
+        state.players[0].ball_touched = true;
+        state.players[0].on_ground = false;
+
+        state.ball.position.z = 250.;
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        state.ball.position.z = 300. + (CEILING_Z - BALL_RADIUS - 300.) / 2.;
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 0.5).abs() < 1e-5, "half way to the ceiling reward was {val}, expected ~0.5");
+
+        // ground touches and steps without a touch give nothing
+        state.players[0].on_ground = true;
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+        state.players[0].on_ground = false;
+        state.players[0].ball_touched = false;
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+    }
+
+    #[test]
+    fn flip_reset_fires_once() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 0., y: 0., z: 1000. };
+        let player = &mut state.players[0];
+        player.car_data.position = Position { x: 0., y: 0., z: 1000. - BALL_RADIUS - 20. };
+        // upside down under the ball so the wheels face it
+        player.car_data.rotation_mtx = EulerAngle { pitch: 0., yaw: 0., roll: PI }.euler_to_rotation();
+        player.car_data.has_computed_rot_mtx = true;
+        player.on_ground = false;
+        player.has_flip = false;
+
+        let mut reward = FlipResetReward::default();
+        reward.reset(&state, None);
+        // (has_flip, ball_touched) for each step: flying up to the ball, the reset, then keeping the flip
+        let steps = [(false, false), (true, true), (true, true), (true, false)];
+        let fires: Vec<f32> = steps
+            .iter()
+            .map(|(has_flip, ball_touched)| {
+                let mut player = state.players[0];
+                player.has_flip = *has_flip;
+                player.ball_touched = *ball_touched;
+                reward.get_reward(&player, &state)
+            })
+            .collect();
+        assert_eq!(fires, vec![0., 1., 0., 0.]);
+
+        // regaining the flip from touching the ball with the roof is not a flip reset
+        let mut player = state.players[0];
+        player.car_data.rotation_mtx = EulerAngle::default().euler_to_rotation();
+        reward.reset(&state, None);
+        player.has_flip = true;
+        player.ball_touched = true;
+        assert_eq!(reward.get_reward(&player, &state), 0.);
+    }
+
+    #[test]
+    fn aerial_distance_grows_while_approaching() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 0., y: 1000., z: 1000. };
+        let mut reward = AerialDistanceReward::new(0., 1.);
+        reward.reset(&state, None);
+
+        // on the ground, nothing
+        state.players[0].on_ground = true;
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: 17. };
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        state.players[0].on_ground = false;
+        let mut last = -1.;
+        for i in 0..5 {
+            state.players[0].car_data.position = Position { x: 0., y: 100. * i as f32, z: 300. + 100. * i as f32 };
+            let val = reward.get_reward(&state.players[0], &state);
+            assert!(val > last || (i == 0 && val == 0.), "reward {val} did not grow from {last} while approaching");
+            last = val;
+        }
+        // moving away keeps the progress
+        state.players[0].car_data.position = Position { x: 0., y: 300., z: 600. };
+        assert_eq!(reward.get_reward(&state.players[0], &state), last);
+
+        // the height part
+        let mut reward = AerialDistanceReward::new(1., 0.);
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: RAMP_HEIGHT + CEILING_Z / 2. };
+        assert!((reward.get_reward(&state.players[0], &state) - 0.5).abs() < 1e-6);
+
+        // a touch resets the progress
+        let mut reward = AerialDistanceReward::new(0., 1.);
+        state.players[0].car_data.position = Position { x: 0., y: 0., z: 500. };
+        reward.get_reward(&state.players[0], &state);
+        state.players[0].car_data.position = Position { x: 0., y: 500., z: 800. };
+        assert!(reward.get_reward(&state.players[0], &state) > 0.);
+        state.players[0].ball_touched = true;
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+        state.players[0].ball_touched = false;
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+    }
+
+    #[test]
+    fn dribble_ball_on_roof() {
+        let mut state = GameState::new_test();
+        let car = &mut state.players[0];
+        car.on_ground = true;
+        car.car_data.position = Position { x: 500., y: 0., z: 17. };
+        car.car_data.rotation_mtx = EulerAngle { pitch: 0., yaw: 0., roll: 0. }.euler_to_rotation();
+        car.car_data.linear_velocity = Velocity { x: CAR_MAX_SPEED / 2., y: 0., z: 0. };
+        state.ball.position = Position { x: 510., y: -20., z: 17. + 140. };
+
+        let mut reward = DribbleReward::new(None, None, None);
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 0.5).abs() < 1e-5, "dribble reward was {val}, expected ~0.5");
+
+        // driving backwards
+        state.players[0].car_data.linear_velocity = Velocity { x: -CAR_MAX_SPEED / 2., y: 0., z: 0. };
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+        state.players[0].car_data.linear_velocity = Velocity { x: CAR_MAX_SPEED / 2., y: 0., z: 0. };
+
+        // ball on the ground next to the car
+        state.ball.position = Position { x: 700., y: 0., z: BALL_RADIUS };
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+        // ball above the car but too high
+        state.ball.position = Position { x: 500., y: 0., z: 500. };
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        // the car jumped
+        state.ball.position = Position { x: 510., y: -20., z: 17. + 140. };
+        state.players[0].on_ground = false;
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+    }
+
+    #[test]
+    fn kickoff_reward_only_during_kickoff() {
+        let mut state = GameState::new_test();
+        state.ball.position = Position { x: 0., y: 0., z: BALL_RADIUS };
+        state.ball.linear_velocity = Velocity::default();
+        state.players[0].car_data.position = Position { x: 0., y: -2000., z: BALL_RADIUS };
+        state.players[0].car_data.linear_velocity = Velocity { x: 0., y: CAR_MAX_SPEED / 2., z: 0. };
+
+        let mut reward = KickoffReward::new(None);
+        reward.reset(&state, None);
+        reward.pre_step(&state);
+        let val = reward.get_reward(&state.players[0], &state);
+        assert!((val - 0.5).abs() < 1e-5, "kickoff reward was {val}, expected ~0.5");
+
+        // the ball was hit away from the center
+        state.ball.position = Position { x: 300., y: 800., z: 200. };
+        reward.pre_step(&state);
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+        // the kickoff is over even if the ball comes back
+        state.ball.position = Position { x: 0., y: 0., z: BALL_RADIUS };
+        reward.pre_step(&state);
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+
+        // no kickoff if the episode didn't start with the ball in the center
+        state.ball.position = Position { x: 0., y: 2000., z: BALL_RADIUS };
+        reward.reset(&state, None);
+        reward.pre_step(&state);
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.);
+    }
+}
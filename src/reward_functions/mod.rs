@@ -1,3 +1,6 @@
 pub mod combined_reward;
 pub mod common_rewards;
+pub mod normalize_reward;
 pub mod reward_fn;
+pub mod sequential_reward;
+pub mod zero_sum_reward;
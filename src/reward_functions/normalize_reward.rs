@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::gamestates::{game_state::GameState, player_data::PlayerData};
+
+use super::reward_fn::RewardFn;
+
+/// Running mean and variance of a stream of values (Welford's algorithm), see `NormalizeReward`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RunningMeanStd {
+    pub count: u64,
+    pub mean: f64,
+    /// sum of the squared differences from the mean
+    pub m2: f64,
+}
+
+impl RunningMeanStd {
+    pub fn update(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// population variance, 0 until there are at least 2 values
+    pub fn var(&self) -> f64 {
+        if self.count < 2 {
+            0.
+        } else {
+            self.m2 / self.count as f64
+        }
+    }
+
+    pub fn std(&self) -> f64 {
+        self.var().sqrt()
+    }
+}
+
+/// Wraps another reward function and standardizes its rewards with the running mean and std of every reward it gave (of all players),
+/// the result is clipped to [-clip, clip].
+///
+/// Every reward updates the statistics before it is standardized. They are kept across episodes (reset doesn't touch them),
+/// use `save_stats`/`load_stats` to carry them over and `set_frozen` to stop updating them (eg. for evaluation).
+pub struct NormalizeReward {
+    reward_fn: Box<dyn RewardFn>,
+    clip: f32,
+    stats: RunningMeanStd,
+    frozen: bool,
+}
+
+impl NormalizeReward {
+    /// small value added to the variance so that constant rewards don't divide by 0
+    const EPSILON: f64 = 1e-8;
+
+    pub fn new(reward_fn: Box<dyn RewardFn>, clip: f32) -> Self {
+        assert!(clip > 0., "clip must be positive");
+        NormalizeReward { reward_fn, clip, stats: RunningMeanStd::default(), frozen: false }
+    }
+
+    pub fn save_stats(&self) -> RunningMeanStd {
+        self.stats
+    }
+
+    pub fn load_stats(&mut self, stats: RunningMeanStd) {
+        self.stats = stats;
+    }
+
+    /// while frozen the statistics are used as is without being updated
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    fn normalize(&mut self, reward: f32) -> f32 {
+        if !self.frozen {
+            self.stats.update(reward as f64);
+        }
+        let normalized = (reward as f64 - self.stats.mean) / (self.stats.var() + Self::EPSILON).sqrt();
+        (normalized as f32).clamp(-self.clip, self.clip)
+    }
+}
+
+impl RewardFn for NormalizeReward {
+    fn reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
+        self.reward_fn.reset(initial_state, reward_stage);
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.reward_fn.pre_step(state);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let reward = self.reward_fn.get_reward(player, state);
+        self.normalize(reward)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        let reward = self.reward_fn.get_final_reward(player, state);
+        self.normalize(reward)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.reward_fn.set_seed(seed);
+    }
+
+    fn name(&self) -> String {
+        self.reward_fn.name()
+    }
+
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        self.reward_fn.drain_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// uniform rewards in [0, 10) (mean 5, variance 100 / 12)
+    struct UniformReward {
+        rng: SmallRng,
+    }
+
+    impl RewardFn for UniformReward {
+        fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+        fn get_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+            self.rng.gen_range(0. ..10.)
+        }
+
+        fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+            self.get_reward(player, state)
+        }
+    }
+
+    fn mean_var(values: &[f32]) -> (f32, f32) {
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let var = values.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / values.len() as f32;
+        (mean, var)
+    }
+
+    #[test]
+    fn normalized_rewards_approach_unit_gaussian() {
+        let state = GameState::new_test();
+        let mut reward = NormalizeReward::new(Box::new(UniformReward { rng: SmallRng::seed_from_u64(0) }), 10.);
+
+        let mut rewards = Vec::new();
+        for episode in 0..20 {
+            // the statistics persist across resets
+            reward.reset(&state, None);
+            for _ in 0..500 {
+                reward.pre_step(&state);
+                for player in &state.players {
+                    let value = reward.get_reward(player, &state);
+                    if episode >= 10 {
+                        rewards.push(value);
+                    }
+                }
+            }
+        }
+        let (mean, var) = mean_var(&rewards);
+        assert!(mean.abs() < 0.05, "mean of the normalized rewards was {mean}");
+        assert!((var - 1.).abs() < 0.05, "variance of the normalized rewards was {var}");
+
+        let stats = reward.save_stats();
+        assert_eq!(stats.count, 20 * 500 * 2);
+        assert!((stats.mean - 5.).abs() < 0.1);
+        assert!((stats.var() - 100. / 12.).abs() < 0.2);
+    }
+
+    #[test]
+    fn frozen_stats_are_not_updated() {
+        let state = GameState::new_test();
+        let stats = RunningMeanStd { count: 100, mean: 5., m2: 400. };
+        let mut reward = NormalizeReward::new(Box::new(UniformReward { rng: SmallRng::seed_from_u64(1) }), 1.);
+        reward.load_stats(stats);
+        reward.set_frozen(true);
+
+        let mut inner = UniformReward { rng: SmallRng::seed_from_u64(1) };
+        for _ in 0..50 {
+            let value = reward.get_reward(&state.players[0], &state);
+            let expected = ((inner.get_reward(&state.players[0], &state) - 5.) / 2.).clamp(-1., 1.);
+            assert!((value - expected).abs() < 1e-5, "got {value}, expected {expected}");
+        }
+        assert_eq!(reward.save_stats(), stats);
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::gamestates::{game_state::GameState, player_data::PlayerData};
 
 pub trait RewardFn {
@@ -5,4 +7,15 @@ pub trait RewardFn {
     fn pre_step(&mut self, _state: &GameState) {}
     fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32;
     fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32;
+    /// only needed for reward functions with randomness, see `Gym::seed`
+    fn set_seed(&mut self, _seed: u64) {}
+    /// name used as the key prefix for logged stats, see `RewardLogger`
+    fn name(&self) -> String {
+        "reward".to_string()
+    }
+    /// returns the stats accumulated since the last drain (or reset) and clears them, 
+    /// the gym merges these into the info of the last step of an episode
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        HashMap::new()
+    }
 }
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use crate::gamestates::{game_state::GameState, player_data::PlayerData};
+
+use super::reward_fn::RewardFn;
+
+/// Curriculum reward function that uses the `reward_stage` given to `reset` (eg. from `Gym::reset_with_stage`) to pick 
+/// which of its reward functions is active for the episode. The stage is clamped to the available reward functions and 
+/// defaults to 0 if there is no stage. 
+/// 
+/// Calls are only forwarded to the active reward function, except `set_seed` which seeds all of them.
+pub struct SequentialReward {
+    reward_structs: Vec<Box<dyn RewardFn>>,
+    active: usize,
+}
+
+impl SequentialReward {
+    pub fn new(reward_structs: Vec<Box<dyn RewardFn>>) -> Self {
+        assert!(!reward_structs.is_empty(), "sequential reward needs at least one reward function");
+        SequentialReward { reward_structs, active: 0 }
+    }
+
+    /// index of the reward function that is active for the current episode
+    pub fn active_stage(&self) -> usize {
+        self.active
+    }
+}
+
+impl RewardFn for SequentialReward {
+    fn reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
+        self.active = reward_stage.unwrap_or(0).min(self.reward_structs.len() - 1);
+        self.reward_structs[self.active].reset(initial_state, reward_stage);
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.reward_structs[self.active].pre_step(state);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.reward_structs[self.active].get_reward(player, state)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.reward_structs[self.active].get_final_reward(player, state)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        for struc in &mut self.reward_structs {
+            struc.set_seed(seed);
+        }
+    }
+
+    fn name(&self) -> String {
+        self.reward_structs[self.active].name()
+    }
+
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        self.reward_structs[self.active].drain_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StageReward {
+        value: f32,
+    }
+
+    impl RewardFn for StageReward {
+        fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+        fn get_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+            self.value
+        }
+
+        fn get_final_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+            self.value * 10.
+        }
+    }
+
+    #[test]
+    fn sequential_reward_routes_by_stage() {
+        let mut reward = SequentialReward::new((0..3).map(|i| Box::new(StageReward { value: i as f32 }) as Box<dyn RewardFn>).collect());
+        let state = GameState::new_test();
+        let player = &state.players[0];
+
+        reward.reset(&state, Some(0));
+        assert_eq!(reward.get_reward(player, &state), 0.);
+        reward.reset(&state, Some(2));
+        assert_eq!(reward.active_stage(), 2);
+        assert_eq!(reward.get_reward(player, &state), 2.);
+        assert_eq!(reward.get_final_reward(player, &state), 20.);
+        // clamped to the last reward function
+        reward.reset(&state, Some(7));
+        assert_eq!(reward.get_reward(player, &state), 2.);
+        reward.reset(&state, None);
+        assert_eq!(reward.get_reward(player, &state), 0.);
+    }
+}
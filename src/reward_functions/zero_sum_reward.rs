@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use crate::{
+    common_values::BLUE_TEAM,
+    gamestates::{game_state::GameState, player_data::PlayerData},
+};
+
+use super::reward_fn::RewardFn;
+
+/// Wraps another reward function and makes it (team) zero-sum, following the RLGym/Necto formulation: 
+/// 
+/// `reward = (1 - team_spirit) * own + team_spirit * team_mean - opp_scale * opponent_mean`
+/// 
+/// With `opp_scale` = 1 and equal team sizes the rewards of all players sum to zero. 
+/// The inner rewards of all players are computed at the first `get_reward`/`get_final_reward` call after `pre_step` and cached for the step.
+pub struct ZeroSumReward {
+    reward_fn: Box<dyn RewardFn>,
+    team_spirit: f32,
+    opp_scale: f32,
+    // (is final, car id -> inner reward, blue mean, orange mean)
+    cache: Option<(bool, HashMap<i32, f32>, f32, f32)>,
+}
+
+impl ZeroSumReward {
+    pub fn new(reward_fn: Box<dyn RewardFn>, team_spirit: f32, opp_scale: f32) -> Self {
+        ZeroSumReward {
+            reward_fn,
+            team_spirit,
+            opp_scale,
+            cache: None,
+        }
+    }
+
+    fn fill_cache(&mut self, state: &GameState, final_reward: bool) {
+        if matches!(self.cache, Some((is_final, ..)) if is_final == final_reward) {
+            return;
+        }
+        let mut rewards = HashMap::with_capacity(state.players.len());
+        let (mut blue_sum, mut blue_count, mut orange_sum, mut orange_count) = (0., 0, 0., 0);
+        for player in &state.players {
+            let reward = if final_reward {
+                self.reward_fn.get_final_reward(player, state)
+            } else {
+                self.reward_fn.get_reward(player, state)
+            };
+            if player.team_num == BLUE_TEAM {
+                blue_sum += reward;
+                blue_count += 1;
+            } else {
+                orange_sum += reward;
+                orange_count += 1;
+            }
+            rewards.insert(player.car_id, reward);
+        }
+        let blue_mean = if blue_count > 0 { blue_sum / blue_count as f32 } else { 0. };
+        let orange_mean = if orange_count > 0 { orange_sum / orange_count as f32 } else { 0. };
+        self.cache = Some((final_reward, rewards, blue_mean, orange_mean));
+    }
+
+    fn zero_sum_reward(&mut self, player: &PlayerData, state: &GameState, final_reward: bool) -> f32 {
+        self.fill_cache(state, final_reward);
+        let (_, rewards, blue_mean, orange_mean) = self.cache.as_ref().unwrap();
+        let own = rewards[&player.car_id];
+        let (team_mean, opp_mean) = if player.team_num == BLUE_TEAM { (*blue_mean, *orange_mean) } else { (*orange_mean, *blue_mean) };
+        (1. - self.team_spirit) * own + self.team_spirit * team_mean - self.opp_scale * opp_mean
+    }
+}
+
+impl RewardFn for ZeroSumReward {
+    fn reset(&mut self, initial_state: &GameState, reward_stage: Option<usize>) {
+        self.cache = None;
+        self.reward_fn.reset(initial_state, reward_stage);
+    }
+
+    fn pre_step(&mut self, state: &GameState) {
+        self.cache = None;
+        self.reward_fn.pre_step(state);
+    }
+
+    fn get_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.zero_sum_reward(player, state, false)
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.zero_sum_reward(player, state, true)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.reward_fn.set_seed(seed);
+    }
+
+    fn name(&self) -> String {
+        self.reward_fn.name()
+    }
+
+    fn drain_stats(&mut self) -> HashMap<String, f32> {
+        self.reward_fn.drain_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::common_values::ORANGE_TEAM;
+
+    /// returns the car id as the reward
+    struct CarIdReward {
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl RewardFn for CarIdReward {
+        fn reset(&mut self, _initial_state: &GameState, _: Option<usize>) {}
+
+        fn get_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+            self.calls.set(self.calls.get() + 1);
+            player.car_id as f32
+        }
+
+        fn get_final_reward(&mut self, player: &PlayerData, _state: &GameState) -> f32 {
+            self.calls.set(self.calls.get() + 1);
+            player.car_id as f32 * 10.
+        }
+    }
+
+    fn two_v_two() -> GameState {
+        let mut state = GameState::new_test();
+        let mut players = Vec::new();
+        for (car_id, team_num) in [(1, BLUE_TEAM), (2, BLUE_TEAM), (3, ORANGE_TEAM), (7, ORANGE_TEAM)] {
+            let mut player = state.players[0].clone();
+            player.car_id = car_id;
+            player.team_num = team_num;
+            players.push(player);
+        }
+        state.players = players;
+        state
+    }
+
+    #[test]
+    fn zero_sum_rewards_sum_to_zero() {
+        let state = two_v_two();
+        for team_spirit in [0., 0.3, 1.] {
+            let mut reward = ZeroSumReward::new(Box::new(CarIdReward { calls: Rc::new(Cell::new(0)) }), team_spirit, 1.);
+            reward.reset(&state, None);
+            reward.pre_step(&state);
+            let rewards: Vec<f32> = state.players.iter().map(|player| reward.get_reward(player, &state)).collect();
+            assert!(rewards.iter().sum::<f32>().abs() < 1e-5, "rewards did not sum to zero: {rewards:?}");
+            let finals: f32 = state.players.iter().map(|player| reward.get_final_reward(player, &state)).sum();
+            assert!(finals.abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn zero_sum_formula_and_caching() {
+        let state = two_v_two();
+        let calls = Rc::new(Cell::new(0));
+        let mut reward = ZeroSumReward::new(Box::new(CarIdReward { calls: calls.clone() }), 0.5, 0.5);
+        reward.reset(&state, None);
+        reward.pre_step(&state);
+        // own 1, team mean 1.5, opponent mean 5
+        assert_eq!(reward.get_reward(&state.players[0], &state), 0.5 * 1. + 0.5 * 1.5 - 0.5 * 5.);
+        // own 7, team mean 5, opponent mean 1.5
+        assert_eq!(reward.get_reward(&state.players[3], &state), 0.5 * 7. + 0.5 * 5. - 0.5 * 1.5);
+        assert_eq!(calls.get(), 4, "inner rewards were not computed once per player");
+        reward.pre_step(&state);
+        reward.get_reward(&state.players[1], &state);
+        assert_eq!(calls.get(), 8);
+    }
+}
@@ -1 +1,2 @@
 pub mod wrapper;
+pub mod tick_observer;
@@ -0,0 +1,15 @@
+use rocketsim_rs::GameState as GameState_sim;
+
+/// Observer that is called by `RocketsimWrapper::step` after every physics tick (so `tick_skip` times per step) 
+/// with a snapshot of the arena, eg. to integrate rewards over the intermediate ticks. 
+/// 
+/// The snapshot is only taken if an observer is set. Closures taking a `&GameState_sim` implement this as well.
+pub trait TickObserver {
+    fn on_tick(&mut self, tick_state: &GameState_sim);
+}
+
+impl<F: FnMut(&GameState_sim)> TickObserver for F {
+    fn on_tick(&mut self, tick_state: &GameState_sim) {
+        self(tick_state)
+    }
+}
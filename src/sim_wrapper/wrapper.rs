@@ -1,11 +1,11 @@
 use rocketsim_rs::{
-    cxx::UniquePtr, math::{RotMat, Vec3}, sim::{Arena, BallState, CarConfig, CarControls, CarState, Team}, BoostPad, GameState as GameState_sim
+    cxx::UniquePtr, sim::{Arena, ArenaConfig, BallState, CarConfig, CarControls, CarState, Team}, BoostPad, GameState as GameState_sim
 };
 // use std::cell::RefCell;
-use std::{collections::HashMap, sync::RwLock};
+use std::{collections::HashMap, pin::Pin, sync::RwLock};
 
 use crate::{
-    common_values::{BLUE_TEAM, GRAVITY_Z, ORANGE_TEAM, ROCKETSIM_BOOST_PER_SEC},
+    common_values::{BACK_WALL_Y, BLUE_TEAM, BOOST_LOCATIONS, GOAL_HALF_WIDTH, GOAL_HEIGHT, GRAVITY_Z, ORANGE_TEAM, ROCKETSIM_BOOST_MAX, ROCKETSIM_BOOST_PER_SEC},
     gamestates::{
         game_state::GameState as GameState_rlgym,
         physics_object::{PhysicsObject, Position, Velocity},
@@ -16,9 +16,15 @@ use crate::{
         // car_wrapper::CarWrapper
     }, 
     envs::game_match::GameConfig,
+    sim_wrapper::tick_observer::TickObserver,
 };
 
 /// used as a means to store stats for a particular agent
+/// 
+/// A shot is counted when a car touches the ball and, after the touch, the ball is on a path
+/// (extrapolated with gravity and without bounces) that crosses the opponent goal line inside of the goal mouth within
+/// `SHOT_MAX_TIME` seconds. 
+/// A save is counted when a car touches the ball while it was on such a path towards the car's own goal and, after the touch, it no longer is.
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Stats {
     pub goals: u32,
@@ -34,6 +40,47 @@ pub struct Stats {
     pub bumped_count: u32,
 }
 
+/// max time (in seconds) to extrapolate the ball for when checking for shots and saves
+const SHOT_MAX_TIME: f32 = 2.;
+
+/// checks whether the ball is on a path into the goal at goal_y within SHOT_MAX_TIME, similar to RocketSim's `is_ball_probably_going_in`
+fn ball_heading_into_goal(ball: &BallState, goal_y: f32, gravity_z: f32) -> bool {
+    // ball is not moving towards the goal
+    if ball.vel.y == 0. || ball.vel.y.signum() != goal_y.signum() {
+        return false;
+    }
+
+    let time_to_goal = (goal_y - ball.pos.y) / ball.vel.y;
+    if !(0. ..=SHOT_MAX_TIME).contains(&time_to_goal) {
+        return false;
+    }
+
+    let x_at_goal = ball.pos.x + ball.vel.x * time_to_goal;
+    let z_at_goal = ball.pos.z + ball.vel.z * time_to_goal + gravity_z * time_to_goal * time_to_goal / 2.;
+
+    x_at_goal.abs() < GOAL_HALF_WIDTH && z_at_goal < GOAL_HEIGHT
+}
+
+/// maps each of the sim's boost pads to the index of the pad with the same location in `BOOST_LOCATIONS` (RLGym's order), 
+/// falls back to the sim's order if the pads don't match (eg. in other game modes)
+fn get_pad_order(arena: &mut UniquePtr<Arena>) -> Vec<usize> {
+    let pads = arena.pin_mut().get_game_state().pads;
+    let pad_order = pads
+        .iter()
+        .filter_map(|pad| {
+            BOOST_LOCATIONS
+                .iter()
+                .position(|loc| (loc[0] - pad.position.x).abs() < 1. && (loc[1] - pad.position.y).abs() < 1.)
+        })
+        .collect::<Vec<_>>();
+
+    if pads.len() == BOOST_LOCATIONS.len() && pad_order.len() == pads.len() {
+        pad_order
+    } else {
+        (0..pads.len()).collect()
+    }
+}
+
 pub struct RocketsimWrapper {
     arena: UniquePtr<Arena>,
     car_ids: Vec<u32>,
@@ -45,6 +92,13 @@ pub struct RocketsimWrapper {
     prev_touched_ticks: HashMap<u32, u64>,
     car_id_map: HashMap<u32, i32>,
     on_ground_vec: Vec<bool>,
+    pad_order: Vec<usize>,
+    stat_touched_ticks: HashMap<u32, u64>,
+    tick_observer: Option<Box<dyn TickObserver>>,
+    /// agent index -> index into car_ids (and so the gym car id - 1) of the car the agent controls
+    agent_order: Vec<usize>,
+    /// arena tick of the last time the state was set, for `GameState.tick_count`
+    episode_start_tick: u64,
 }
 
 impl RocketsimWrapper {
@@ -53,6 +107,8 @@ impl RocketsimWrapper {
         static ORANGE_SCORE: RwLock<i32> = const { RwLock::new(0) };
         static LAST_GOAL_TICK: RwLock<u64> = const { RwLock::new(0) };
         static STATS: RwLock<Vec<(u32, Stats)>> = const { RwLock::new(Vec::new()) };
+        /// (demolisher, victim) sim car ids of the demos since the last decoded state
+        static DEMO_EVENTS: RwLock<Vec<(u32, u32)>> = const { RwLock::new(Vec::new()) };
     );
 
     pub fn new(config: GameConfig) -> Self {
@@ -60,7 +116,7 @@ impl RocketsimWrapper {
         // rocketsim start
         // required only once for all threads so we should do it before the multithreading parts instead of here
         // rocketsim_rs::init(None);
-        let mut rocket_sim_instance = Arena::default_standard();
+        let mut rocket_sim_instance = Arena::new(config.game_mode, ArenaConfig::default(), 120);
 
         let mut sim_mutator_config = rocket_sim_instance.get_mutator_config();
         sim_mutator_config.gravity.z = GRAVITY_Z * config.gravity;
@@ -70,36 +126,16 @@ impl RocketsimWrapper {
         rocket_sim_instance.pin_mut().reset_to_random_kickoff(None);
         let mut car_ids = Vec::new();
         let mut car_id_map = HashMap::new();
-        if config.spawn_opponents {
-            let mut i = 1;
-            // spawn blue cars
-            for _ in 0..config.team_size {
-                let car_id = rocket_sim_instance.pin_mut().add_car(Team::Blue, config.car_config);
-                car_id_map.insert(car_id, i);
-                car_ids.push(car_id);
-                i += 1;
-            }
-            // spawn orange cars
-            for _ in 0..config.team_size {
-                let car_id = rocket_sim_instance.pin_mut().add_car(Team::Orange, config.car_config);
-                car_id_map.insert(car_id, i);
-                car_ids.push(car_id);
-                i += 1;
-            }
-        } else {
-            let mut i = 1;
-            // spawn blue cars
-            for _ in 0..config.team_size as u32 {
-                let car_id = rocket_sim_instance.pin_mut().add_car(Team::Blue, config.car_config);
-                car_id_map.insert(car_id, i);
-                car_ids.push(car_id);
-                i += 1;
-            }
+        // spawn blue cars then orange cars, gym car ids start at 1
+        let teams = std::iter::repeat_n(Team::Blue, config.blue_count()).chain(std::iter::repeat_n(Team::Orange, config.orange_count()));
+        for (i, team) in teams.enumerate() {
+            let car_id = rocket_sim_instance.pin_mut().add_car(team, config.car_config_for(i));
+            car_id_map.insert(car_id, i as i32 + 1);
+            car_ids.push(car_id);
         }
 
         // init on_ground array
-        let num_cars = if config.spawn_opponents { config.team_size * 2 } else { config.team_size };
-        let on_ground_vec = vec![false; num_cars];
+        let on_ground_vec = vec![false; config.num_cars()];
 
         // init stats
         Self::STATS.with(|stats| {
@@ -109,110 +145,7 @@ impl RocketsimWrapper {
             }
         });
 
-        rocket_sim_instance.pin_mut().set_goal_scored_callback(
-            |mut arena, team, tick_skip| {
-                let curr_tick = arena.as_mut().get_tick_count();
-                let tick_skip = tick_skip as u64;
-
-                // -- This section is for orange and blue scores --
-                let last_goal_tick = Self::LAST_GOAL_TICK.with(|val| *val.read().unwrap());
-
-                // make it so that tick skip doesn't count multiple goals scored
-                if curr_tick < last_goal_tick + tick_skip {
-                    Self::LAST_GOAL_TICK.with(|val| {
-                        let mut ref_val = val.write().unwrap();
-                        *ref_val = curr_tick;
-                    });
-                    return;
-                }
-
-                if team == Team::Blue {
-                    Self::BLUE_SCORE.with(|val| *val.write().unwrap() += 1);
-                } else {
-                    Self::ORANGE_SCORE.with(|val| *val.write().unwrap() += 1);
-                }
-
-                // value that holds the last tick the goal was scored from
-                Self::LAST_GOAL_TICK.with(|val| *val.write().unwrap() = curr_tick);
-                // -- end of section --
-
-                // -- start of stats section --
-                // section adapted from stat_tracker in bindings made by VirxEC
-
-                // Collect all valid ball touches
-                let mut all_ball_touches = arena
-                    .as_mut()
-                    .get_car_infos()
-                    .into_iter()
-                    .filter_map(|car_info| {
-                        if car_info.state.ball_hit_info.is_valid {
-                            Some((car_info.id, car_info.team, car_info.state.ball_hit_info.tick_count_when_hit))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>();
-                // Sort by ball touch time
-                all_ball_touches.sort_by_key(|(_, _, tick_count_when_hit)| *tick_count_when_hit);
-
-                // Sort ball touches by team
-                let ball_touches = [
-                    all_ball_touches.iter().filter(|(_, team, _)| *team == Team::Blue).map(|(id, _, _)| *id).collect::<Vec<_>>(),
-                    all_ball_touches
-                        .iter()
-                        .filter(|(_, team, _)| *team == Team::Orange)
-                        .map(|(id, _, _)| *id)
-                        .collect::<Vec<_>>(),
-                ];
-
-                // update stats
-                let t_index = team as u8 as usize;
-
-                Self::STATS.with(|stats| {
-                    // it's possible no car touched the ball on the team that got the goal
-                    // so ensure that were was at least one ball touch
-                    if !ball_touches[t_index].is_empty() {
-                        // the latest ball touch on the same team is the scorer
-                        let scorer = ball_touches[t_index].last().copied().unwrap();
-                        // println!("Car {scorer} SCORED");
-
-                        let mut guard = stats.write().unwrap();
-                        // +1 to the car's goals stat
-                        guard.iter_mut().find(|(id, _)| *id == scorer).unwrap().1.goals += 1;
-
-                        if ball_touches[t_index].len() > 1 {
-                            // if there were two ball touches, they get the assist
-                            let assist = ball_touches[t_index][ball_touches[t_index].len() - 2];
-
-                            // Get the tick count of when the scorer and assist touched the ball
-                            let scorer_tick = arena.as_mut().get_car(scorer).ball_hit_info.tick_count_when_hit;
-                            let assist_tick = arena.as_mut().get_car(assist).ball_hit_info.tick_count_when_hit;
-
-                            // ensure that the assist is < 5s before the touch of the scoring player
-                            if (scorer_tick - assist_tick) as f32 / arena.get_tick_rate() < 5. {
-                                // println!("CAR {assist} got an ASSIST");
-
-                                // +1 to the car's assists stat
-                                guard.iter_mut().find(|(id, _)| id == &assist).unwrap().1.assists += 1;
-                            }
-                        }
-
-                        if let Some(latest_hit_id) = all_ball_touches.last().map(|(id, _, _)| *id) {
-                            // if the last hit was not the scorer, they get the own goal
-                            // rocket league tracks this stat in secret and isn't shown on the scoreboard
-                            if latest_hit_id != scorer {
-                                // println!("CAR {latest_hit_id} OWN GOALED");
-
-                                // +1 to the car's own goals stat
-                                guard.iter_mut().find(|(id, _)| *id == latest_hit_id).unwrap().1.own_goals += 1;
-                            }
-                        }
-                    }
-                });
-                // -- end of stats section --
-            },
-            config.tick_skip,
-        );
+        rocket_sim_instance.pin_mut().set_goal_scored_callback(Self::goal_scored_callback, config.tick_skip);
 
         rocket_sim_instance.pin_mut().set_car_bump_callback(
             |_, bumper, victim, is_demo, _| {
@@ -236,10 +169,15 @@ impl RocketsimWrapper {
                     stats_for_victim_id.1.bumped_count += 1;
                     stats_for_victim_id.1.last_bumped_by_id = bumper;
                 });
+                if is_demo {
+                    Self::DEMO_EVENTS.with(|events| events.write().unwrap().push((bumper, victim)));
+                }
             },
             0,
         );
 
+        let pad_order = get_pad_order(&mut rocket_sim_instance);
+
         RocketsimWrapper {
             arena: rocket_sim_instance,
             car_ids,
@@ -249,15 +187,24 @@ impl RocketsimWrapper {
             prev_touched_ticks: HashMap::new(),
             car_id_map,
             on_ground_vec,
+            pad_order,
+            stat_touched_ticks: HashMap::new(),
+            tick_observer: None,
+            agent_order: (0..config.num_cars()).collect(),
+            episode_start_tick: 0,
         }
     }
 
     pub fn set_state(&mut self, state_wrapper: StateWrapper, get_sim_state: bool) -> (GameState_rlgym, Option<GameState_sim>) {
+        // demos from before the state was set should not show up in the new state
+        Self::DEMO_EVENTS.with(|events| events.write().unwrap().clear());
         let mut sim_state = self.arena.pin_mut().get_game_state();
 
         // reset boost pads
-        for (i, pad) in sim_state.pads.iter_mut().enumerate() {
-            pad.state = state_wrapper.pads[i];
+        for (pad, pad_ind) in sim_state.pads.iter_mut().zip(&self.pad_order) {
+            if let Some(pad_state) = state_wrapper.pads.get(*pad_ind) {
+                pad.state = *pad_state;
+            }
         };
 
         // cars
@@ -282,21 +229,15 @@ impl RocketsimWrapper {
             // reset the state in order to reset demo, flip timers, etc.
             car_info.state = CarState::default();
 
-            car_info.state.pos = Vec3::new(car_wrapper.position.x, car_wrapper.position.y, car_wrapper.position.z);
-            car_info.state.vel = Vec3::new(car_wrapper.linear_velocity.x, car_wrapper.linear_velocity.y, car_wrapper.linear_velocity.z);
-            car_info.state.ang_vel = Vec3::new(car_wrapper.angular_velocity.x, car_wrapper.angular_velocity.y, car_wrapper.angular_velocity.z);
-
-            let wrapper_rot_mtx = car_wrapper.rotation.euler_to_rotation();
-            car_info.state.rot_mat = RotMat {
-                forward: Vec3::new(wrapper_rot_mtx.array[0][0], wrapper_rot_mtx.array[1][0], wrapper_rot_mtx.array[2][0]),
-                right: Vec3::new(wrapper_rot_mtx.array[0][1], wrapper_rot_mtx.array[1][1], wrapper_rot_mtx.array[2][1]),
-                up: Vec3::new(wrapper_rot_mtx.array[0][2], wrapper_rot_mtx.array[1][2], wrapper_rot_mtx.array[2][2]),
-            };
+            car_info.state.pos = car_wrapper.position.into();
+            car_info.state.vel = car_wrapper.linear_velocity.into();
+            car_info.state.ang_vel = car_wrapper.angular_velocity.into();
+            car_info.state.rot_mat = car_wrapper.rotation.euler_to_rotation().into();
 
             if self.arena.get_mutator_config().boost_used_per_second == 0. {
                 car_info.state.boost = 100.;
             } else {
-                car_info.state.boost = car_wrapper.boost * 100.;
+                car_info.state.boost = car_wrapper.boost * ROCKETSIM_BOOST_MAX;
             }
 
             self.arena.pin_mut().set_car_controls(car_info.id, CarControls::default()).unwrap();
@@ -306,19 +247,12 @@ impl RocketsimWrapper {
         // reset ball state
         sim_state.ball = BallState::default();
 
-        sim_state.ball.pos = Vec3::new(state_wrapper.ball.position.x, state_wrapper.ball.position.y, state_wrapper.ball.position.z);
-        sim_state.ball.vel = Vec3::new(
-            state_wrapper.ball.linear_velocity.x,
-            state_wrapper.ball.linear_velocity.y,
-            state_wrapper.ball.linear_velocity.z,
-        );
-        sim_state.ball.ang_vel = Vec3::new(
-            state_wrapper.ball.angular_velocity.x,
-            state_wrapper.ball.angular_velocity.y,
-            state_wrapper.ball.angular_velocity.z,
-        );
+        sim_state.ball.pos = state_wrapper.ball.position.into();
+        sim_state.ball.vel = state_wrapper.ball.linear_velocity.into();
+        sim_state.ball.ang_vel = state_wrapper.ball.angular_velocity.into();
 
         self.arena.pin_mut().set_game_state(&sim_state).unwrap();
+        self.episode_start_tick = self.arena.get_tick_count();
 
         // println!("Set ball state");
         // let sim_state = self.arena.pin_mut().get_game_state();
@@ -329,45 +263,23 @@ impl RocketsimWrapper {
     // used for state setting from RLViser (which returns a sim state)
     pub fn set_state_sim(&mut self, sim_state: GameState_sim) -> GameState_rlgym {
         self.arena.pin_mut().set_game_state(&sim_state).unwrap();
+        self.episode_start_tick = self.arena.get_tick_count();
         self.decode_gamestate(&sim_state)
     }
 
     fn decode_gamestate(&mut self, sim_gamestate: &GameState_sim) -> GameState_rlgym {
         let curr_tick = self.arena.get_tick_count();
+        let tick_count = curr_tick.saturating_sub(self.episode_start_tick);
 
         let mut ball = PhysicsObject::new();
-        ball.position = Position {
-            x: sim_gamestate.ball.pos.x,
-            y: sim_gamestate.ball.pos.y,
-            z: sim_gamestate.ball.pos.z,
-        };
-        ball.linear_velocity = Velocity {
-            x: sim_gamestate.ball.vel.x,
-            y: sim_gamestate.ball.vel.y,
-            z: sim_gamestate.ball.vel.z,
-        };
-        ball.angular_velocity = Velocity {
-            x: sim_gamestate.ball.ang_vel.x,
-            y: sim_gamestate.ball.ang_vel.y,
-            z: sim_gamestate.ball.ang_vel.z,
-        };
+        ball.position = sim_gamestate.ball.pos.into();
+        ball.linear_velocity = sim_gamestate.ball.vel.into();
+        ball.angular_velocity = sim_gamestate.ball.ang_vel.into();
 
         let mut inverted_ball = PhysicsObject::new();
-        inverted_ball.position = Position {
-            x: -sim_gamestate.ball.pos.x,
-            y: -sim_gamestate.ball.pos.y,
-            z: sim_gamestate.ball.pos.z,
-        };
-        inverted_ball.linear_velocity = Velocity {
-            x: -sim_gamestate.ball.vel.x,
-            y: -sim_gamestate.ball.vel.y,
-            z: sim_gamestate.ball.vel.z,
-        };
-        inverted_ball.angular_velocity = Velocity {
-            x: -sim_gamestate.ball.ang_vel.x,
-            y: -sim_gamestate.ball.ang_vel.y,
-            z: sim_gamestate.ball.ang_vel.z,
-        };
+        inverted_ball.position = Position::from(sim_gamestate.ball.pos).invert();
+        inverted_ball.linear_velocity = Velocity::from(sim_gamestate.ball.vel).invert();
+        inverted_ball.angular_velocity = Velocity::from(sim_gamestate.ball.ang_vel).invert();
 
         let mut players = Vec::with_capacity(sim_gamestate.cars.len());
 
@@ -378,25 +290,11 @@ impl RocketsimWrapper {
             let car = car_info.state;
 
             let mut car_data = PhysicsObject::new();
-            car_data.position = Position {
-                x: car.pos.x,
-                y: car.pos.y,
-                z: car.pos.z,
-            };
-            car_data.linear_velocity = Velocity {
-                x: car.vel.x,
-                y: car.vel.y,
-                z: car.vel.z,
-            };
-            car_data.angular_velocity = Velocity {
-                x: car.ang_vel.x,
-                y: car.ang_vel.y,
-                z: car.ang_vel.z,
-            };
+            car_data.position = car.pos.into();
+            car_data.linear_velocity = car.vel.into();
+            car_data.angular_velocity = car.ang_vel.into();
             
-            car_data.rotation_mtx.array[0] = [car.rot_mat.forward.x, car.rot_mat.right.x, car.rot_mat.up.x];
-            car_data.rotation_mtx.array[1] = [car.rot_mat.forward.y, car.rot_mat.right.y, car.rot_mat.up.y];
-            car_data.rotation_mtx.array[2] = [car.rot_mat.forward.z, car.rot_mat.right.z, car.rot_mat.up.z];
+            car_data.rotation_mtx = car.rot_mat.into();
             car_data.has_computed_rot_mtx = true;
 
             car_data.quaternion = car_data.rotation_mtx.rotation_to_quaternion();
@@ -405,21 +303,9 @@ impl RocketsimWrapper {
             car_data.has_computed_euler_angles = true;
 
             let mut inverted_car_data = PhysicsObject::new();
-            inverted_car_data.position = Position {
-                x: -car.pos.x,
-                y: -car.pos.y,
-                z: car.pos.z,
-            };
-            inverted_car_data.linear_velocity = Velocity {
-                x: -car.vel.x,
-                y: -car.vel.y,
-                z: car.vel.z,
-            };
-            inverted_car_data.angular_velocity = Velocity {
-                x: -car.ang_vel.x,
-                y: -car.ang_vel.y,
-                z: car.ang_vel.z,
-            };
+            inverted_car_data.position = Position::from(car.pos).invert();
+            inverted_car_data.linear_velocity = Velocity::from(car.vel).invert();
+            inverted_car_data.angular_velocity = Velocity::from(car.ang_vel).invert();
             inverted_car_data.quaternion = car_data.quaternion.invert();
             // no particular reason to do this I think other than to match that car_data also has a computed rot_mtx
             // previously the behavior was that each clone of the gamestate would have to recompute if not already computed
@@ -476,7 +362,7 @@ impl RocketsimWrapper {
             let player = PlayerData {
                 car_id: car_id as i32,
                 team_num: if car_info.team == Team::Blue { BLUE_TEAM } else { ORANGE_TEAM },
-                match_goals: (orange_score + blue_score) as i64,
+                match_goals: stats.goals as i64,
                 // TODO: adapt PlayerData struct to structs that represent better RocketSim data
                 match_saves: stats.saves as i64,
                 match_shots: stats.shots as i64,
@@ -497,42 +383,204 @@ impl RocketsimWrapper {
                 ball_info: car.ball_hit_info,
                 has_jump: !car.has_jumped,
                 has_flip: car.air_time_since_jump < self.jump_timer && !(car.has_flipped || car.has_double_jumped),
-                boost_amount: (car.boost / 100.),
+                boost_amount: (car.boost / ROCKETSIM_BOOST_MAX),
                 car_data,
                 inverted_car_data,
                 last_ball_touch_tick: last_touch_tick,
                 last_actions: car_info.state.last_controls,
+                hitbox_size: [car_info.config.hitbox_size.x, car_info.config.hitbox_size.y, car_info.config.hitbox_size.z],
             };
             players.push(player);
         }
         players.sort_unstable_by_key(|p| p.car_id);
+        if !self.agent_order.iter().enumerate().all(|(agent, car_index)| agent == *car_index) {
+            players = self.agent_order.iter().map(|car_index| players[*car_index]).collect();
+        }
+
+        // the demos are only given once, in the first state decoded after them
+        let demo_events = Self::DEMO_EVENTS.with(|events| {
+            events
+                .write()
+                .unwrap()
+                .drain(..)
+                .filter_map(|(demolisher, victim)| Some((*self.car_id_map.get(&demolisher)?, *self.car_id_map.get(&victim)?)))
+                .collect()
+        });
+
+        // the hit info of the cars is reset when the state is set so this is the last touch since then
+        let last_touch_player = players
+            .iter()
+            .filter(|player| player.ball_info.is_valid)
+            .max_by_key(|player| player.ball_info.tick_count_when_hit);
+        let (last_touch_car_id, last_touch_location) = match last_touch_player {
+            Some(player) => {
+                let hit_info = &player.ball_info;
+                let (ball_pos, rel_pos) = (hit_info.ball_pos, hit_info.relative_pos_on_ball);
+                (Some(player.car_id), [ball_pos.x + rel_pos.x, ball_pos.y + rel_pos.y, ball_pos.z + rel_pos.z])
+            },
+            None => (None, [0.; 3]),
+        };
 
-        // TODO: make this just the actual boost pad stats instead of is_active
         let mut pad_vec = [BoostPad::default(); 34];
-        for (pad, vec_item) in sim_gamestate.pads.iter().zip(&mut pad_vec) {
-            *vec_item = *pad;
+        let mut pad_timers = [0.; 34];
+        for (pad, pad_ind) in sim_gamestate.pads.iter().zip(&self.pad_order) {
+            if *pad_ind < pad_vec.len() {
+                pad_vec[*pad_ind] = *pad;
+                pad_timers[*pad_ind] = if pad.state.is_active { 0. } else { pad.state.cooldown };
+            }
         }
         let mut pad_reversed = pad_vec;
         pad_reversed.reverse();
+        let mut pad_timers_reversed = pad_timers;
+        pad_timers_reversed.reverse();
         GameState_rlgym {
-            game_type: 0,
+            game_type: self.arena.get_game_mode() as u8 as i32,
             blue_score,
             orange_score,
             last_touch: 0,
+            last_touch_car_id,
+            last_touch_location,
+            demo_events,
             players,
             ball,
             inverted_ball,
             boost_pads: pad_vec,
             inverted_boost_pads: pad_reversed,
+            boost_pads_timers: pad_timers,
+            inverted_boost_pads_timers: pad_timers_reversed,
             tick_num: curr_tick,
+            tick_count,
+            seconds_elapsed: tick_count as f32 / self.arena.get_tick_rate(),
         }
     }
 
-    pub fn set_game_config(&mut self, new_config: GameConfig, get_sim_state: bool) -> (GameState_rlgym, Option<GameState_sim>) {
+    /// Sets the gravity and boost consumption (as multipliers of the defaults) of the arena, 
+    /// unlike `set_game_config` this does not reset anything so it can be done in the middle of an episode
+    pub fn set_mutators(&mut self, gravity: f32, boost_consumption: f32) {
         let mut sim_mutator_config = self.arena.get_mutator_config();
-        sim_mutator_config.gravity.z = GRAVITY_Z * new_config.gravity;
-        sim_mutator_config.boost_used_per_second = ROCKETSIM_BOOST_PER_SEC * new_config.boost_consumption;
+        sim_mutator_config.gravity.z = GRAVITY_Z * gravity;
+        sim_mutator_config.boost_used_per_second = ROCKETSIM_BOOST_PER_SEC * boost_consumption;
         self.arena.pin_mut().set_mutator_config(sim_mutator_config);
+    }
+
+    /// updates the scores and the goal stats, tick_skip (the user data of the callback) is used so that a goal is only counted once. 
+    /// 
+    /// The sim decides when the ball is scored for the game mode (soccar style goals for Soccar, Heatseeker and Snowday, the hoop for Hoops) 
+    /// and `team` is the team that scored, so the scores in the `GameState` (and so `get_result` and `GoalScoredCondition`) work the same in all of them. 
+    /// Heatseeker goals can be scored into either net no matter where the ball was heading, the scoring team is only given by the net the ball went into.
+    fn goal_scored_callback(mut arena: Pin<&mut Arena>, team: Team, tick_skip: usize) {
+        let curr_tick = arena.as_mut().get_tick_count();
+        let tick_skip = tick_skip as u64;
+
+        // -- This section is for orange and blue scores --
+        let last_goal_tick = Self::LAST_GOAL_TICK.with(|val| *val.read().unwrap());
+
+        // make it so that tick skip doesn't count multiple goals scored
+        if curr_tick < last_goal_tick + tick_skip {
+            Self::LAST_GOAL_TICK.with(|val| {
+                let mut ref_val = val.write().unwrap();
+                *ref_val = curr_tick;
+            });
+            return;
+        }
+
+        if team == Team::Blue {
+            Self::BLUE_SCORE.with(|val| *val.write().unwrap() += 1);
+        } else {
+            Self::ORANGE_SCORE.with(|val| *val.write().unwrap() += 1);
+        }
+
+        // value that holds the last tick the goal was scored from
+        Self::LAST_GOAL_TICK.with(|val| *val.write().unwrap() = curr_tick);
+        // -- end of section --
+
+        // -- start of stats section --
+        // section adapted from stat_tracker in bindings made by VirxEC
+
+        // Collect all valid ball touches
+        let mut all_ball_touches = arena
+            .as_mut()
+            .get_car_infos()
+            .into_iter()
+            .filter_map(|car_info| {
+                if car_info.state.ball_hit_info.is_valid {
+                    Some((car_info.id, car_info.team, car_info.state.ball_hit_info.tick_count_when_hit))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        // Sort by ball touch time
+        all_ball_touches.sort_by_key(|(_, _, tick_count_when_hit)| *tick_count_when_hit);
+
+        // Sort ball touches by team
+        let ball_touches = [
+            all_ball_touches.iter().filter(|(_, team, _)| *team == Team::Blue).map(|(id, _, _)| *id).collect::<Vec<_>>(),
+            all_ball_touches
+                .iter()
+                .filter(|(_, team, _)| *team == Team::Orange)
+                .map(|(id, _, _)| *id)
+                .collect::<Vec<_>>(),
+        ];
+
+        // update stats
+        let t_index = team as u8 as usize;
+
+        Self::STATS.with(|stats| {
+            // it's possible no car touched the ball on the team that got the goal
+            // so ensure that were was at least one ball touch
+            if !ball_touches[t_index].is_empty() {
+                // the latest ball touch on the same team is the scorer
+                let scorer = ball_touches[t_index].last().copied().unwrap();
+                // println!("Car {scorer} SCORED");
+
+                let mut guard = stats.write().unwrap();
+                // +1 to the car's goals stat
+                guard.iter_mut().find(|(id, _)| *id == scorer).unwrap().1.goals += 1;
+
+                if ball_touches[t_index].len() > 1 {
+                    // if there were two ball touches, they get the assist
+                    let assist = ball_touches[t_index][ball_touches[t_index].len() - 2];
+
+                    // Get the tick count of when the scorer and assist touched the ball
+                    let scorer_tick = arena.as_mut().get_car(scorer).ball_hit_info.tick_count_when_hit;
+                    let assist_tick = arena.as_mut().get_car(assist).ball_hit_info.tick_count_when_hit;
+
+                    // ensure that the assist is < 5s before the touch of the scoring player
+                    if (scorer_tick - assist_tick) as f32 / arena.get_tick_rate() < 5. {
+                        // println!("CAR {assist} got an ASSIST");
+
+                        // +1 to the car's assists stat
+                        guard.iter_mut().find(|(id, _)| id == &assist).unwrap().1.assists += 1;
+                    }
+                }
+
+                if let Some(latest_hit_id) = all_ball_touches.last().map(|(id, _, _)| *id) {
+                    // if the last hit was not the scorer, they get the own goal
+                    // rocket league tracks this stat in secret and isn't shown on the scoreboard
+                    if latest_hit_id != scorer {
+                        // println!("CAR {latest_hit_id} OWN GOALED");
+
+                        // +1 to the car's own goals stat
+                        guard.iter_mut().find(|(id, _)| *id == latest_hit_id).unwrap().1.own_goals += 1;
+                    }
+                }
+            }
+        });
+        // -- end of stats section --
+    }
+
+    pub fn set_game_config(&mut self, new_config: GameConfig, get_sim_state: bool) -> (GameState_rlgym, Option<GameState_sim>) {
+        // the game mode can only be set when making the arena so we need to start over with a new one
+        if new_config.game_mode != self.arena.get_game_mode() {
+            Self::STATS.with(|stats| stats.write().unwrap().clear());
+            let tick_observer = self.tick_observer.take();
+            *self = Self::new(new_config);
+            self.tick_observer = tick_observer;
+            return self.get_rlgym_gamestate(get_sim_state);
+        }
+
+        self.set_mutators(new_config.gravity, new_config.boost_consumption);
 
         let mut car_ids = self.arena.get_cars();
         let mut car_blue = 0;
@@ -552,8 +600,8 @@ impl RocketsimWrapper {
         // } else {
         //     new_config.team_size
         // };
-        let car_count_blue = new_config.team_size;
-        let car_count_orange = if new_config.spawn_opponents {new_config.team_size} else {0};
+        let car_count_blue = new_config.blue_count();
+        let car_count_orange = new_config.orange_count();
 
         // NOTE: need to check if the old car and new car settings are the same, sadly don't think we can check via if the reference points to the same thing?
         let new_hitbox_size = new_config.car_config.hitbox_size;
@@ -572,31 +620,12 @@ impl RocketsimWrapper {
             self.car_id_map.clear();
     
             // let mut car_ids = Vec::new();
-            if new_config.spawn_opponents {
-                let mut i = 1;
-                // spawn blue cars
-                for _ in 0..new_config.team_size {
-                    let car_id = self.arena.pin_mut().add_car(Team::Blue, new_config.car_config);
-                    self.car_id_map.insert(car_id, i);
-                    car_ids.push(car_id);
-                    i += 1;
-                }
-                // spawn orange cars
-                for _ in 0..new_config.team_size {
-                    let car_id = self.arena.pin_mut().add_car(Team::Orange, new_config.car_config);
-                    self.car_id_map.insert(car_id, i);
-                    car_ids.push(car_id);
-                    i += 1;
-                }
-            } else {
-                let mut i = 1;
-                // spawn blue cars
-                for _ in 0..new_config.team_size as u32 {
-                    let car_id = self.arena.pin_mut().add_car(Team::Blue, new_config.car_config);
-                    self.car_id_map.insert(car_id, i);
-                    car_ids.push(car_id);
-                    i += 1;
-                }
+            // spawn blue cars then orange cars, gym car ids start at 1
+            let teams = std::iter::repeat_n(Team::Blue, car_count_blue).chain(std::iter::repeat_n(Team::Orange, car_count_orange));
+            for (i, team) in teams.enumerate() {
+                let car_id = self.arena.pin_mut().add_car(team, new_config.car_config_for(i));
+                self.car_id_map.insert(car_id, i as i32 + 1);
+                car_ids.push(car_id);
             }
         }
         // for car_id in car_ids {
@@ -635,7 +664,15 @@ impl RocketsimWrapper {
             }
         });
 
+        self.stat_touched_ticks.clear();
+        Self::DEMO_EVENTS.with(|events| events.write().unwrap().clear());
+
         self.car_ids = car_ids;
+        self.agent_order = (0..self.car_ids.len()).collect();
+        if self.tick_skip != new_config.tick_skip {
+            // the goal callback was given the old tick skip
+            self.arena.pin_mut().set_goal_scored_callback(Self::goal_scored_callback, new_config.tick_skip);
+        }
         self.tick_skip = new_config.tick_skip;
         self.car_config = new_config.car_config;
 
@@ -654,6 +691,106 @@ impl RocketsimWrapper {
         // self.decode_gamestate(&rlsim_gamestate)
     }
 
+    /// Predicts the ball for num_steps steps of dt seconds (rounded to whole ticks, at least one) from the current state of the arena.
+    /// 
+    /// The prediction is done on a clone of the arena without the cars (and without the goal callbacks so the scores and stats are untouched),
+    /// the ball state after each step is returned in order.
+    pub fn predict_ball(&self, num_steps: usize, dt: f32) -> Vec<BallState> {
+        let mut arena = self.arena.clone(false);
+        for car_id in arena.get_cars() {
+            arena.pin_mut().remove_car(car_id).unwrap();
+        }
+
+        let ticks_per_step = (dt * arena.get_tick_rate()).round().max(1.) as u32;
+        let mut prediction = Vec::with_capacity(num_steps);
+        for _ in 0..num_steps {
+            arena.pin_mut().step(ticks_per_step);
+            prediction.push(arena.pin_mut().get_ball());
+        }
+        prediction
+    }
+
+    /// returns the stats of each car (since the game config was last set) along with the car id used in the gym, sorted by that id
+    pub fn get_stats(&self) -> Vec<(i32, Stats)> {
+        let mut stats_vec = Self::STATS.with(|stats| {
+            let guard = stats.read().unwrap();
+            guard
+                .iter()
+                .filter_map(|(id, stats)| self.car_id_map.get(id).map(|car_id| (*car_id, *stats)))
+                .collect::<Vec<_>>()
+        });
+        stats_vec.sort_unstable_by_key(|(car_id, _)| *car_id);
+        self.agent_order.iter().filter_map(|car_index| stats_vec.get(*car_index).copied()).collect()
+    }
+
+    /// Sets which car each agent controls, `agent_order[i]` is the index of the car (its gym car id - 1) that agent i controls.
+    /// The players of the decoded states, the actions given to `step` and the stats all follow this order.
+    ///
+    /// Panics if the order is not a permutation of the cars.
+    pub fn set_agent_order(&mut self, agent_order: Vec<usize>) {
+        let mut sorted = agent_order.clone();
+        sorted.sort_unstable();
+        assert!(sorted.iter().copied().eq(0..self.car_ids.len()), "agent order {agent_order:?} is not a permutation of the {} cars", self.car_ids.len());
+        self.agent_order = agent_order;
+    }
+
+    pub fn agent_order(&self) -> &[usize] {
+        &self.agent_order
+    }
+
+    /// checks for new ball touches on the latest tick and counts shots and saves from them
+    fn update_touch_stats(&mut self, prev_ball: &BallState) {
+        let ball = self.arena.pin_mut().get_ball();
+        let gravity_z = self.arena.get_mutator_config().gravity.z;
+
+        for car_id in self.car_ids.iter() {
+            let ball_hit_info = self.arena.pin_mut().get_car(*car_id).ball_hit_info;
+            if !ball_hit_info.is_valid {
+                continue;
+            }
+            // touch was already counted
+            if self.stat_touched_ticks.insert(*car_id, ball_hit_info.tick_count_when_hit) == Some(ball_hit_info.tick_count_when_hit) {
+                continue;
+            }
+
+            let (own_goal_y, opponent_goal_y) = if self.arena.get_car_team(*car_id) == Team::Blue {
+                (-BACK_WALL_Y, BACK_WALL_Y)
+            } else {
+                (BACK_WALL_Y, -BACK_WALL_Y)
+            };
+
+            let shot = ball_heading_into_goal(&ball, opponent_goal_y, gravity_z);
+            let save = ball_heading_into_goal(prev_ball, own_goal_y, gravity_z) && !ball_heading_into_goal(&ball, own_goal_y, gravity_z);
+
+            if shot || save {
+                Self::STATS.with(|stats| {
+                    let mut guard = stats.write().unwrap();
+                    if let Some((_, car_stats)) = guard.iter_mut().find(|(id, _)| id == car_id) {
+                        car_stats.shots += shot as u32;
+                        car_stats.saves += save as u32;
+                    }
+                });
+            }
+        }
+    }
+
+    /// steps the arena a single tick and updates the per-tick data (on ground flags, stats)
+    fn step_tick(&mut self) {
+        let prev_ball = self.arena.pin_mut().get_ball();
+        self.arena.pin_mut().step(1);
+        self.check_on_ground();
+        self.update_touch_stats(&prev_ball);
+        if let Some(observer) = self.tick_observer.as_mut() {
+            let tick_state = self.arena.pin_mut().get_game_state();
+            observer.on_tick(&tick_state);
+        }
+    }
+
+    /// Sets (or removes with None) the observer that is called after every physics tick of `step`
+    pub fn set_tick_observer(&mut self, observer: Option<Box<dyn TickObserver>>) {
+        self.tick_observer = observer;
+    }
+
     fn check_on_ground(&mut self) {
         let new_iter = self.arena
         .get_cars()
@@ -665,14 +802,14 @@ impl RocketsimWrapper {
         }
     }
 
-    /// clone actions before this to set prev_acts
-    pub fn step(&mut self, actions: Vec<Vec<f32>>, get_sim_state: bool) -> (GameState_rlgym, Option<Vec<GameState_sim>>) {
+    /// sets the controls of the cars from one parsed action per agent
+    fn set_controls(&mut self, actions: &[Vec<f32>]) {
         let mut acts = Vec::<(u32, CarControls)>::new();
 
         // package spectator ids with the corresponding action to send to arena
-        for (spectator_id, action) in self.car_ids.iter().zip(actions) {
+        for (car_index, action) in self.agent_order.iter().zip(actions) {
             acts.push((
-                *spectator_id,
+                self.car_ids[*car_index],
                 CarControls {
                     throttle: action[0],
                     steer: action[1],
@@ -687,41 +824,72 @@ impl RocketsimWrapper {
         }
 
         self.arena.pin_mut().set_all_controls(&acts).unwrap();
+    }
+
+    /// Same as `step` but with separate actions for each tick, `actions_per_tick` must have `tick_skip` lists of one parsed action per agent
+    /// (see `ActionParser::tick_schedule`)
+    pub fn step_scheduled(&mut self, actions_per_tick: Vec<Vec<Vec<f32>>>, get_sim_state: bool) -> (GameState_rlgym, Option<Vec<GameState_sim>>) {
+        let ticks = actions_per_tick.len();
+        assert!(ticks == self.tick_skip, "tick schedule (len: {ticks}) did not match the tick skip ({})", self.tick_skip);
 
         self.on_ground_vec.fill(false);
 
-        self.arena.pin_mut().step(1);
+        let mut gamestate_sim_vec = Vec::new();
+        for actions in &actions_per_tick {
+            self.set_controls(actions);
+            self.step_tick();
+            if get_sim_state {
+                gamestate_sim_vec.push(self.arena.pin_mut().get_game_state());
+            }
+        }
 
-        self.check_on_ground();
+        // same as `step`, the state is taken after the last tick
+        let (gamestate_rlgym, _) = self.get_rlgym_gamestate(false);
+        (gamestate_rlgym, get_sim_state.then_some(gamestate_sim_vec))
+    }
 
-        let (gamestate_rlgym, gamestate_sim) = self.get_rlgym_gamestate(get_sim_state);
+    /// clone actions before this to set prev_acts. 
+    /// 
+    /// Steps `tick_skip` ticks with the same actions, the returned state is taken after the last tick (like RLGym)
+    /// so it matches the arena, on ground is set if the car was on the ground on any of the ticks.
+    pub fn step(&mut self, actions: Vec<Vec<f32>>, get_sim_state: bool) -> (GameState_rlgym, Option<Vec<GameState_sim>>) {
+        self.set_controls(&actions);
 
-        // originally was here
-        // self.arena.pin_mut().step(self.tick_skip as i32);
+        self.on_ground_vec.fill(false);
 
         // TODO: need to somehow extract ball hit information from every step probably
-        if get_sim_state {
-            let mut gamestate_sim_vec = Vec::new();
-            gamestate_sim_vec.push(gamestate_sim.unwrap());
-            
-            if self.tick_skip > 1 {
-                for _ in 0..self.tick_skip-1 {
-                    self.arena.pin_mut().step(1);
-                    self.check_on_ground();
-                    gamestate_sim_vec.push(self.arena.pin_mut().get_game_state());
-                }
+        let mut gamestate_sim_vec = Vec::new();
+        for _ in 0..self.tick_skip {
+            self.step_tick();
+            if get_sim_state {
+                gamestate_sim_vec.push(self.arena.pin_mut().get_game_state());
             }
+        }
 
-            (gamestate_rlgym, Some(gamestate_sim_vec))
-        } else {
-            if self.tick_skip > 1 {
-                for _ in 0..self.tick_skip-1 {
-                    self.arena.pin_mut().step(1);
-                    self.check_on_ground();
-                }
-            }
+        let (gamestate_rlgym, _) = self.get_rlgym_gamestate(false);
+        (gamestate_rlgym, get_sim_state.then_some(gamestate_sim_vec))
+    }
+}
 
-            (gamestate_rlgym, None)
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocketsim_rs::math::Vec3;
+
+    #[test]
+    fn ball_heading_into_goal_checks_path() {
+        let mut ball = BallState::default();
+        ball.pos = Vec3::new(0., 3000., 200.);
+        ball.vel = Vec3::new(0., 2000., 300.);
+        assert!(ball_heading_into_goal(&ball, BACK_WALL_Y, GRAVITY_Z));
+        assert!(!ball_heading_into_goal(&ball, -BACK_WALL_Y, GRAVITY_Z), "ball is moving away from this goal");
+
+        // wide of the post
+        ball.vel = Vec3::new(1000., 2000., 300.);
+        assert!(!ball_heading_into_goal(&ball, BACK_WALL_Y, GRAVITY_Z));
+
+        // too slow to reach the goal within the time limit
+        ball.vel = Vec3::new(0., 500., 300.);
+        assert!(!ball_heading_into_goal(&ball, BACK_WALL_Y, GRAVITY_Z));
     }
 }
@@ -13,7 +13,7 @@ impl CombinedStateGenerator {
     pub fn new(modifiers: Vec<Box<dyn StateModifier>>, random_state_init_op: Option<bool>) -> Self {
         let random_state_init = random_state_init_op.unwrap_or(false);
         let state_setter: Box<dyn StateSetter> = if random_state_init {
-            Box::new(RandomState::new(Some(true), Some(true), Some(false), None))
+            Box::new(RandomState::new(Some(true), Some(true), Some(false), None, None))
         } else {
             Box::new(DefaultState::new(None))
         };
@@ -1,20 +1,32 @@
 use rand::{rngs::SmallRng, thread_rng, Rng, SeedableRng};
 use std::f32::consts::PI;
 
+use crate::common_values::{BOOST_MAX, CAR_MAX_SPEED, KICKOFF_BOOST};
 use crate::gamestates::physics_object::{Position, Velocity, EulerAngle};
 
 use super::{state_setter::StateSetter, wrappers::state_wrapper::StateWrapper};
 
-/// State setter that creates a default Rocket League state
+/// State setter that creates a default Rocket League state (a kickoff), 
+/// the spawns are shuffled every reset unless it was made with `DefaultState::deterministic`
 pub struct DefaultState {
     spawn_blue_pos: Vec<Vec<f32>>,
     spawn_blue_yaw: Vec<f32>,
     spawn_orange_pos: Vec<Vec<f32>>,
     spawn_orange_yaw: Vec<f32>,
     rng: SmallRng,
+    shuffle: bool,
 }
 
 impl DefaultState {
+    /// Fixed kickoff without any randomness, the n-th car of each team always gets the n-th spawn 
+    /// (diagonals first, then the off-centers and then the back center)
+    pub fn deterministic() -> Self {
+        DefaultState {
+            shuffle: false,
+            ..Self::new(Some(0))
+        }
+    }
+
     pub fn new(seed: Option<u64>) -> Self {
         let seed = match seed {
             Some(seed) => seed,
@@ -39,6 +51,7 @@ impl DefaultState {
             ],
             spawn_orange_yaw: vec![-0.75 * PI, -0.25 * PI, -0.5 * PI, -0.5 * PI, -0.5 * PI],
             rng,
+            shuffle: true,
         }
     }
 }
@@ -55,7 +68,9 @@ impl StateSetter for DefaultState {
         // let mut rng = rand::thread_rng();
 
         // this is to try to rearrange the order in a randomized way
-        spawn_inds.sort_by_cached_key(|_| self.rng.gen::<usize>());
+        if self.shuffle {
+            spawn_inds.sort_by_cached_key(|_| self.rng.gen::<usize>());
+        }
 
         let mut blue_count = 0;
         let mut orange_count = 0;
@@ -77,7 +92,7 @@ impl StateSetter for DefaultState {
             car.set_lin_vel(Some(0.), Some(0.), Some(0.));
             car.set_ang_vel(Some(0.), Some(0.), Some(0.));
             car.set_rot(Some(0.), Some(yaw), Some(0.));
-            car.boost = 0.33;
+            car.boost = KICKOFF_BOOST;
         }
 
         state_wrapper.ball.position = Position { x: 0., y: 0., z: 91.25 };
@@ -192,7 +207,7 @@ impl StateSetter for DefaultStateTester {
 
             car.set_pos(Some(pos[0]), Some(pos[1]), Some(pos[2]));
             car.set_rot(None, Some(yaw), None);
-            car.boost = 0.33;
+            car.boost = KICKOFF_BOOST;
         }
 
         state_wrapper.ball.position = Position { x: 0., y: 0., z: 91.25 };
@@ -262,7 +277,7 @@ impl StateSetter for DefaultStateTesterPitched {
 
             car.set_pos(Some(pos[0]), Some(pos[1]), Some(pos[2]));
             car.set_rot(Some(0.25 * PI), Some(yaw), Some(0.15 * PI));
-            car.boost = 0.33;
+            car.boost = KICKOFF_BOOST;
         }
 
         state_wrapper.ball.position = Position { x: 0., y: 0., z: 91.25 };
@@ -332,7 +347,7 @@ impl StateSetter for BlueGoalStateTester {
 
             car.set_pos(Some(pos[0]), Some(pos[1]), Some(pos[2]));
             car.set_rot(None, Some(yaw), None);
-            car.boost = 0.33;
+            car.boost = KICKOFF_BOOST;
         }
 
         state_wrapper.ball.position = Position { x: 0., y: 4500., z: 91.25 };
@@ -402,7 +417,7 @@ impl StateSetter for OrangeGoalStateTester {
 
             car.set_pos(Some(pos[0]), Some(pos[1]), Some(pos[2]));
             car.set_rot(None, Some(yaw), None);
-            car.boost = 0.33;
+            car.boost = KICKOFF_BOOST;
         }
 
         state_wrapper.ball.position = Position { x: 0., y: -4900., z: 91.25 };
@@ -472,7 +487,7 @@ impl StateSetter for AgentBallHitStateTester {
 
             car.set_pos(Some(0.), Some(-300.), Some(pos[2]));
             car.set_rot(None, Some(0.5 * PI), None);
-            car.boost = 0.33;
+            car.boost = KICKOFF_BOOST;
         }
 
         state_wrapper.ball.position = Position { x: 0., y: 0., z: 91.25 };
@@ -543,8 +558,8 @@ impl StateSetter for DemoStateTester {
 
         car1.set_pos(Some(0.), Some(-300.), Some(17.));
         car1.set_rot(None, Some(0.5 * PI), None);
-        car1.set_lin_vel(Some(0.), Some(2300.), Some(0.));
-        car1.boost = 1.0;
+        car1.set_lin_vel(Some(0.), Some(CAR_MAX_SPEED), Some(0.));
+        car1.boost = BOOST_MAX;
 
         let car2 = &mut state_wrapper.cars[1];
         // let pos;
@@ -562,8 +577,8 @@ impl StateSetter for DemoStateTester {
 
         car2.set_pos(Some(0.), Some(300.), Some(17.));
         car2.set_rot(None, Some(-0.5 * PI), None);
-        car2.set_lin_vel(Some(0.), Some(-2300.), Some(0.));
-        car2.boost = 1.0;
+        car2.set_lin_vel(Some(0.), Some(-CAR_MAX_SPEED), Some(0.));
+        car2.boost = BOOST_MAX;
 
         state_wrapper.ball.position = Position { x: 4000., y: 0., z: 91.25 };
         state_wrapper.ball.linear_velocity = Velocity { x: 0., y: 0., z: 0. };
@@ -571,3 +586,48 @@ impl StateSetter for DemoStateTester {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_values::{BLUE_TEAM, ORANGE_TEAM};
+
+    #[test]
+    fn deterministic_default_state() {
+        let mut state_setter = DefaultState::deterministic();
+        let mut state_wrapper = state_setter.build_wrapper(2, 2, None);
+        state_setter.reset(&mut state_wrapper);
+
+        let expected = [
+            (BLUE_TEAM, [-2048., -2560., 17.], 0.25 * PI),
+            (BLUE_TEAM, [2048., -2560., 17.], 0.75 * PI),
+            (ORANGE_TEAM, [2048., 2560., 17.], -0.75 * PI),
+            (ORANGE_TEAM, [-2048., 2560., 17.], -0.25 * PI),
+        ];
+        for (car, (team, pos, yaw)) in state_wrapper.cars.iter().zip(expected) {
+            assert_eq!(car.get_team_num(), team);
+            assert_eq!(car.position.into_array(), pos);
+            assert_eq!(car.rotation.into_array(), [0., yaw, 0.]);
+            assert_eq!(car.linear_velocity.into_array(), [0.; 3]);
+            assert_eq!(car.boost, KICKOFF_BOOST);
+        }
+        assert_eq!(state_wrapper.ball.position.into_array(), [0., 0., 91.25]);
+        assert_eq!(state_wrapper.ball.linear_velocity.into_array(), [0.; 3]);
+
+        // repeated resets give the same wrapper
+        for _ in 0..5 {
+            let mut other = state_setter.build_wrapper(2, 2, None);
+            state_setter.reset(&mut other);
+            for (car, other_car) in state_wrapper.cars.iter().zip(&other.cars) {
+                assert_eq!(car.position.into_array(), other_car.position.into_array());
+                assert_eq!(car.rotation.into_array(), other_car.rotation.into_array());
+            }
+        }
+
+        // no opponents
+        let mut state_wrapper = state_setter.build_wrapper(3, 0, None);
+        state_setter.reset(&mut state_wrapper);
+        assert_eq!(state_wrapper.cars.len(), 3);
+        assert_eq!(state_wrapper.cars[2].position.into_array(), [-256., -3840., 17.]);
+        assert!(state_wrapper.cars.iter().all(|car| car.get_team_num() == BLUE_TEAM));
+    }
+}
@@ -0,0 +1,108 @@
+use rand::{rngs::SmallRng, thread_rng, Rng, SeedableRng};
+use std::f32::consts::PI;
+
+use crate::common_values::{BLUE_TEAM, KICKOFF_BOOST};
+use crate::gamestates::physics_object::{Position, Velocity};
+
+use super::{state_setter::StateSetter, wrappers::state_wrapper::StateWrapper};
+
+/// the five standard kickoff spawns for blue (corners, back corners, back center), orange uses the mirrored spawns
+const KICKOFF_BLUE_POS: [[f32; 3]; 5] = [
+    [-2048., -2560., 17.],
+    [2048., -2560., 17.],
+    [-256., -3840., 17.],
+    [256., -3840., 17.],
+    [0., -4608., 17.],
+];
+const KICKOFF_BLUE_YAW: [f32; 5] = [0.25 * PI, 0.75 * PI, 0.5 * PI, 0.5 * PI, 0.5 * PI];
+
+/// State setter that places cars at the standard kickoff spawns with kickoff boost and the ball at rest in the center.
+/// 
+/// The spawns are shuffled with the seeded rng, orange cars take the mirrored spawn of the blue car with the same index on their team.
+/// Only the cars in the wrapper are placed, so without opponents only blue is populated.
+pub struct KickoffLikeState {
+    rng: SmallRng,
+}
+
+impl KickoffLikeState {
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = match seed {
+            Some(seed) => seed,
+            None => thread_rng().gen_range(0..10000),
+        };
+        KickoffLikeState { rng: SmallRng::seed_from_u64(seed) }
+    }
+}
+
+impl Default for KickoffLikeState {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl StateSetter for KickoffLikeState {
+    fn reset(&mut self, state_wrapper: &mut StateWrapper) {
+        let mut spawn_inds = [0, 1, 2, 3, 4];
+        spawn_inds.sort_by_cached_key(|_| self.rng.gen::<usize>());
+
+        let mut blue_count = 0;
+        let mut orange_count = 0;
+        for car in &mut state_wrapper.cars {
+            let (pos, yaw) = if car.get_team_num() == BLUE_TEAM {
+                let spawn_ind = spawn_inds[blue_count];
+                blue_count += 1;
+                (KICKOFF_BLUE_POS[spawn_ind], KICKOFF_BLUE_YAW[spawn_ind])
+            } else {
+                let spawn_ind = spawn_inds[orange_count];
+                orange_count += 1;
+                let pos = KICKOFF_BLUE_POS[spawn_ind];
+                ([-pos[0], -pos[1], pos[2]], KICKOFF_BLUE_YAW[spawn_ind] - PI)
+            };
+
+            car.set_pos(Some(pos[0]), Some(pos[1]), Some(pos[2]));
+            car.set_lin_vel(Some(0.), Some(0.), Some(0.));
+            car.set_ang_vel(Some(0.), Some(0.), Some(0.));
+            car.set_rot(Some(0.), Some(yaw), Some(0.));
+            car.boost = KICKOFF_BOOST;
+        }
+
+        state_wrapper.ball.position = Position { x: 0., y: 0., z: 91.25 };
+        state_wrapper.ball.linear_velocity = Velocity { x: 0., y: 0., z: 0. };
+        state_wrapper.ball.angular_velocity = Velocity { x: 0., y: 0., z: 0. };
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kickoff_like_1v1_positions() {
+        let mut state_setter = KickoffLikeState::new(Some(0));
+        let mut state_wrapper = state_setter.build_wrapper(1, 1, None);
+        state_setter.reset(&mut state_wrapper);
+
+        let blue = &state_wrapper.cars[0];
+        let orange = &state_wrapper.cars[1];
+        let spawn_ind = KICKOFF_BLUE_POS
+            .iter()
+            .position(|pos| *pos == blue.position.into_array())
+            .expect("blue car was not placed at a kickoff spawn");
+        assert_eq!(blue.rotation.yaw, KICKOFF_BLUE_YAW[spawn_ind]);
+        assert_eq!(orange.position.into_array(), [-blue.position.x, -blue.position.y, 17.]);
+        assert_eq!(orange.rotation.yaw, KICKOFF_BLUE_YAW[spawn_ind] - PI);
+        for car in &state_wrapper.cars {
+            assert_eq!(car.boost, KICKOFF_BOOST);
+            assert_eq!(car.linear_velocity.into_array(), [0.; 3]);
+        }
+        assert_eq!(state_wrapper.ball.position.into_array(), [0., 0., 91.25]);
+
+        let mut state_wrapper = state_setter.build_wrapper(2, 0, None);
+        state_setter.reset(&mut state_wrapper);
+        assert!(state_wrapper.cars.iter().all(|car| car.get_team_num() == BLUE_TEAM));
+    }
+}
@@ -1,5 +1,7 @@
 pub mod default_state;
+pub mod kickoff_like_state;
 pub mod random_state;
+pub mod shot_state;
 pub mod state_setter;
 pub mod wrappers;
 pub mod weighted_state_setter;
@@ -1,7 +1,10 @@
 use rand::prelude::*;
 use std::f32::consts::PI;
 
-use crate::math::rand_vec3;
+use crate::{
+    common_values::{CAR_MAX_ANG_VEL, CAR_MAX_SPEED, KICKOFF_BOOST},
+    math::rand_vec3,
+};
 
 use super::{state_setter::StateSetter, wrappers::state_wrapper::StateWrapper};
 
@@ -14,18 +17,23 @@ const YAW_MAX: f32 = PI;
 const ROLL_MAX: f32 = PI;
 
 /// Random state setter that makes random position/velocity/rotation values for each car and for the ball (within reason, eg. below max speeds)
+/// 
+/// Boost is randomized over the full range (0 to 100 in the sim) unless rand_boost is false, then every car gets kickoff boost.
 pub struct RandomState {
     ball_rand_speed: bool,
     cars_rand_speed: bool,
     cars_on_ground: bool,
+    rand_boost: bool,
     rng: SmallRng,
 }
 
 impl RandomState {
-    pub fn new(ball_rand_speed: Option<bool>, cars_rand_speed: Option<bool>, cars_on_ground: Option<bool>, seed: Option<u64>) -> Self {
+    /// all flags default to false except for rand_boost which defaults to true, seed defaults to a random seed
+    pub fn new(ball_rand_speed: Option<bool>, cars_rand_speed: Option<bool>, cars_on_ground: Option<bool>, rand_boost: Option<bool>, seed: Option<u64>) -> Self {
         let ball_rand_speed = ball_rand_speed.unwrap_or(false);
         let cars_rand_speed = cars_rand_speed.unwrap_or(false);
         let cars_on_ground = cars_on_ground.unwrap_or(false);
+        let rand_boost = rand_boost.unwrap_or(true);
         let seed = match seed {
             Some(seed) => seed,
             None => thread_rng().gen_range(0..10000),
@@ -36,6 +44,7 @@ impl RandomState {
             ball_rand_speed,
             cars_rand_speed,
             cars_on_ground,
+            rand_boost,
             rng,
         }
     }
@@ -87,11 +96,11 @@ impl RandomState {
                 Some(self.rng.gen::<f32>() * ROLL_MAX - ROLL_MAX / 2.),
             );
 
-            car.boost = self.rng.gen::<f32>();
+            car.boost = if self.rand_boost { self.rng.gen::<f32>() } else { KICKOFF_BOOST };
 
             if random_speed {
-                let lin_vel = rand_vec3(2300., &mut self.rng);
-                let ang_vel = rand_vec3(5.5, &mut self.rng);
+                let lin_vel = rand_vec3(CAR_MAX_SPEED, &mut self.rng);
+                let ang_vel = rand_vec3(CAR_MAX_ANG_VEL, &mut self.rng);
                 car.set_lin_vel(Some(lin_vel[0]), Some(lin_vel[1]), Some(lin_vel[2]));
                 car.set_ang_vel(Some(ang_vel[0]), Some(ang_vel[1]), Some(ang_vel[2]));
             } else {
@@ -119,3 +128,44 @@ impl StateSetter for RandomState {
         self.rng = SmallRng::seed_from_u64(seed);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_wrapper(seed: u64) -> StateWrapper {
+        let mut state_setter = RandomState::new(Some(true), Some(true), None, None, Some(0));
+        state_setter.set_seed(seed);
+        let mut state_wrapper = StateWrapper::new(Some(2), Some(2), None);
+        state_setter.reset(&mut state_wrapper);
+        state_wrapper
+    }
+
+    #[test]
+    fn random_state_seeded() {
+        let wrapper_a = make_wrapper(42);
+        let wrapper_b = make_wrapper(42);
+        let wrapper_c = make_wrapper(43);
+
+        assert_eq!(wrapper_a.ball.position.into_array(), wrapper_b.ball.position.into_array());
+        assert_eq!(wrapper_a.ball.linear_velocity.into_array(), wrapper_b.ball.linear_velocity.into_array());
+        for (car_a, car_b) in wrapper_a.cars.iter().zip(&wrapper_b.cars) {
+            assert_eq!(car_a.position.into_array(), car_b.position.into_array());
+            assert_eq!(car_a.linear_velocity.into_array(), car_b.linear_velocity.into_array());
+            assert_eq!(car_a.rotation.into_array(), car_b.rotation.into_array());
+            assert_eq!(car_a.boost, car_b.boost);
+        }
+        assert_ne!(wrapper_a.ball.position.into_array(), wrapper_c.ball.position.into_array());
+    }
+
+    #[test]
+    fn random_state_fixed_boost() {
+        let mut state_setter = RandomState::new(None, None, Some(true), Some(false), Some(0));
+        let mut state_wrapper = StateWrapper::new(Some(1), Some(1), None);
+        state_setter.reset(&mut state_wrapper);
+        for car in &state_wrapper.cars {
+            assert_eq!(car.boost, KICKOFF_BOOST);
+            assert_eq!(car.position.z, 17.);
+        }
+    }
+}
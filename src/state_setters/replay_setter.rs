@@ -6,17 +6,30 @@ use ndarray_npy::ViewNpyExt;
 use rand::rngs::SmallRng;
 use rand::Rng;
 use rand::SeedableRng;
+use crate::common_values::{BLUE_TEAM, ORANGE_TEAM};
+use crate::gamestates::game_state::GameState;
 use crate::state_setters::state_setter::StateSetter;
 use crate::state_setters::wrappers::state_wrapper::StateWrapper;
 
+/// where the states of a `ReplaySetter` come from
+enum ReplaySource<'a> {
+    /// rows of ball (9) and then 13 values per car, see `ReplaySetter::new`
+    Npy(ArrayView2<'a, f32>),
+    /// full snapshots, see `ReplaySetter::from_states`
+    States(Vec<GameState>),
+}
+
+/// Resets into states taken from real games, either rows of a npy file (`new`) or full `GameState` snapshots (`from_states`).
 pub struct ReplaySetter<'a> {
-    states: ArrayView2<'a, f32>,
+    source: ReplaySource<'a>,
     rng: SmallRng,
     random_boost: bool,
     random_pads: bool,
 }
 
 impl<'a> ReplaySetter<'a> {
+    /// Loads a 2d f32 npy file where each row is the ball (position, linear velocity, angular velocity) followed by 
+    /// each car (position, rotation as pitch/yaw/roll, linear velocity, angular velocity, boost) in the order of the cars of the state.
     pub fn new(file_str: &str, random_boost: Option<bool>, random_pads: Option<bool>) -> Self {
         let rng = SmallRng::from_entropy();
         let file = File::open(file_str).expect("Make sure your file exists");
@@ -26,7 +39,28 @@ impl<'a> ReplaySetter<'a> {
             .expect("Data types of npy file must be f32 and save a numpy array and be 2d");
         let random_boost = random_boost.unwrap_or(false);
         let random_pads = random_pads.unwrap_or(false);
-        Self { states, rng, random_boost, random_pads}
+        Self { source: ReplaySource::Npy(states), rng, random_boost, random_pads}
+    }
+
+    /// Samples from full state snapshots, the ball, the cars and the boost pads (unless random_pads) of the snapshot are written into the state.
+    /// 
+    /// Only the snapshots with the same number of blue and orange players as the state being reset are used, 
+    /// the cars are filled in the order of the players of each team in the snapshot. Reset panics if no snapshot matches.
+    /// 
+    /// seed defaults to a random seed
+    pub fn from_states(states: Vec<GameState>, random_boost: Option<bool>, random_pads: Option<bool>, seed: Option<u64>) -> Self {
+        assert!(!states.is_empty(), "ReplaySetter needs at least one state");
+        let rng = match seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::from_entropy(),
+        };
+        Self { source: ReplaySource::States(states), rng, random_boost: random_boost.unwrap_or(false), random_pads: random_pads.unwrap_or(false) }
+    }
+
+    /// Same as `from_states` with the states serialized by `GameState::to_vec`
+    pub fn from_state_vecs(state_vecs: &[Vec<f32>], random_boost: Option<bool>, random_pads: Option<bool>, seed: Option<u64>) -> Self {
+        let states = state_vecs.iter().map(|vec| GameState::from_vec(vec)).collect();
+        Self::from_states(states, random_boost, random_pads, seed)
     }
 
     fn set_cars(&mut self, state_wrapper: &mut StateWrapper, state: Vec<f32>) {
@@ -53,6 +87,39 @@ impl<'a> ReplaySetter<'a> {
         state_wrapper.ball.set_ang_vel(Some(data[6]), Some(data[7]), Some(data[8]));
     }
 
+    /// whether the snapshot has as many blue and orange players as the wrapper has cars
+    fn teams_match(state_wrapper: &StateWrapper, state: &GameState) -> bool {
+        let team_counts_match = |team: i32| {
+            state.players.iter().filter(|player| player.team_num == team).count() == state_wrapper.cars.iter().filter(|car| car.get_team_num() == team).count()
+        };
+        state.players.len() == state_wrapper.cars.len() && team_counts_match(BLUE_TEAM) && team_counts_match(ORANGE_TEAM)
+    }
+
+    /// writes a snapshot (with matching teams) into the wrapper
+    fn set_from_state(state_wrapper: &mut StateWrapper, state: &GameState, rng: &mut SmallRng, random_boost: bool) {
+
+        state_wrapper.ball.position = state.ball.position;
+        state_wrapper.ball.linear_velocity = state.ball.linear_velocity;
+        state_wrapper.ball.angular_velocity = state.ball.angular_velocity;
+
+        for team in [BLUE_TEAM, ORANGE_TEAM] {
+            let players = state.players.iter().filter(|player| player.team_num == team);
+            let cars = state_wrapper.cars.iter_mut().filter(|car| car.get_team_num() == team);
+            for (car, player) in cars.zip(players) {
+                car.position = player.car_data.position;
+                car.rotation = player.car_data.euler_angles();
+                car.linear_velocity = player.car_data.linear_velocity;
+                car.angular_velocity = player.car_data.angular_velocity;
+                car.boost = if random_boost { rng.gen_range(0.0..=1.) } else { player.boost_amount };
+            }
+        }
+
+        for (pad, state_pad) in state_wrapper.pads.iter_mut().zip(state.boost_pads.iter()) {
+            pad.is_active = state_pad.state.is_active;
+            pad.cooldown = state_pad.state.cooldown;
+        }
+    }
+
     fn set_pads(&mut self, state_wrapper: &mut StateWrapper){
         let big_pads = [3, 4, 15, 18, 29, 30];
         for (i, pad) in state_wrapper.pads.iter_mut().enumerate(){
@@ -71,17 +138,32 @@ impl<'a> ReplaySetter<'a> {
 
 impl<'a> StateSetter for ReplaySetter<'a> {
     fn reset(&mut self, state_wrapper: &mut StateWrapper) {
-        let index = self.rng.gen_range(0..self.states.dim().0);
-        // dbg!(index);
-        let binding = self.states.index_axis(Axis(0), index);
-        // dbg!(binding);
-        let state = binding.as_slice().unwrap();
-        Self::set_ball(state_wrapper, state);
-        self.set_cars(state_wrapper, state.to_owned());
+        match &self.source {
+            ReplaySource::Npy(states) => {
+                let index = self.rng.gen_range(0..states.dim().0);
+                // dbg!(index);
+                let binding = states.index_axis(Axis(0), index);
+                // dbg!(binding);
+                let state = binding.as_slice().unwrap().to_owned();
+                Self::set_ball(state_wrapper, &state);
+                self.set_cars(state_wrapper, state);
+            }
+            ReplaySource::States(states) => {
+                // snapshots with other team sizes are skipped
+                let matching: Vec<&GameState> = states.iter().filter(|state| Self::teams_match(state_wrapper, state)).collect();
+                assert!(!matching.is_empty(), "none of the ReplaySetter states have the same team sizes as the state being reset");
+                let state = matching[self.rng.gen_range(0..matching.len())];
+                Self::set_from_state(state_wrapper, state, &mut self.rng, self.random_boost);
+            }
+        }
         if self.random_pads{
             self.set_pads(state_wrapper);
         }
     }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+    }
 }
 
 
@@ -89,7 +171,7 @@ impl<'a> StateSetter for ReplaySetter<'a> {
 mod tests{
     use ndarray::Array2;
     use ndarray_npy::write_npy;
-    use rocketsim_rs::sim::CarConfig;
+    use rocketsim_rs::sim::{CarConfig, GameMode};
     use crate::sim_wrapper::wrapper::RocketsimWrapper;
     use super::*;
     
@@ -97,14 +179,14 @@ mod tests{
     fn replay_setter_load_threes(){
         rocketsim_rs::init(None);
         let gameconfig = crate::envs::game_match::GameConfig{team_size: 3, spawn_opponents: true,
-             gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(),};
+             gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false,};
         let mut sim = RocketsimWrapper::new(gameconfig);
         let (state, _) = sim.get_rlgym_gamestate(false);
         let array_to_write = make_test_array();
         let path = "./tests/test_files_npy/test3.npy";
         write_npy(path, &array_to_write).unwrap();
         let mut setter = ReplaySetter::new("./tests/test_files_npy/test3.npy", Some(false), Some(false));
-        let mut wrapper = setter.build_wrapper(3, true, Some(&state));
+        let mut wrapper = setter.build_wrapper(3, 3, Some(&state));
         setter.reset(&mut wrapper);
         let (state, _) = sim.set_state(wrapper, false);
         assert_eq!(state.ball.position.x, 2.);
@@ -117,7 +199,7 @@ mod tests{
     fn replay_setter_load_ones(){
         rocketsim_rs::init(None);
         let gameconfig = crate::envs::game_match::GameConfig{team_size: 1, spawn_opponents: true,
-            gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(),};
+            gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false,};
         let mut sim = RocketsimWrapper::new(gameconfig);
         let (state, _) = sim.get_rlgym_gamestate(false);
         let pos_ball_0_x = 0;
@@ -132,7 +214,7 @@ mod tests{
         let path = "./tests/test_files_npy/test1.npy";
         write_npy(path, &array_to_write).unwrap();
         let mut setter = ReplaySetter::new(path, Some(false), Some(false));
-        let mut wrapper = setter.build_wrapper(1, true, Some(&state));
+        let mut wrapper = setter.build_wrapper(1, 1, Some(&state));
         setter.reset(&mut wrapper);
         let (state, _) = sim.set_state(wrapper, false);
         assert_eq!(state.ball.position.x, 2.);
@@ -154,14 +236,14 @@ mod tests{
     fn replay_setter_random_boost(){
         rocketsim_rs::init(None);
         let gameconfig = crate::envs::game_match::GameConfig{team_size: 3, spawn_opponents: true,
-             gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(),};
+             gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false,};
         let mut sim = RocketsimWrapper::new(gameconfig);
         let (state, _) = sim.get_rlgym_gamestate(false);
         let array_to_write = make_test_array();
         let path = "./tests/test_files_npy/test_boost.npy";
         write_npy(path, &array_to_write).unwrap();
         let mut setter = ReplaySetter::new(path, Some(true), Some(false));
-        let mut wrapper = setter.build_wrapper(3, true, Some(&state));
+        let mut wrapper = setter.build_wrapper(3, 3, Some(&state));
         setter.reset(&mut wrapper);
         let (state, _) = sim.set_state(wrapper, false);
         assert_ne!(state.players[5].boost_amount, 4.);
@@ -171,14 +253,14 @@ mod tests{
     fn replay_setter_random_pads(){
         rocketsim_rs::init(None);
         let gameconfig = crate::envs::game_match::GameConfig{team_size: 3, spawn_opponents: true,
-             gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(),};
+             gravity: 1., boost_consumption: 1., tick_skip: 8, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false,};
         let mut sim = RocketsimWrapper::new(gameconfig);
         let (state, _) = sim.get_rlgym_gamestate(false);
         let array_to_write = make_test_array();
         let path = "./tests/test_files_npy/test_pads.npy";
         write_npy(path, &array_to_write).unwrap();
         let mut setter = ReplaySetter::new(path, Some(false), Some(true));
-        let mut wrapper = setter.build_wrapper(3, true, Some(&state));
+        let mut wrapper = setter.build_wrapper(3, 3, Some(&state));
         setter.reset(&mut wrapper);
         for pad in wrapper.pads.iter(){
             if !pad.is_active{
@@ -190,6 +272,56 @@ mod tests{
         assert!(!state.boost_pads.iter().all(|x| (*x).state.is_active != false));  
     }
 
+    #[test]
+    fn replay_setter_from_states(){
+        let mut snapshots = Vec::new();
+        for i in 0..2 {
+            let mut state = GameState::new_test();
+            state.players[1].team_num = ORANGE_TEAM;
+            state.ball.position.x = 100. * (i + 1) as f32;
+            state.players[0].car_data.position.y = -1000. * (i + 1) as f32;
+            state.players[1].car_data.euler_angles.yaw = 0.5 * (i + 1) as f32;
+            state.players[1].boost_amount = 0.25 * (i + 1) as f32;
+            state.boost_pads.iter_mut().for_each(|pad| pad.state.is_active = true);
+            state.boost_pads[i].state.is_active = false;
+            state.boost_pads[i].state.cooldown = 3.;
+            snapshots.push(state);
+        }
+        // a 2v2 snapshot that can't be used for 1v1
+        let mut twos = snapshots[0].clone();
+        twos.players.extend(snapshots[0].players.clone());
+        twos.ball.position.x = -1.;
+        let mut states = snapshots.clone();
+        states.push(twos);
+
+        let state_vecs: Vec<Vec<f32>> = states.iter().map(GameState::to_vec).collect();
+        for mut setter in [ReplaySetter::from_states(states, None, None, Some(0)), ReplaySetter::from_state_vecs(&state_vecs, None, None, Some(0))] {
+            let mut seen = [false; 2];
+            for _ in 0..20 {
+                let mut wrapper = setter.build_wrapper(1, 1, None);
+                setter.reset(&mut wrapper);
+                let i = snapshots.iter().position(|snapshot| snapshot.ball.position.x == wrapper.ball.position.x)
+                    .expect("reset into a state that is not one of the 1v1 snapshots");
+                let snapshot = &snapshots[i];
+                seen[i] = true;
+                assert_eq!(wrapper.cars[0].position.into_array(), snapshot.players[0].car_data.position.into_array());
+                assert_eq!(wrapper.cars[1].rotation.yaw, snapshot.players[1].car_data.euler_angles.yaw);
+                assert_eq!(wrapper.cars[1].boost, snapshot.players[1].boost_amount);
+                assert!(!wrapper.pads[i].is_active && wrapper.pads[i].cooldown == 3.);
+                assert!(wrapper.pads[1 - i].is_active);
+            }
+            assert_eq!(seen, [true, true]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "same team sizes")]
+    fn replay_setter_from_states_no_match(){
+        let mut setter = ReplaySetter::from_states(vec![GameState::new_test()], None, None, Some(0));
+        let mut wrapper = setter.build_wrapper(3, 3, None);
+        setter.reset(&mut wrapper);
+    }
+
     fn make_test_array() -> ndarray::prelude::ArrayBase<ndarray::OwnedRepr<f32>, ndarray::prelude::Dim<[usize; 2]>> {
         let pos_ball_0_x = 0;
         let pos_car_0_x = 9;
@@ -0,0 +1,157 @@
+use rand::{rngs::SmallRng, thread_rng, Rng, SeedableRng};
+
+use crate::common_values::{BACK_WALL_Y, BALL_RADIUS, BLUE_TEAM, GOAL_HEIGHT, GRAVITY_Z, ORANGE_TEAM};
+use crate::gamestates::physics_object::{Position, Velocity};
+use crate::math::Vec3;
+
+use super::{state_setter::StateSetter, wrappers::state_wrapper::StateWrapper};
+
+/// State setter for shooting practice, the ball is launched from the attacking third toward the goal of the other team
+/// with the first car of the shooting team spawned behind it.
+///
+/// The ball is placed randomly in the shooting team's attacking third and aimed at a point in the goal that is offset by up to
+/// `aim_noise` (uu) sideways and vertically, the launch is ballistic so the ball reaches that point (before bouncing) without touching it.
+/// The other cars of the shooting team wait further behind and the defenders start on their goal line.
+/// The shooting team is drawn every reset from the teams that have cars unless it is given.
+pub struct ShotState {
+    shooting_team: Option<i32>,
+    aim_noise: f32,
+    rng: SmallRng,
+}
+
+impl ShotState {
+    /// shooting_team defaults to a random team every reset, aim_noise defaults to 400. (uu), seed defaults to a random seed
+    pub fn new(shooting_team: Option<i32>, aim_noise: Option<f32>, seed: Option<u64>) -> Self {
+        if let Some(team) = shooting_team {
+            assert!(team == BLUE_TEAM || team == ORANGE_TEAM, "shooting team must be BLUE_TEAM or ORANGE_TEAM");
+        }
+        let seed = match seed {
+            Some(seed) => seed,
+            None => thread_rng().gen(),
+        };
+        ShotState {
+            shooting_team,
+            aim_noise: aim_noise.unwrap_or(400.),
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for ShotState {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl StateSetter for ShotState {
+    fn reset(&mut self, state_wrapper: &mut StateWrapper) {
+        let has_orange = state_wrapper.cars.iter().any(|car| car.get_team_num() == ORANGE_TEAM);
+        let shooting_team = match self.shooting_team {
+            Some(team) => team,
+            None if has_orange && self.rng.gen_bool(0.5) => ORANGE_TEAM,
+            None => BLUE_TEAM,
+        };
+        // everything is generated for blue (attacking +y) and mirrored for orange
+        let side = if shooting_team == BLUE_TEAM { 1. } else { -1. };
+
+        let ball_pos = Vec3::new(
+            self.rng.gen_range(-2500. ..2500.),
+            self.rng.gen_range(BACK_WALL_Y / 3. ..BACK_WALL_Y - 1500.),
+            self.rng.gen_range(BALL_RADIUS..400.),
+        );
+        let target = Vec3::new(
+            self.rng.gen_range(-self.aim_noise..=self.aim_noise),
+            BACK_WALL_Y,
+            (GOAL_HEIGHT / 2. + self.rng.gen_range(-self.aim_noise..=self.aim_noise)).clamp(BALL_RADIUS, GOAL_HEIGHT - BALL_RADIUS),
+        );
+        let speed = self.rng.gen_range(1200. ..2500.);
+        let to_target = Vec3::new(target.x - ball_pos.x, target.y - ball_pos.y, 0.);
+        let flight_time = to_target.norm() / speed;
+        let vel_z = (target.z - ball_pos.z) / flight_time - 0.5 * GRAVITY_Z * flight_time;
+        let ball_vel = to_target.normalized() * speed + Vec3::new(0., 0., vel_z);
+
+        state_wrapper.ball.position = Position { x: ball_pos.x * side, y: ball_pos.y * side, z: ball_pos.z };
+        state_wrapper.ball.linear_velocity = Velocity { x: ball_vel.x * side, y: ball_vel.y * side, z: ball_vel.z };
+        state_wrapper.ball.angular_velocity = Velocity { x: 0., y: 0., z: 0. };
+
+        let dir = to_target.normalized();
+        let yaw = (dir.y * side).atan2(dir.x * side);
+        let mut shooter_count = 0;
+        let mut defender_count = 0;
+        for car in &mut state_wrapper.cars {
+            let pos = if car.get_team_num() == shooting_team {
+                // the shooter is right behind the ball, its teammates further back
+                let behind = ball_pos - dir * (500. + 700. * shooter_count as f32);
+                shooter_count += 1;
+                car.set_rot(Some(0.), Some(yaw), Some(0.));
+                Vec3::new(behind.x, behind.y, 17.)
+            } else {
+                let x = [0., -600., 600.][defender_count % 3];
+                defender_count += 1;
+                car.set_rot(Some(0.), Some(-side * std::f32::consts::FRAC_PI_2), Some(0.));
+                Vec3::new(x, BACK_WALL_Y - 200., 17.)
+            };
+            car.set_pos(Some(pos.x * side), Some(pos.y * side), Some(pos.z));
+            car.set_lin_vel(Some(0.), Some(0.), Some(0.));
+            car.set_ang_vel(Some(0.), Some(0.), Some(0.));
+            car.boost = 0.5;
+        }
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_shot(state_wrapper: &StateWrapper, shooting_team: i32) {
+        let goal_y = if shooting_team == BLUE_TEAM { BACK_WALL_Y } else { -BACK_WALL_Y };
+        let ball_pos = Vec3::from(state_wrapper.ball.position);
+        let ball_vel = Vec3::from(state_wrapper.ball.linear_velocity);
+        let to_goal = Vec3::new(-ball_pos.x, goal_y - ball_pos.y, 0.).normalized();
+        let vel_xy = Vec3::new(ball_vel.x, ball_vel.y, 0.).normalized();
+        assert!(vel_xy.dot(to_goal) > 0.8, "ball velocity {ball_vel:?} from {ball_pos:?} is not toward the goal at y={goal_y}");
+        assert!(ball_pos.y * goal_y > 0., "ball {ball_pos:?} is not in the attacking half");
+
+        let shooter = state_wrapper.cars.iter().find(|car| car.get_team_num() == shooting_team).unwrap();
+        let shooter_pos = Vec3::from(shooter.position);
+        assert!(Vec3::from(state_wrapper.ball.position - shooter.position).dot(vel_xy) > 0., "shooter {shooter_pos:?} is not behind the ball");
+    }
+
+    #[test]
+    fn shot_toward_goal_for_each_team() {
+        for team in [BLUE_TEAM, ORANGE_TEAM] {
+            let mut state_setter = ShotState::new(Some(team), None, Some(0));
+            for _ in 0..50 {
+                let mut state_wrapper = state_setter.build_wrapper(2, 2, None);
+                state_setter.reset(&mut state_wrapper);
+                check_shot(&state_wrapper, team);
+            }
+        }
+    }
+
+    #[test]
+    fn shot_random_team() {
+        let mut state_setter = ShotState::new(None, Some(0.), Some(1));
+        let mut teams = Vec::new();
+        for _ in 0..20 {
+            let mut state_wrapper = state_setter.build_wrapper(1, 1, None);
+            state_setter.reset(&mut state_wrapper);
+            // without aim noise every shot is aimed at the center of the goal
+            let team = if state_wrapper.ball.linear_velocity.y > 0. { BLUE_TEAM } else { ORANGE_TEAM };
+            check_shot(&state_wrapper, team);
+            teams.push(team);
+        }
+        assert!(teams.contains(&BLUE_TEAM) && teams.contains(&ORANGE_TEAM));
+
+        // only blue has cars
+        let mut state_wrapper = state_setter.build_wrapper(1, 0, None);
+        for _ in 0..10 {
+            state_setter.reset(&mut state_wrapper);
+            assert!(state_wrapper.ball.linear_velocity.y > 0.);
+        }
+    }
+}
@@ -1,39 +1,105 @@
 use rand::{distributions::{weighted::WeightedIndex, Distribution}, thread_rng, Rng, rngs::SmallRng, SeedableRng};
 
+use crate::gamestates::game_state::GameState;
+
 use super::{state_setter::StateSetter, wrappers::state_wrapper::StateWrapper};
 
 /// weighted state setter that uses a rand distribution to poll for a choice
+/// 
+/// The choice is made in `build_wrapper` (so the chosen setter also builds the wrapper) and then used for the following `reset`. 
+/// If `reset` is called without `build_wrapper` first then a new choice is made there.
 pub struct WeightedSampleSetter {
     state_setters: Vec<Box<dyn StateSetter>>,
     distribution: WeightedIndex<f64>,
     rng: SmallRng,
+    choice: Option<usize>,
 }
 
 impl WeightedSampleSetter {
+    /// weights do not need to sum to 1 as they are normalized, but they must be non-negative and sum to more than 0
     pub fn new(state_setters: Vec<Box<dyn StateSetter>>, weights: Vec<f64>, seed: Option<u64>) -> Self {
         assert!(state_setters.len() == weights.len(), "WeightedSampleSetter requires the argument lengths match");
-        let distribution = WeightedIndex::new(&weights).unwrap();
+        let weight_sum: f64 = weights.iter().sum();
+        assert!(weight_sum > 0., "WeightedSampleSetter requires the weights sum to more than 0");
+        let weights: Vec<f64> = weights.iter().map(|weight| weight / weight_sum).collect();
+        let distribution = WeightedIndex::new(&weights).expect("WeightedSampleSetter requires the weights be non-negative");
         let seed = match seed {
             Some(seed) => seed,
             None => thread_rng().gen_range(0..10000),
         };
         let rng = SmallRng::seed_from_u64(seed);
-        WeightedSampleSetter { state_setters, distribution, rng }
+        WeightedSampleSetter { state_setters, distribution, rng, choice: None }
     }
 }
 
 impl StateSetter for WeightedSampleSetter {
-    fn reset(&mut self, state_wrapper: &mut StateWrapper) {
+    fn build_wrapper(&mut self, blue_count: usize, orange_count: usize, game_state: Option<&GameState>) -> StateWrapper {
         let choice = self.distribution.sample(&mut self.rng);
-        // dbg!(choice);
-        // dbg!(state_wrapper.cars.len());
+        self.choice = Some(choice);
+        self.state_setters[choice].build_wrapper(blue_count, orange_count, game_state)
+    }
+
+    fn reset(&mut self, state_wrapper: &mut StateWrapper) {
+        let choice = match self.choice.take() {
+            Some(choice) => choice,
+            None => self.distribution.sample(&mut self.rng),
+        };
         self.state_setters[choice].reset(state_wrapper);
     }
 
     fn set_seed(&mut self, seed: u64) {
         self.rng = SmallRng::seed_from_u64(seed);
+        self.choice = None;
         for state_setter in &mut self.state_setters {
             state_setter.set_seed(seed);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct BallHeightSetter(f32);
+
+    impl StateSetter for BallHeightSetter {
+        fn reset(&mut self, state_wrapper: &mut StateWrapper) {
+            state_wrapper.ball.position.z = self.0;
+        }
+    }
+
+    fn make_setter(weights: Vec<f64>) -> WeightedSampleSetter {
+        WeightedSampleSetter::new(vec![Box::new(BallHeightSetter(100.)), Box::new(BallHeightSetter(200.))], weights, Some(0))
+    }
+
+    fn sample_heights(state_setter: &mut WeightedSampleSetter, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|_| {
+                let mut state_wrapper = state_setter.build_wrapper(1, 1, None);
+                state_setter.reset(&mut state_wrapper);
+                state_wrapper.ball.position.z
+            })
+            .collect()
+    }
+
+    #[test]
+    fn weighted_sample_picks_weighted() {
+        let mut state_setter = make_setter(vec![0., 2.]);
+        assert!(sample_heights(&mut state_setter, 10).iter().all(|z| *z == 200.));
+    }
+
+    #[test]
+    fn weighted_sample_seeded() {
+        let mut state_setter_a = make_setter(vec![1., 1.]);
+        let mut state_setter_b = make_setter(vec![1., 1.]);
+        state_setter_a.set_seed(7);
+        state_setter_b.set_seed(7);
+        assert_eq!(sample_heights(&mut state_setter_a, 20), sample_heights(&mut state_setter_b, 20));
+    }
+
+    #[test]
+    #[should_panic]
+    fn weighted_sample_zero_weights() {
+        make_setter(vec![0., 0.]);
+    }
+}
@@ -6,6 +6,7 @@ use crate::gamestates::{
 };
 
 /// Car wrapper that allows for easy modification of all of the units in a car (PlayerData, PhysicsObject)
+#[derive(Clone, Debug)]
 pub struct CarWrapper {
     pub rotation: EulerAngle,
     team_num: i32,
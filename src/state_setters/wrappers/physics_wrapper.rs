@@ -1,6 +1,7 @@
 use crate::gamestates::physics_object::{PhysicsObject, Position, Velocity};
 
 /// Physics wrapper that allows for easy modification of a PhysicsObject
+#[derive(Clone, Debug)]
 pub struct PhysicsWrapper {
     pub position: Position,
     pub linear_velocity: Velocity,
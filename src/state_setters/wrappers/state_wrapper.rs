@@ -8,6 +8,7 @@ use super::{car_wrapper::CarWrapper, physics_wrapper::PhysicsWrapper};
 // const ORANGE_ID1: i32 = 5;
 
 /// State wrapper that allows for easy modification of the state via itself
+#[derive(Clone, Debug)]
 pub struct StateWrapper {
     pub ball: PhysicsWrapper,
     pub cars: Vec<CarWrapper>,
@@ -41,6 +42,23 @@ impl StateWrapper {
         }
     }
 
+    pub fn ball(&self) -> &PhysicsWrapper {
+        &self.ball
+    }
+
+    pub fn ball_mut(&mut self) -> &mut PhysicsWrapper {
+        &mut self.ball
+    }
+
+    /// iterates over the car wrappers in the same order as the players of the `GameState`
+    pub fn cars(&self) -> impl Iterator<Item = &CarWrapper> {
+        self.cars.iter()
+    }
+
+    pub fn cars_mut(&mut self) -> impl Iterator<Item = &mut CarWrapper> {
+        self.cars.iter_mut()
+    }
+
     fn _read_from_gamestate(game_state: &GameState) -> StateWrapper {
         let mut cars = Vec::<CarWrapper>::new();
         // let players = &mut game_state.players;
@@ -72,3 +90,26 @@ impl StateWrapper {
     //     return full_vec
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_read_car_position() {
+        let mut state_wrapper = StateWrapper::new(Some(1), Some(1), None);
+        for car in state_wrapper.cars_mut().filter(|car| car.get_team_num() == 1) {
+            car.set_pos(Some(100.), Some(-200.), Some(17.));
+        }
+        state_wrapper.ball_mut().set_pos(None, None, Some(500.));
+        let snapshot = state_wrapper.clone();
+
+        let cars: Vec<&CarWrapper> = snapshot.cars().collect();
+        assert_eq!(cars.len(), 2);
+        assert_eq!((cars[0].get_car_id(), cars[0].get_team_num()), (1, 0));
+        assert_eq!(cars[0].position.x, 0.);
+        assert_eq!((cars[1].position.x, cars[1].position.y, cars[1].position.z), (100., -200., 17.));
+        assert_eq!(snapshot.ball().position.z, 500.);
+        assert!(format!("{snapshot:?}").contains("CarWrapper"));
+    }
+}
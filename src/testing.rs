@@ -0,0 +1,251 @@
+//! Helpers for checking that stepping this crate's sim matches recorded traces, eg. golden traces from Python rlgym-sim.
+//!
+//! A trace is a 2d f32 npy file with one row per step: the state before the step (`GameState::to_vec`), the 8 controls of
+//! every player (in the order of the players in the state, same order as the action parsers) and the state after the step
+//! (also `GameState::to_vec`). Every row has the same players, their car ids must be 1, 2, ... with the blue cars first
+//! (like the cars spawned by `RocketsimWrapper`). The number of ticks of a step is the difference of the tick nums of the two states.
+//!
+//! Every row is replayed from its own state, so a divergence shows up on the step that caused it instead of accumulating.
+//! Setting the state only restores the physics and boost of the cars (not eg. their jump or boost timers), so traces should avoid jumping and boosting.
+
+use std::path::Path;
+
+use ndarray::Array2;
+use ndarray_npy::{read_npy, write_npy};
+
+use crate::{
+    common_values::BLUE_TEAM,
+    envs::game_match::GameConfig,
+    gamestates::game_state::GameState,
+    sim_wrapper::wrapper::RocketsimWrapper,
+    state_setters::wrappers::state_wrapper::StateWrapper,
+};
+
+/// length of the controls of a single player in a trace row
+const ACTION_LEN: usize = 8;
+
+/// A single step of a trace, see the module docs for the layout of its row
+#[derive(Clone, Debug)]
+pub struct TraceStep {
+    pub state: GameState,
+    pub actions: Vec<Vec<f32>>,
+    pub next_state: GameState,
+}
+
+impl TraceStep {
+    pub fn from_row(row: &[f32]) -> Self {
+        assert!(row.len() >= 2, "trace row is too short (len: {})", row.len());
+        let state_len = row[1] as usize;
+        assert!(row.len() > state_len, "trace row (len: {}) is shorter than its state vec (len: {state_len})", row.len());
+        let state = GameState::from_vec(&row[..state_len]);
+        let actions_len = ACTION_LEN * state.players.len();
+        assert!(row.len() >= state_len + actions_len + 2, "trace row (len: {}) is too short for the actions of {} players", row.len(), state.players.len());
+        let actions = row[state_len..state_len + actions_len].chunks(ACTION_LEN).map(|action| action.to_vec()).collect();
+        let next_state = GameState::from_vec(&row[state_len + actions_len..]);
+        TraceStep { state, actions, next_state }
+    }
+
+    pub fn to_row(&self) -> Vec<f32> {
+        let mut row = self.state.to_vec();
+        for action in &self.actions {
+            assert!(action.len() == ACTION_LEN, "action (len: {}) was not of length {ACTION_LEN}", action.len());
+            row.extend(action);
+        }
+        row.extend(self.next_state.to_vec());
+        row
+    }
+}
+
+/// panics if the file can't be read or isn't a trace
+pub fn load_trace<P: AsRef<Path>>(path: P) -> Vec<TraceStep> {
+    let path = path.as_ref();
+    let rows: Array2<f32> = read_npy(path).unwrap_or_else(|e| panic!("could not read the trace {}: {e}", path.display()));
+    rows.rows().into_iter().map(|row| TraceStep::from_row(&row.to_vec())).collect()
+}
+
+/// writes the steps as a trace (all of them must have the same players), eg. to record a regression trace from this crate
+pub fn save_trace<P: AsRef<Path>>(path: P, steps: &[TraceStep]) {
+    let rows: Vec<Vec<f32>> = steps.iter().map(TraceStep::to_row).collect();
+    let row_len = rows.first().map_or(0, |row| row.len());
+    assert!(rows.iter().all(|row| row.len() == row_len), "every step of a trace must have the same players");
+    let rows = Array2::from_shape_vec((rows.len(), row_len), rows.concat()).unwrap();
+    let path = path.as_ref();
+    write_npy(path, &rows).unwrap_or_else(|e| panic!("could not write the trace {}: {e}", path.display()));
+}
+
+fn close(expected: f32, actual: f32, tolerance: f32) -> bool {
+    (expected - actual).abs() <= tolerance * (1. + expected.abs().max(actual.abs()))
+}
+
+fn compare_arrays(mismatches: &mut Vec<String>, name: &str, expected: &[f32], actual: &[f32], tolerance: f32) {
+    if expected.iter().zip(actual).any(|(&e, &a)| !close(e, a, tolerance)) {
+        mismatches.push(format!("{name}: expected {expected:?}, got {actual:?}"));
+    }
+}
+
+/// Compares the physics of the ball and the cars, the boost, demo and on ground state of the players, the boost pads and the scores.
+///
+/// Floats match if their difference is at most `tolerance * (1 + the larger magnitude)`, so it is relative for large values and absolute for small ones.
+/// The quaternions q and -q are the same rotation. Returns a description of every field that doesn't match (empty if the states match).
+pub fn compare_states(expected: &GameState, actual: &GameState, tolerance: f32) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    if (expected.blue_score, expected.orange_score) != (actual.blue_score, actual.orange_score) {
+        mismatches.push(format!(
+            "score: expected {}-{}, got {}-{}",
+            expected.blue_score, expected.orange_score, actual.blue_score, actual.orange_score
+        ));
+    }
+
+    let (ball, other_ball) = (&expected.ball, &actual.ball);
+    compare_arrays(&mut mismatches, "ball position", &ball.position.into_array(), &other_ball.position.into_array(), tolerance);
+    compare_arrays(&mut mismatches, "ball linear velocity", &ball.linear_velocity.into_array(), &other_ball.linear_velocity.into_array(), tolerance);
+    compare_arrays(&mut mismatches, "ball angular velocity", &ball.angular_velocity.into_array(), &other_ball.angular_velocity.into_array(), tolerance);
+
+    for (i, (pad, other_pad)) in expected.boost_pads.iter().zip(&actual.boost_pads).enumerate() {
+        if pad.state.is_active != other_pad.state.is_active {
+            mismatches.push(format!("boost pad {i} active: expected {}, got {}", pad.state.is_active, other_pad.state.is_active));
+        }
+    }
+
+    if expected.players.len() != actual.players.len() {
+        mismatches.push(format!("player count: expected {}, got {}", expected.players.len(), actual.players.len()));
+        return mismatches;
+    }
+    for (player, other) in expected.players.iter().zip(&actual.players) {
+        let name = format!("car {}", player.car_id);
+        if (player.car_id, player.team_num) != (other.car_id, other.team_num) {
+            mismatches.push(format!("{name}: expected car {} of team {}, got car {} of team {}", player.car_id, player.team_num, other.car_id, other.team_num));
+            continue;
+        }
+        let (car, other_car) = (&player.car_data, &other.car_data);
+        compare_arrays(&mut mismatches, &format!("{name} position"), &car.position.into_array(), &other_car.position.into_array(), tolerance);
+        compare_arrays(&mut mismatches, &format!("{name} linear velocity"), &car.linear_velocity.into_array(), &other_car.linear_velocity.into_array(), tolerance);
+        compare_arrays(&mut mismatches, &format!("{name} angular velocity"), &car.angular_velocity.into_array(), &other_car.angular_velocity.into_array(), tolerance);
+
+        let quat = car.quaternion.into_array();
+        let other_quat = other_car.quaternion.into_array();
+        let negated: Vec<f32> = other_quat.iter().map(|x| -x).collect();
+        if !quat.iter().zip(&negated).all(|(&e, &a)| close(e, a, tolerance)) {
+            compare_arrays(&mut mismatches, &format!("{name} quaternion"), &quat, &other_quat, tolerance);
+        }
+
+        compare_arrays(&mut mismatches, &format!("{name} boost"), &[player.boost_amount], &[other.boost_amount], tolerance);
+        if (player.is_demoed, player.on_ground) != (other.is_demoed, other.on_ground) {
+            mismatches.push(format!(
+                "{name} (is demoed, on ground): expected {:?}, got {:?}",
+                (player.is_demoed, player.on_ground),
+                (other.is_demoed, other.on_ground)
+            ));
+        }
+    }
+    mismatches
+}
+
+/// Steps this crate's sim through every row of the trace from the row's state with the row's actions for all the ticks of the row
+/// and compares the state after the last tick with the recorded next state (see `compare_states`, tolerance defaults to 1e-3).
+///
+/// Panics on the first step that doesn't match, listing every field that diverged. Returns the number of steps compared.
+/// `rocketsim_rs::init` must have been called before.
+pub fn replay_and_compare<P: AsRef<Path>>(trace_path: P, tolerance: Option<f32>) -> usize {
+    let tolerance = tolerance.unwrap_or(1e-3);
+    let trace_path = trace_path.as_ref();
+    let steps = load_trace(trace_path);
+    let mut sim_wrapper: Option<(GameConfig, RocketsimWrapper)> = None;
+
+    for (i, step) in steps.iter().enumerate() {
+        let blue_count = step.state.players.iter().filter(|player| player.team_num == BLUE_TEAM).count();
+        let orange_count = step.state.players.len() - blue_count;
+        let tick_skip = step.next_state.tick_num.checked_sub(step.state.tick_num).filter(|&ticks| ticks > 0);
+        let tick_skip = tick_skip.unwrap_or_else(|| panic!("step {i} of {} does not advance the tick num", trace_path.display())) as usize;
+        let config = GameConfig {
+            team_size: blue_count.max(orange_count),
+            tick_skip,
+            spawn_opponents: orange_count > 0,
+            blue_size: Some(blue_count),
+            orange_size: Some(orange_count),
+            ..GameConfig::default()
+        };
+
+        // the arena only has to be rebuilt when the cars or the tick skip change
+        let (_, wrapper) = match sim_wrapper.take() {
+            Some((prev_config, wrapper))
+                if (prev_config.blue_count(), prev_config.orange_count(), prev_config.tick_skip) == (blue_count, orange_count, tick_skip) =>
+            {
+                sim_wrapper.insert((prev_config, wrapper))
+            }
+            _ => sim_wrapper.insert((config.clone(), RocketsimWrapper::new(config))),
+        };
+
+        wrapper.set_state(StateWrapper::new(None, None, Some(&step.state)), false);
+        let (next_state, _) = wrapper.step(step.actions.clone(), false);
+        let mismatches = compare_states(&step.next_state, &next_state, tolerance);
+        assert!(mismatches.is_empty(), "step {i} of {} diverged:\n{}", trace_path.display(), mismatches.join("\n"));
+    }
+    steps.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common_values::ORANGE_TEAM, gamestates::physics_object::Quaternion};
+
+    fn test_step() -> TraceStep {
+        let mut state = GameState::new_test();
+        state.players[1].team_num = ORANGE_TEAM;
+        let mut next_state = state.clone();
+        next_state.tick_num += 8;
+        next_state.ball.position.x += 10.;
+        TraceStep { state, actions: vec![vec![1., 0., 0., 0., 0., 0., 1., 0.], vec![0., -1., 0., 0., 0., 1., 0., 0.]], next_state }
+    }
+
+    #[test]
+    fn trace_row_round_trip() {
+        let step = test_step();
+        let row = step.to_row();
+        let parsed = TraceStep::from_row(&row);
+        assert_eq!(parsed.actions, step.actions);
+        assert!(compare_states(&step.state, &parsed.state, 0.).is_empty());
+        assert!(compare_states(&step.next_state, &parsed.next_state, 0.).is_empty());
+        assert_eq!(parsed.next_state.tick_num, step.next_state.tick_num);
+        assert_eq!(parsed.to_row(), row);
+
+        let path = "./tests/test_files_npy/test_trace.npy";
+        save_trace(path, &[step.clone(), step]);
+        let loaded = load_trace(path);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].to_row(), row);
+    }
+
+    #[test]
+    fn compare_states_reports_divergences() {
+        let step = test_step();
+        let state = &step.state;
+        assert!(compare_states(state, state, 1e-3).is_empty());
+
+        // within tolerance
+        let mut close_state = state.clone();
+        close_state.ball.position.x += 0.01;
+        close_state.players[0].car_data.quaternion = Quaternion {
+            w: -state.players[0].car_data.quaternion.w,
+            x: -state.players[0].car_data.quaternion.x,
+            y: -state.players[0].car_data.quaternion.y,
+            z: -state.players[0].car_data.quaternion.z,
+        };
+        assert!(compare_states(state, &close_state, 1e-3).is_empty());
+
+        let mismatches = compare_states(state, &step.next_state, 1e-3);
+        assert_eq!(mismatches.len(), 1, "{mismatches:?}");
+        assert!(mismatches[0].starts_with("ball position"));
+
+        let mut diverged = state.clone();
+        diverged.players[1].car_data.linear_velocity.z += 50.;
+        diverged.players[1].on_ground = !diverged.players[1].on_ground;
+        diverged.boost_pads[3].state.is_active = !diverged.boost_pads[3].state.is_active;
+        diverged.orange_score += 1;
+        let mismatches = compare_states(state, &diverged, 1e-3);
+        assert_eq!(mismatches.len(), 4, "{mismatches:?}");
+
+        diverged.players.pop();
+        assert!(compare_states(state, &diverged, 1e-3).iter().any(|mismatch| mismatch.starts_with("player count")));
+    }
+}
@@ -0,0 +1,112 @@
+use rand::{thread_rng, Rng};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::envs::game_match::sub_seed;
+use crate::gym::Gym;
+use crate::make::{make, MakeConfig};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local!(
+    // each thread of a VecGym's pool owns exactly one gym, the sim wrapper keeps its scores and stats in thread locals
+    // so the gyms can't move between threads
+    static ENV: RefCell<Option<Gym>> = const { RefCell::new(None) };
+);
+
+fn with_env<R>(func: impl FnOnce(&mut Gym) -> R) -> R {
+    ENV.with(|env| {
+        let mut env = env.borrow_mut();
+        let gym = env.as_mut().expect("VecGym thread did not have a gym");
+        func(gym)
+    })
+}
+
+/// Info for one env of a `VecGym` step, `terminal_obs` holds the last observations of the episode if the env was reset during the step
+#[derive(Clone, Debug, Default)]
+pub struct EnvInfo {
+    pub info: HashMap<String, f32>,
+    pub terminal_obs: Option<Vec<Vec<f32>>>,
+}
+
+/// (obs, rewards, dones, infos) with one entry per env, as returned by `VecGym::step`
+pub type VecStepResult = (Vec<Vec<Vec<f32>>>, Vec<Vec<f32>>, Vec<bool>, Vec<EnvInfo>);
+
+/// Runs multiple independent gyms in parallel.
+/// 
+/// Each gym lives on its own thread of a rayon thread pool for its whole life, the batched calls are broadcast to all of the threads.
+/// Envs that are done (or truncated) are reset automatically during `step`, the observations returned for that env are then the ones from the reset
+/// and the last observations of the finished episode are in the env's `EnvInfo`.
+/// 
+/// `rocketsim_rs::init` must have been called before making a `VecGym`.
+pub struct VecGym {
+    pool: ThreadPool,
+    n_envs: usize,
+}
+
+impl VecGym {
+    /// make_config is called with the index of each env on the thread that will own the env.
+    /// 
+    /// Every env is seeded (see `Gym::seed`) with a sub-seed of seed for its env index, seed defaults to a random seed.
+    pub fn new<F>(n_envs: usize, make_config: F, use_truncation: Option<bool>, seed: Option<u64>) -> Self
+    where
+        F: Fn(usize) -> MakeConfig + Sync,
+    {
+        assert!(n_envs > 0, "VecGym requires at least one env");
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(n_envs)
+            .thread_name(|i| format!("rlgym-sim-env-{i}"))
+            .build()
+            .expect("unable to build the VecGym thread pool");
+        let seed = seed.unwrap_or_else(|| thread_rng().gen());
+
+        pool.broadcast(|ctx| {
+            let env_index = ctx.index();
+            let mut gym = make(make_config(env_index), None, use_truncation);
+            gym.seed(sub_seed(seed, env_index as u64));
+            ENV.with(|env| *env.borrow_mut() = Some(gym));
+        });
+
+        VecGym { pool, n_envs }
+    }
+
+    pub fn num_envs(&self) -> usize {
+        self.n_envs
+    }
+
+    /// resets every env and returns the observations of each env
+    pub fn reset(&mut self) -> Vec<Vec<Vec<f32>>> {
+        self.pool.broadcast(|_| with_env(|gym| gym.reset(None, None, None).0))
+    }
+
+    /// steps every env with its actions (one set of actions per env, in the same order as the envs)
+    pub fn step(&mut self, actions: Vec<Vec<Vec<f32>>>) -> VecStepResult {
+        let actions_len = actions.len();
+        assert!(actions_len == self.n_envs, "actions were given for {actions_len} envs but there are {} envs", self.n_envs);
+
+        let results = self.pool.broadcast(|ctx| {
+            with_env(|gym| {
+                let (obs, rewards, done, info, _) = gym.step(actions[ctx.index()].clone());
+                let truncated = info.get("truncated").is_some_and(|val| *val > 0.);
+                if done || truncated {
+                    let (reset_obs, _, _) = gym.reset(None, None, None);
+                    (reset_obs, rewards, done, EnvInfo { info, terminal_obs: Some(obs) })
+                } else {
+                    (obs, rewards, done, EnvInfo { info, terminal_obs: None })
+                }
+            })
+        });
+
+        let mut obs_vec = Vec::with_capacity(self.n_envs);
+        let mut rewards_vec = Vec::with_capacity(self.n_envs);
+        let mut dones = Vec::with_capacity(self.n_envs);
+        let mut infos = Vec::with_capacity(self.n_envs);
+        for (obs, rewards, done, info) in results {
+            obs_vec.push(obs);
+            rewards_vec.push(rewards);
+            dones.push(done);
+            infos.push(info);
+        }
+        (obs_vec, rewards_vec, dones, infos)
+    }
+}
@@ -0,0 +1,132 @@
+"""Records a golden trace with Python rlgym-sim for `golden_trace_test` (see README.md in this directory).
+
+Every row is `TraceStep::to_row` of rlgym_sim_rs::testing: the state before the step (`GameState::to_vec`, version 4),
+the 8 parsed controls of every player and the state after the step.
+
+usage: python record_trace.py [out path] [steps] [seed]
+"""
+import sys
+
+import numpy as np
+import rlgym_sim
+from rlgym_sim.utils.action_parsers import ContinuousAction
+from rlgym_sim.utils.common_values import BOOST_LOCATIONS
+from rlgym_sim.utils.state_setters import DefaultState
+from rlgym_sim.utils.terminal_conditions.common_conditions import TimeoutCondition
+
+STATE_VEC_VERSION = 4
+TICK_SKIP = 8
+TICK_RATE = 120
+# layout lengths of GameState::to_vec
+HEADER_LEN = 3
+INFO_LEN = 11
+PHYSICS_LEN = 27
+PAD_LEN = 6
+PLAYER_LEN = 29 + 2 * PHYSICS_LEN
+
+
+def encode_physics(phys, vec):
+    quat = np.asarray(phys.quaternion, dtype=np.float32)
+    # the ball has no rotation
+    if not np.any(quat):
+        quat = np.array([1, 0, 0, 0], dtype=np.float32)
+        euler = np.zeros(3)
+        rot_mtx = np.eye(3)
+    else:
+        euler = phys.euler_angles()
+        rot_mtx = phys.rotation_mtx()
+    vec.extend(phys.position)
+    vec.extend(quat)
+    vec.extend(phys.linear_velocity)
+    vec.extend(phys.angular_velocity)
+    # pitch, yaw, roll
+    vec.extend(euler)
+    # columns are forward, right, up like rlgym_sim_rs, flattened row major
+    vec.extend(np.asarray(rot_mtx).flatten())
+    vec.extend([1, 1])
+
+
+def state_to_vec(state, tick_num, tick_count):
+    """GameState::to_vec of an rlgym-sim state, the fields rlgym-sim doesn't have are 0 (or -1 for the last touch car id)"""
+    num_players = len(state.players)
+    total_len = HEADER_LEN + INFO_LEN + 2 * PHYSICS_LEN + PAD_LEN * len(BOOST_LOCATIONS) + PLAYER_LEN * num_players
+    vec = [STATE_VEC_VERSION, total_len, num_players]
+    vec.extend([getattr(state, "game_type", 0), state.blue_score, state.orange_score, state.last_touch, tick_num])
+    vec.extend([-1, 0, 0, 0])
+    vec.extend([tick_count, tick_count / TICK_RATE])
+    encode_physics(state.ball, vec)
+    encode_physics(state.inverted_ball, vec)
+    for (x, y, z), active in zip(BOOST_LOCATIONS, state.boost_pads):
+        # big pads are a bit higher than the small ones
+        vec.extend([z > 71, x, y, z, active > 0, 0])
+    for player in state.players:
+        vec.extend([
+            player.car_id, player.team_num, player.match_goals, player.match_saves, player.match_shots,
+            player.match_demolishes, player.boost_pickups, player.is_demoed,
+            # last bumped by, last bumpee, bumps, been bumped
+            0, 0, 0, 0,
+            player.on_ground, player.ball_touched, player.has_jump, player.has_flip, player.boost_amount,
+            # last ball touch tick
+            0,
+        ])
+        # last actions, hitbox size
+        vec.extend([0] * 11)
+        encode_physics(player.car_data, vec)
+        encode_physics(player.inverted_car_data, vec)
+    assert len(vec) == total_len, f"state vec was {len(vec)} long, expected {total_len}"
+    return vec
+
+
+def check_players(state):
+    teams = [player.team_num for player in state.players]
+    assert [player.car_id for player in state.players] == list(range(1, len(teams) + 1)), "car ids must be 1, 2, ..."
+    assert teams == sorted(teams), "the blue cars must come first"
+
+
+def main():
+    out_path = sys.argv[1] if len(sys.argv) > 1 else "soccar_1v1.npy"
+    num_steps = int(sys.argv[2]) if len(sys.argv) > 2 else 150
+    rng = np.random.default_rng(int(sys.argv[3]) if len(sys.argv) > 3 else 0)
+
+    env = rlgym_sim.make(
+        tick_skip=TICK_SKIP,
+        team_size=1,
+        spawn_opponents=True,
+        terminal_conditions=[TimeoutCondition(num_steps + 1)],
+        action_parser=ContinuousAction(),
+        state_setter=DefaultState(),
+    )
+    _, info = env.reset(return_info=True)
+    state = info["state"]
+    check_players(state)
+
+    rows = []
+    tick_count = 0
+    steer = rng.uniform(-1, 1, size=len(state.players))
+    for _ in range(num_steps):
+        # setting the state in rlgym_sim_rs only restores the physics and boost of the cars (not eg. jump or boost timers),
+        # so the cars only drive and steer
+        steer = np.clip(steer + rng.uniform(-0.3, 0.3, size=steer.shape), -1, 1)
+        actions = np.zeros((len(state.players), 8), dtype=np.float32)
+        actions[:, 0] = 1
+        actions[:, 1] = steer
+
+        _, _, done, info = env.step(actions)
+        next_state = info["state"]
+        check_players(next_state)
+        row = state_to_vec(state, tick_count, tick_count)
+        row.extend(actions.flatten())
+        row.extend(state_to_vec(next_state, tick_count + TICK_SKIP, tick_count + TICK_SKIP))
+        rows.append(row)
+
+        state = next_state
+        tick_count += TICK_SKIP
+        if done:
+            break
+
+    np.save(out_path, np.array(rows, dtype=np.float32))
+    print(f"wrote {len(rows)} steps to {out_path}")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,36 @@
+#![cfg(feature = "python")]
+
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+
+#[test]
+fn python_gym_smoke_test() {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "rlgym_sim_rs").unwrap();
+        rlgym_sim_rs::python::register(&module).unwrap();
+        let globals = PyDict::new(py);
+        globals.set_item("rlgym_sim_rs", module).unwrap();
+
+        py.run(
+            c_str!(
+                r#"
+gym = rlgym_sim_rs.Gym(rlgym_sim_rs.MakeConfig(team_size=1, timeout_steps=10))
+obs = gym.reset(seed=0)
+assert len(obs) == 2
+obs, rewards, done, info = gym.step([[1., 0., 0., 0., 0., 0., 0., 0.]] * 2)
+assert len(obs) == 2 and len(rewards) == 2
+assert isinstance(done, bool)
+assert info["reward_0"] == rewards[0]
+try:
+    gym.step([[0.] * 8])
+    raise AssertionError("mismatched actions did not raise")
+except ValueError:
+    pass
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+    });
+}
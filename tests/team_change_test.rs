@@ -17,7 +17,7 @@ use rlgym_sim_rs::{
     obs_builders::obs_builder::ObsBuilder,
     state_setters::default_state::DefaultStateTester,
 };
-use rocketsim_rs::sim::CarConfig;
+use rocketsim_rs::sim::{CarConfig, GameMode};
 
 pub struct CombinedTerminalConditions {
     timeout_condition: TimeoutCondition,
@@ -78,6 +78,12 @@ fn team_change_test() {
         gravity: 1.,
         boost_consumption: 1.,
         car_config: CarConfig::octane(),
+        game_mode: GameMode::Soccar,
+        blue_size: None,
+        orange_size: None,
+        car_configs: vec![],
+        shuffle_cars: false,
+        pad_opponent_obs: false,
     };
     // let obs_build: Box<dyn ObsBuilder> = Box::new(AdvancedObs::new());
     let mut obs_build_vec: Vec<Box<dyn ObsBuilder>> = Vec::new();
@@ -93,6 +99,7 @@ fn team_change_test() {
         use_single_obs: true,
         action_parser: act_parse,
         state_setter: state_set, 
+        state_modifiers: vec![],
     };
     let mut gym = make::make(game_config, None, None);
 
@@ -105,7 +112,7 @@ fn team_change_test() {
     assert!(length == 1, "obs was not of correct length for 1v0, was: {length}");
     assert!(gym._prev_state.players.iter().all(|player| (player.car_id <= 6 && player.car_id >= 0)), "car ids in state did not work correctly in 1v0");
 
-    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 2, tick_skip: 1, spawn_opponents: false, car_config: CarConfig::octane(), };
+    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 2, tick_skip: 1, spawn_opponents: false, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false, };
     let mut obs_build_vec: Vec<Box<dyn ObsBuilder>> = Vec::new();
     for _ in 0..2 {
         obs_build_vec.push(Box::new(AdvancedObs::new()));
@@ -119,7 +126,7 @@ fn team_change_test() {
     assert!(length == 2, "obs was not of correct length for 2v0, was: {length}");
     assert!(gym._prev_state.players.iter().all(|player| (player.car_id <= 6 && player.car_id >= 0)), "car ids in state did not work correctly in 2v0");
 
-    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 3, tick_skip: 1, spawn_opponents: false, car_config: CarConfig::octane(), };
+    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 3, tick_skip: 1, spawn_opponents: false, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false, };
     let mut obs_build_vec: Vec<Box<dyn ObsBuilder>> = Vec::new();
     for _ in 0..3 {
         obs_build_vec.push(Box::new(AdvancedObs::new()));
@@ -134,7 +141,7 @@ fn team_change_test() {
 
     // -- start of self-play=true --
 
-    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 1, tick_skip: 1, spawn_opponents: true, car_config: CarConfig::octane(), };
+    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 1, tick_skip: 1, spawn_opponents: true, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false, };
     let mut obs_build_vec: Vec<Box<dyn ObsBuilder>> = Vec::new();
     for _ in 0..2 {
         obs_build_vec.push(Box::new(AdvancedObs::new()));
@@ -148,7 +155,7 @@ fn team_change_test() {
     assert!(length == 2, "obs was not of correct length for 1v1, was: {length}");
     assert!(gym._prev_state.players.iter().all(|player| (player.car_id <= 6 && player.car_id >= 0)), "car ids in state did not work correctly in 1v1");
 
-    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 2, tick_skip: 1, spawn_opponents: true, car_config: CarConfig::octane(), };
+    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 2, tick_skip: 1, spawn_opponents: true, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false, };
     let mut obs_build_vec: Vec<Box<dyn ObsBuilder>> = Vec::new();
     for _ in 0..4 {
         obs_build_vec.push(Box::new(AdvancedObs::new()));
@@ -162,7 +169,7 @@ fn team_change_test() {
     assert!(length == 4, "obs was not of correct length for 2v2, was: {length}");
     assert!(gym._prev_state.players.iter().all(|player| (player.car_id <= 6 && player.car_id >= 0)), "car ids in state did not work correctly in 2v2");
 
-    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 3, tick_skip: 1, spawn_opponents: true, car_config: CarConfig::octane(), };
+    let new_config = GameConfig { gravity: 1., boost_consumption: 1., team_size: 3, tick_skip: 1, spawn_opponents: true, car_config: CarConfig::octane(), game_mode: GameMode::Soccar, blue_size: None, orange_size: None, car_configs: vec![], shuffle_cars: false, pad_opponent_obs: false, };
     let mut obs_build_vec: Vec<Box<dyn ObsBuilder>> = Vec::new();
     for _ in 0..6 {
         obs_build_vec.push(Box::new(AdvancedObs::new()));
@@ -8,7 +8,7 @@ use std::{
 
 // use std::collections::HashMap;
 use rlgym_sim_rs::{
-    action_parsers::test_parser::TestAction, 
+    action_parsers::{action_parser::ActionParser, test_parser::TestAction}, 
     // gamestates::physics_object::Position
 };
 use rlgym_sim_rs::conditionals::common_conditions::{TimeoutCondition, GoalScoredCondition};
@@ -17,10 +17,16 @@ use rlgym_sim_rs::envs::game_match::GameConfig;
 // use communication::communication_handler::f32vec_as_u8_slice;
 // use rlgym_sim_rs::envs::game_match::GameMatch;
 use rlgym_sim_rs::gamestates::game_state::GameState;
+use rlgym_sim_rs::gamestates::player_data::PlayerData;
+use rlgym_sim_rs::reward_functions::reward_fn::RewardFn;
 // use rlgym_sim_rs::gamestates::physics_object::Position;
+use rlgym_sim_rs::state_setters::{state_setter::StateSetter, wrappers::state_wrapper::StateWrapper};
+use rlgym_sim_rs::common_values::{BLUE_TEAM, BOOST_LOCATIONS, GRAVITY_Z, ORANGE_TEAM};
 use rlgym_sim_rs::make;
+use rlgym_sim_rs::{Gym, GymError, VecGym};
 use rlgym_sim_rs::obs_builders::advanced_obs::AdvancedObs;
-use rlgym_sim_rs::reward_functions::common_rewards::misc_rewards::EventReward;
+use rlgym_sim_rs::reward_functions::common_rewards::misc_rewards::{DemoReward, EventReward};
+use rlgym_sim_rs::state_setters::random_state::RandomState;
 use rlgym_sim_rs::state_setters::default_state::{
     AgentBallHitStateTester, 
     BlueGoalStateTester, 
@@ -32,7 +38,11 @@ use rlgym_sim_rs::state_setters::default_state::{
 };
 
 use rlgym_sim_rs::obs_builders::obs_builder::ObsBuilder;
-use rocketsim_rs::sim::CarConfig;
+use rlgym_sim_rs::obs_builders::default_obs::DefaultObs;
+use rlgym_sim_rs::state_generator::state_modifier::StateModifier;
+use rlgym_sim_rs::gamestates::physics_object::Position;
+use rocketsim_rs::sim::{CarConfig, GameMode};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
 
 pub struct CombinedTerminalConditions {
     timeout_condition: TimeoutCondition,
@@ -101,7 +111,7 @@ fn general_test() {
     let obs_build: Box<dyn ObsBuilder> = Box::new(AdvancedObs::new());
     let obs_build_vec = vec![obs_build];
     let act_parse = Box::new(TestAction::new());
-    // let act_parse = Box::new(DiscreteAction::new());
+    // let act_parse = Box::new(DiscreteAction::new(None));
     // let act_parse_2 = Box::new(OldNectoAction::new());
     // let size = act_parse.get_action_space();
     // let size_old = act_parse_2.get_action_space();
@@ -118,6 +128,12 @@ fn general_test() {
         gravity: 1.,
         boost_consumption: 1.,
         car_config: CarConfig::octane(),
+        game_mode: GameMode::Soccar,
+        blue_size: None,
+        orange_size: None,
+        car_configs: vec![],
+        shuffle_cars: false,
+        pad_opponent_obs: false,
     };
     let game_config = make::MakeConfig {
         game_config: config,
@@ -132,6 +148,7 @@ fn general_test() {
         use_single_obs: true,
         action_parser: act_parse,
         state_setter: state_set, 
+        state_modifiers: vec![],
     };
     let mut gym = make::make(game_config, None, None);
 
@@ -520,6 +537,12 @@ fn general_test() {
         gravity: 1.,
         boost_consumption: 1.,
         car_config: CarConfig::octane(),
+        game_mode: GameMode::Soccar,
+        blue_size: None,
+        orange_size: None,
+        car_configs: vec![],
+        shuffle_cars: false,
+        pad_opponent_obs: false,
     };
     let game_config = make::MakeConfig {
         game_config: config,
@@ -529,6 +552,7 @@ fn general_test() {
         use_single_obs: true,
         action_parser: act_parse,
         state_setter: state_set, 
+        state_modifiers: vec![],
     };
     let mut gym = make::make(game_config, None, None);
 
@@ -637,6 +661,12 @@ fn general_test() {
         gravity: 1.,
         boost_consumption: 1.,
         car_config: CarConfig::octane(),
+        game_mode: GameMode::Soccar,
+        blue_size: None,
+        orange_size: None,
+        car_configs: vec![],
+        shuffle_cars: false,
+        pad_opponent_obs: false,
     };
     let game_config = make::MakeConfig {
         game_config: config,
@@ -646,6 +676,7 @@ fn general_test() {
         use_single_obs: true,
         action_parser: act_parse,
         state_setter: state_set, 
+        state_modifiers: vec![],
     };
     let mut gym = make::make(game_config, None, Some(true));
 
@@ -680,3 +711,1108 @@ fn general_test() {
     println!("fps: {fps}");
     println!("rewards: {rew_val}");
 }
+
+struct BallPosModifier;
+
+impl StateModifier for BallPosModifier {
+    fn modify_state(&mut self, state: &mut GameState) {
+        state.ball.position = Position { x: 1000., y: 2000., z: 500. };
+    }
+}
+
+#[test]
+fn state_modifier_test() {
+    rocketsim_rs::init(None);
+    let config = GameConfig {
+        spawn_opponents: false,
+        ..GameConfig::default()
+    };
+    let game_config = make::MakeConfig {
+        game_config: config,
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(1), Some(false), Some([1.; 3]), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![Box::new(BallPosModifier)],
+    };
+    let mut gym = make::make(game_config, None, None);
+
+    let (obs, _, state) = gym.reset(None, None, None);
+    assert_eq!(state.ball.position.into_array(), [1000., 2000., 500.]);
+    assert_eq!(obs[0][..3], [1000., 2000., 500.]);
+
+    let (obs, _, _, _, state) = gym.step(vec![vec![0.; 8]]);
+    assert_eq!(state.ball.position.into_array(), [1000., 2000., 500.]);
+    assert_eq!(obs[0][..3], [1000., 2000., 500.]);
+}
+
+#[test]
+fn step_gymnasium_test() {
+    rocketsim_rs::init(None);
+    let config = GameConfig {
+        spawn_opponents: false,
+        ..GameConfig::default()
+    };
+    let game_config = make::MakeConfig {
+        game_config: config,
+        terminal_condition: Box::new(TimeoutCondition::new(3)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(AdvancedObs::new())],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+
+    gym.reset(None, None, None);
+    for i in 0..3 {
+        let (_, _, terminated, truncated, _, _) = gym.step_gymnasium(vec![vec![0.; 8]]);
+        assert!(!terminated);
+        assert_eq!(truncated, i == 2);
+    }
+}
+
+#[test]
+fn game_mode_test() {
+    rocketsim_rs::init(None);
+    let make_gym = |game_mode: GameMode| {
+        let config = GameConfig {
+            game_mode,
+            ..GameConfig::default()
+        };
+        let game_config = make::MakeConfig {
+            game_config: config,
+            terminal_condition: Box::new(TimeoutCondition::new(100)),
+            reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+            obs_builder: vec![Box::new(AdvancedObs::new())],
+            use_single_obs: true,
+            action_parser: Box::new(TestAction::new()),
+            state_setter: Box::new(DefaultStateTester::new()),
+            state_modifiers: vec![],
+        };
+        make::make(game_config, None, None)
+    };
+    // ball thrown at the side wall from x=2000, returns its x after about half a second
+    let wall_bounce_x = |gym: &mut Gym| {
+        let (_, _, state) = gym.reset(None, None, None);
+        let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+        state_wrapper.ball.set_pos(Some(2000.), Some(0.), Some(500.));
+        state_wrapper.ball.set_lin_vel(Some(3000.), Some(0.), Some(0.));
+        gym.set_state(state_wrapper);
+        for _ in 0..8 {
+            gym.step(vec![vec![0.; 8]; 2]);
+        }
+        gym.last_state().ball.position.x
+    };
+    // the blue car drives into the ball along +x, returns the ball's y velocity a second later
+    let touch_ball_y_vel = |gym: &mut Gym| {
+        let (_, _, state) = gym.reset(None, None, None);
+        let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+        state_wrapper.ball.set_pos(Some(0.), Some(0.), Some(93.15));
+        state_wrapper.ball.set_lin_vel(Some(0.), Some(0.), Some(0.));
+        let car = state_wrapper.cars.iter_mut().find(|car| car.get_team_num() == BLUE_TEAM).unwrap();
+        car.set_pos(Some(-400.), Some(0.), Some(17.));
+        car.set_rot(Some(0.), Some(0.), Some(0.));
+        car.set_lin_vel(Some(1500.), Some(0.), Some(0.));
+        gym.set_state(state_wrapper);
+        for _ in 0..15 {
+            gym.step(vec![vec![0.; 8]; 2]);
+        }
+        gym.last_state().ball.linear_velocity.y
+    };
+
+    let mut soccar_gym = make_gym(GameMode::Soccar);
+    let mut hoops_gym = make_gym(GameMode::Hoops);
+    // the hoops arena is narrower (side walls at x = +-2966 instead of +-4096)
+    let soccar_x = wall_bounce_x(&mut soccar_gym);
+    let hoops_x = wall_bounce_x(&mut hoops_gym);
+    assert!(soccar_x > 3300., "soccar ball stopped at x {soccar_x}");
+    assert!(hoops_x < 2966., "hoops ball passed the hoops side wall, x {hoops_x}");
+
+    // the soccar ball keeps going along x after the touch
+    let soccar_y_vel = touch_ball_y_vel(&mut soccar_gym);
+    assert!(soccar_y_vel.abs() < 200., "soccar ball y velocity was {soccar_y_vel}");
+
+    // switching the mode on an existing gym rebuilds the arena
+    let mut config = soccar_gym._game_match.get_config();
+    config.game_mode = GameMode::Hoops;
+    soccar_gym.update_config(config.clone(), None);
+    let switched_x = wall_bounce_x(&mut soccar_gym);
+    assert!(switched_x < 2966., "ball passed the hoops side wall after switching to hoops, x {switched_x}");
+
+    // after a blue touch the heatseeker ball turns towards the orange goal (+y)
+    config.game_mode = GameMode::Heatseeker;
+    soccar_gym.update_config(config, None);
+    let heatseeker_y_vel = touch_ball_y_vel(&mut soccar_gym);
+    assert!(heatseeker_y_vel > 500., "heatseeker ball y velocity was {heatseeker_y_vel}");
+    assert_eq!(soccar_gym.last_state().players.len(), 2);
+}
+
+struct BigPadStateSetter;
+
+impl StateSetter for BigPadStateSetter {
+    fn reset(&mut self, state_wrapper: &mut StateWrapper) {
+        let [x, y, _] = BOOST_LOCATIONS[3];
+        let car = &mut state_wrapper.cars[0];
+        car.set_pos(Some(x), Some(y), Some(17.));
+        car.set_lin_vel(Some(0.), Some(0.), Some(0.));
+        car.set_ang_vel(Some(0.), Some(0.), Some(0.));
+        car.set_rot(Some(0.), Some(0.), Some(0.));
+        car.boost = 0.;
+    }
+}
+
+#[test]
+fn boost_pad_timers_test() {
+    rocketsim_rs::init(None);
+    let config = GameConfig {
+        spawn_opponents: false,
+        ..GameConfig::default()
+    };
+    let game_config = make::MakeConfig {
+        game_config: config,
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(AdvancedObs::new())],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(BigPadStateSetter),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+
+    let (_, _, state) = gym.reset(None, None, None);
+    assert!(state.boost_pads_timers.iter().all(|timer| *timer == 0.));
+
+    let (_, _, _, _, state) = gym.step(vec![vec![0.; 8]]);
+    assert!(state.players[0].boost_amount > 0.9);
+    assert!(!state.boost_pads[3].state.is_active);
+    assert!(state.boost_pads[3].is_big);
+    assert!(state.boost_pads_timers[3] > 0.);
+    assert_eq!(state.inverted_boost_pads_timers[30], state.boost_pads_timers[3]);
+    assert_eq!(state.boost_pads_timers.iter().filter(|timer| **timer > 0.).count(), 1);
+}
+
+#[test]
+fn vec_gym_test() {
+    rocketsim_rs::init(None);
+    let n_envs = 4;
+    let make_config = |_env_index: usize| make::MakeConfig {
+        game_config: GameConfig {
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(5)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(AdvancedObs::new_with_coefs(Some(1), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut vec_gym = VecGym::new(n_envs, make_config, None, Some(0));
+
+    let obs = vec_gym.reset();
+    assert_eq!(obs.len(), n_envs);
+    for env_obs in &obs {
+        assert_eq!(env_obs.len(), 2);
+        assert!(env_obs.iter().all(|agent_obs| agent_obs.len() == 107));
+    }
+
+    for i in 0..5 {
+        let (obs, rewards, dones, infos) = vec_gym.step(vec![vec![vec![0.; 8]; 2]; n_envs]);
+        assert_eq!(obs.len(), n_envs);
+        assert_eq!(rewards.len(), n_envs);
+        assert!(obs.iter().all(|env_obs| env_obs.len() == 2 && env_obs.iter().all(|agent_obs| agent_obs.len() == 107)));
+        // timeout is reported as done since the gyms don't use truncation
+        assert!(dones.iter().all(|done| *done == (i == 4)));
+        assert!(infos.iter().all(|info| info.terminal_obs.is_some() == (i == 4)));
+    }
+}
+
+#[test]
+fn seeded_rollout_test() {
+    rocketsim_rs::init(None);
+    // one gym at a time since the sim wrapper stats are per thread
+    let rollout = |seed: u64| {
+        let game_config = make::MakeConfig {
+            game_config: GameConfig {
+                ..GameConfig::default()
+            },
+            terminal_condition: Box::new(TimeoutCondition::new(1000)),
+            reward_fn: Box::new(EventReward::new(None, None, None, Some(1.), None, None, None, None)),
+            obs_builder: vec![Box::new(AdvancedObs::new())],
+            use_single_obs: true,
+            action_parser: Box::new(TestAction::new()),
+            state_setter: Box::new(RandomState::new(Some(true), Some(true), Some(false), Some(true), None)),
+            state_modifiers: vec![],
+        };
+        let mut gym = make::make(game_config, None, None);
+        gym.seed(seed);
+        let (obs, _, _) = gym.reset(None, None, None);
+        let mut trajectory = vec![obs];
+
+        let mut rng = SmallRng::seed_from_u64(seed);
+        for _ in 0..20 {
+            let actions = (0..2).map(|_| (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect()).collect();
+            let (obs, rewards, _, _, _) = gym.step(actions);
+            trajectory.push(obs);
+            trajectory.push(vec![rewards]);
+        }
+        trajectory
+    };
+
+    let first = rollout(7);
+    assert_eq!(first, rollout(7), "two rollouts with the same seed and actions were different");
+    assert_ne!(first, rollout(8), "rollouts with different seeds were the same");
+}
+
+struct BallUpStateSetter;
+
+impl StateSetter for BallUpStateSetter {
+    fn reset(&mut self, state_wrapper: &mut StateWrapper) {
+        state_wrapper.ball.set_pos(Some(0.), Some(0.), Some(200.));
+        state_wrapper.ball.set_lin_vel(Some(0.), Some(0.), Some(1000.));
+        state_wrapper.ball.set_ang_vel(Some(0.), Some(0.), Some(0.));
+        // keep the car out of the way of the ball
+        state_wrapper.cars[0].set_pos(Some(3000.), Some(-4000.), Some(17.));
+    }
+}
+
+#[test]
+fn ball_prediction_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            spawn_opponents: false,
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(AdvancedObs::new())],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(BallUpStateSetter),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    let (_, _, state) = gym.reset(None, None, None);
+
+    // 1000 uu/s up with 650 uu/s^2 of gravity peaks after ~1.5s
+    let prediction = gym._game_match.predict_ball(state.tick_num, 30, 0.1);
+    assert_eq!(prediction.len(), 30);
+    let heights: Vec<f32> = prediction.iter().map(|ball| ball.pos.z).collect();
+    let peak = heights.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+    assert!((13..=17).contains(&peak), "ball peaked at step {peak}, heights: {heights:?}");
+    assert!(heights[..peak].windows(2).all(|w| w[0] < w[1]), "ball was not rising before the peak: {heights:?}");
+    assert!(heights[peak..].windows(2).all(|w| w[0] > w[1]), "ball was not falling after the peak: {heights:?}");
+
+    // the prediction must not move the real ball
+    assert_eq!(gym._game_match.get_state().ball.position.z, state.ball.position.z);
+    // the cached prediction is reused for shorter requests on the same tick
+    let cached = gym._game_match.predict_ball(state.tick_num, 10, 0.1);
+    assert_eq!(cached.iter().map(|ball| ball.pos.z).collect::<Vec<_>>(), heights[..10]);
+
+    // after a step the prediction starts from the ball of the returned state, one step ahead is where the next step puts the ball
+    let tick_skip = gym._game_match.game_config.tick_skip;
+    let state = gym.step(vec![vec![0.; 8]]).4;
+    let prediction = gym._game_match.predict_ball(state.tick_num, 1, tick_skip as f32 / 120.);
+    let next_state = gym.step(vec![vec![0.; 8]]).4;
+    let (predicted_z, next_z) = (prediction[0].pos.z, next_state.ball.position.z);
+    assert!((predicted_z - next_z).abs() < 1., "predicted the ball at z {predicted_z} but the next step had it at {next_z}");
+}
+
+struct StageReward {
+    stage: usize,
+}
+
+impl RewardFn for StageReward {
+    fn reset(&mut self, _initial_state: &GameState, reward_stage: Option<usize>) {
+        self.stage = reward_stage.unwrap_or(0);
+    }
+
+    fn get_reward(&mut self, _player: &PlayerData, _state: &GameState) -> f32 {
+        self.stage as f32
+    }
+
+    fn get_final_reward(&mut self, player: &PlayerData, state: &GameState) -> f32 {
+        self.get_reward(player, state)
+    }
+}
+
+#[test]
+fn reward_stage_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            spawn_opponents: false,
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(StageReward { stage: 0 }),
+        obs_builder: vec![Box::new(AdvancedObs::new())],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+
+    for stage in 0..3 {
+        gym.reset_with_stage(stage, None);
+        let (_, rewards, _, _, _) = gym.step(vec![vec![0.; 8]]);
+        assert_eq!(rewards, vec![stage as f32], "stage {stage} did not reach the reward function");
+    }
+    // a plain reset goes back to no stage
+    gym.reset(None, None, None);
+    let (_, rewards, _, _, _) = gym.step(vec![vec![0.; 8]]);
+    assert_eq!(rewards, vec![0.]);
+}
+
+#[test]
+fn asymmetric_teams_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            blue_size: Some(1),
+            orange_size: Some(2),
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(2), Some(true), None, None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+
+    let (obs, _, state) = gym.reset(None, None, None);
+    assert_eq!(obs.len(), 3);
+    assert_eq!(state.players.len(), 3);
+    assert_eq!(state.players.iter().filter(|player| player.team_num == BLUE_TEAM).count(), 1);
+    assert_eq!(state.players.iter().filter(|player| player.team_num == ORANGE_TEAM).count(), 2);
+
+    let (obs, rewards, _, _, state) = gym.step(vec![vec![0.; 8]; 3]);
+    assert_eq!(obs.len(), 3);
+    assert_eq!(rewards.len(), 3);
+    assert_eq!(state.players.len(), 3);
+}
+
+#[test]
+fn per_car_config_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            car_configs: vec![CarConfig::dominus()],
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(AdvancedObs::new())],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    let (_, _, state) = gym.reset(None, None, None);
+
+    let hitbox = |car_config: &CarConfig| [car_config.hitbox_size.x, car_config.hitbox_size.y, car_config.hitbox_size.z];
+    assert_ne!(hitbox(CarConfig::dominus()), hitbox(CarConfig::octane()));
+    // the blue car spawns first so it gets the dominus, the orange car falls back to the octane
+    assert_eq!(state.players[0].team_num, BLUE_TEAM);
+    assert_eq!(state.players[0].hitbox_size, hitbox(CarConfig::dominus()));
+    assert_eq!(state.players[1].hitbox_size, hitbox(CarConfig::octane()));
+}
+
+#[test]
+fn gym_set_state_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            spawn_opponents: false,
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(1), Some(false), Some([1.; 3]), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    let (_, _, state) = gym.reset(None, None, None);
+    gym.step(vec![vec![0.; 8]]);
+
+    let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+    state_wrapper.ball.set_pos(Some(1000.), Some(-2000.), Some(500.));
+    let (obs, new_state) = gym.set_state(state_wrapper);
+
+    // DefaultObs starts with the ball position, unscaled here
+    assert_eq!(obs[0][..3], [1000., -2000., 500.]);
+    assert_eq!(new_state.ball.position.x, 1000.);
+    // the episode keeps going from the new state
+    let (_, _, done, _, state) = gym.step(vec![vec![0.; 8]]);
+    assert!(!done);
+    assert!((state.ball.position.x - 1000.).abs() < 1.);
+}
+
+#[test]
+fn reset_state_only_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            team_size: 2,
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(None, None, None, None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(RandomState::new(None, None, None, None, None)),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+
+    let state = gym.reset_state_only(Some(7));
+    assert_eq!(state.players.len(), 4);
+    assert_eq!(state.players.iter().filter(|p| p.team_num == BLUE_TEAM).count(), 2);
+    assert!(state.players.iter().all(|p| p.car_id > 0));
+    assert_ne!(state.ball.position.into_array(), [0.; 3]);
+    // the same seed gives the same state
+    assert!(gym.reset_state_only(Some(7)).diff(&state, 1e-3).is_empty());
+
+    let (obs, rewards, done, _, new_state) = gym.step(vec![vec![0.; 8]; 4]);
+    assert_eq!(obs.len(), 4);
+    assert_eq!(rewards.len(), 4);
+    assert!(!done);
+    assert_eq!(new_state.players.len(), 4);
+}
+
+#[test]
+fn tick_skip_update_test() {
+    rocketsim_rs::init(None);
+    let make_game_config = |tick_skip| GameConfig {
+        tick_skip,
+        ..GameConfig::default()
+    };
+    let config = make::MakeConfig {
+        game_config: make_game_config(8),
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(None, None, None, None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(config, None, None);
+    gym.reset(None, None, None);
+    let first = gym.step(vec![vec![0.; 8]; 2]).4.tick_num;
+    let second = gym.step(vec![vec![0.; 8]; 2]).4.tick_num;
+    assert_eq!(second - first, 8);
+
+    gym.update_config(make_game_config(4), None);
+    assert_eq!(gym._game_match.get_config().tick_skip, 4);
+    gym.reset(None, None, None);
+    let first = gym.step(vec![vec![0.; 8]; 2]).4.tick_num;
+    let second = gym.step(vec![vec![0.; 8]; 2]).4.tick_num;
+    assert_eq!(second - first, 4);
+}
+
+#[test]
+fn live_gravity_mutation_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            spawn_opponents: false,
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(None, None, None, None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    let (_, _, state) = gym.reset(None, None, None);
+
+    // mid-episode, ball high up in the middle of the field and not moving
+    gym.set_mutators(Some(5.), None);
+    assert_eq!(gym._game_match.get_config().gravity, 5.);
+    let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+    state_wrapper.ball.set_pos(Some(0.), Some(0.), Some(1800.));
+    state_wrapper.ball.set_lin_vel(Some(0.), Some(0.), Some(0.));
+    gym.set_state(state_wrapper);
+
+    let first = gym.step(vec![vec![0.; 8]]).4;
+    let second = gym.step(vec![vec![0.; 8]]).4;
+    let dt = (second.tick_num - first.tick_num) as f32 / 120.;
+    let accel = (second.ball.linear_velocity.z - first.ball.linear_velocity.z) / dt;
+    let expected = GRAVITY_Z * 5.;
+    // ball drag makes this slightly off
+    assert!((accel - expected).abs() < 0.05 * expected.abs(), "ball acceleration was {accel}, expected ~{expected}");
+}
+
+#[test]
+fn last_touch_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(None, None, None, None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    let (_, _, state) = gym.reset(None, None, None);
+    assert_eq!(state.last_touch_car_id, None);
+
+    // the orange car drives straight into the ball, the blue car is out of the way
+    let orange_id = state.players.iter().find(|p| p.team_num == ORANGE_TEAM).unwrap().car_id;
+    let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+    state_wrapper.ball.set_pos(Some(0.), Some(0.), Some(93.));
+    state_wrapper.ball.set_lin_vel(Some(0.), Some(0.), Some(0.));
+    for car in &mut state_wrapper.cars {
+        if car.get_car_id() == orange_id {
+            car.set_pos(Some(0.), Some(600.), Some(17.));
+            car.set_rot(Some(0.), Some(-std::f32::consts::FRAC_PI_2), Some(0.));
+            car.set_lin_vel(Some(0.), Some(-1500.), Some(0.));
+        } else {
+            car.set_pos(Some(3000.), Some(-3000.), Some(17.));
+        }
+    }
+    gym.set_state(state_wrapper);
+
+    let mut touched = false;
+    for _ in 0..30 {
+        let state = gym.step(vec![vec![1., 0., 0., 0., 0., 0., 0., 0.]; 2]).4;
+        if state.players.iter().any(|p| p.ball_touched) {
+            assert!(state.players.iter().find(|p| p.car_id == orange_id).unwrap().ball_touched);
+            assert_eq!(state.last_touch_car_id, Some(orange_id));
+            // the contact point is on the surface of the ball
+            let location = Position { x: state.last_touch_location[0], y: state.last_touch_location[1], z: state.last_touch_location[2] };
+            assert!(location.y > 0. && location.z > 0.);
+            touched = true;
+            break;
+        }
+    }
+    assert!(touched, "the ball was never touched");
+
+    // the touch is carried over to the following steps
+    let state = gym.step(vec![vec![0.; 8]; 2]).4;
+    assert_eq!(state.last_touch_car_id, Some(orange_id));
+}
+
+#[test]
+fn spaces_detected_test() {
+    rocketsim_rs::init(None);
+    let make_game_config = |team_size| GameConfig {
+        team_size,
+        ..GameConfig::default()
+    };
+    let config = make::MakeConfig {
+        game_config: make_game_config(1),
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(AdvancedObs::new_with_coefs(Some(1), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(config, None, None);
+    assert_eq!(gym.observation_space, AdvancedObs::new_with_coefs(Some(1), None, None, None).get_obs_space());
+    assert_eq!(gym.action_space, TestAction::new().get_action_space());
+    assert_eq!(gym._game_match.observation_space, gym.observation_space);
+    assert!(!gym.observation_space.is_empty() && !gym.action_space.is_empty());
+
+    let new_obs: Vec<Box<dyn ObsBuilder>> = vec![Box::new(AdvancedObs::new_with_coefs(Some(2), None, None, None))];
+    gym.update_config(make_game_config(2), Some(new_obs));
+    assert_eq!(gym.observation_space, AdvancedObs::new_with_coefs(Some(2), None, None, None).get_obs_space());
+    assert_eq!(gym._game_match.observation_space, gym.observation_space);
+}
+
+#[test]
+fn build_observations_into_test() {
+    rocketsim_rs::init(None);
+    let config = make::MakeConfig {
+        game_config: GameConfig {
+            team_size: 3,
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(1000)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(3), None, None, None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(RandomState::new(None, None, None, None, Some(0))),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(config, None, None);
+    gym.reset(None, None, None);
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let actions = (0..6).map(|_| (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect()).collect();
+        let (_, _, _, _, state) = gym.step(actions);
+        let obs = gym._game_match.build_observations(&state);
+        let obs_into = gym._game_match.build_observations_into(&state);
+        assert_eq!(obs.len(), obs_into.len());
+        for (a, b) in obs.iter().zip(obs_into) {
+            assert_eq!(a.iter().map(|x| x.to_bits()).collect::<Vec<_>>(), b.iter().map(|x| x.to_bits()).collect::<Vec<_>>());
+        }
+    }
+}
+
+#[test]
+fn demo_event_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(DemoReward::new(None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(None, None, None, None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    let (_, _, state) = gym.reset(None, None, None);
+
+    // blue car drives into the stationary orange car at supersonic speed
+    let blue_id = state.players.iter().find(|p| p.team_num == BLUE_TEAM).unwrap().car_id;
+    let orange_id = state.players.iter().find(|p| p.team_num == ORANGE_TEAM).unwrap().car_id;
+    let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+    state_wrapper.ball.set_pos(Some(3000.), Some(3000.), Some(93.));
+    for car in &mut state_wrapper.cars {
+        if car.get_car_id() == blue_id {
+            car.set_pos(Some(0.), Some(-1000.), Some(17.));
+            car.set_rot(Some(0.), Some(std::f32::consts::FRAC_PI_2), Some(0.));
+            car.set_lin_vel(Some(0.), Some(2295.), Some(0.));
+            car.boost = 1.;
+        } else {
+            car.set_pos(Some(0.), Some(0.), Some(17.));
+            car.set_rot(Some(0.), Some(0.), Some(0.));
+        }
+    }
+    gym.set_state(state_wrapper);
+
+    let mut demos = Vec::new();
+    let mut positive_rewards = 0;
+    for _ in 0..15 {
+        let (_, rewards, _, _, state) = gym.step(vec![vec![1., 0., 0., 0., 0., 0., 1., 0.], vec![0.; 8]]);
+        demos.extend(state.demo_events.iter().copied());
+        for (player, reward) in state.players.iter().zip(rewards) {
+            if reward > 0. {
+                assert_eq!(player.car_id, blue_id);
+                positive_rewards += 1;
+            }
+        }
+    }
+    assert_eq!(demos, vec![(blue_id, orange_id)]);
+    assert_eq!(positive_rewards, 1);
+}
+
+#[test]
+fn step_info_rewards_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(1), Some(false), Some([1.; 3]), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    gym.reset(None, None, None);
+    let (_, rewards, done, info, _) = gym.step(vec![vec![1., 0., 0., 0., 0., 0., 1., 0.]; 2]);
+
+    assert_eq!(rewards.len(), 2);
+    for (i, reward) in rewards.iter().enumerate() {
+        assert_eq!(info[&format!("reward_{i}")], *reward);
+    }
+    assert!(!info.contains_key("reward_2"));
+    assert_eq!(info["done"], done as u8 as f32);
+    assert!(info.contains_key("result"));
+}
+
+#[test]
+fn try_step_mismatch_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(1), Some(false), Some([1.; 3]), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    gym.reset(None, None, None);
+
+    let err = gym.try_step(vec![vec![0.; 8]]).unwrap_err();
+    assert_eq!(err, GymError::ActionCountMismatch { actions: 1, players: 2 });
+    let err = gym.try_step(vec![vec![0.; 8], vec![0.; 3]]).unwrap_err();
+    assert_eq!(err, GymError::ActionLengthMismatch { index: 1, len: 3, expected: 8 });
+
+    let (_, rewards, ..) = gym.try_step(vec![vec![0.; 8]; 2]).unwrap();
+    assert_eq!(rewards.len(), 2);
+}
+
+#[test]
+fn tick_observer_test() {
+    use std::{cell::RefCell, rc::Rc};
+
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(1), Some(false), Some([1.; 3]), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    gym.reset(None, None, None);
+
+    let ticks = Rc::new(RefCell::new(Vec::new()));
+    let observed = ticks.clone();
+    gym.set_tick_observer(Some(Box::new(move |tick_state: &rocketsim_rs::GameState| observed.borrow_mut().push(tick_state.tick_count))));
+
+    gym.step(vec![vec![0.; 8]; 2]);
+    assert_eq!(ticks.borrow().len(), 8);
+    gym.step(vec![vec![0.; 8]; 2]);
+    assert_eq!(ticks.borrow().len(), 16);
+    // one call per consecutive physics tick
+    assert!(ticks.borrow().windows(2).all(|pair| pair[1] == pair[0] + 1));
+
+    gym.set_tick_observer(None);
+    gym.step(vec![vec![0.; 8]; 2]);
+    assert_eq!(ticks.borrow().len(), 16);
+}
+
+#[test]
+fn last_state_test() {
+    rocketsim_rs::init(None);
+    let game_config = make::MakeConfig {
+        game_config: GameConfig {
+            spawn_opponents: false,
+            ..GameConfig::default()
+        },
+        terminal_condition: Box::new(TimeoutCondition::new(100)),
+        reward_fn: Box::new(EventReward::new(None, None, None, None, None, None, None, None)),
+        obs_builder: vec![Box::new(DefaultObs::new(Some(1), Some(false), Some([1.; 3]), None, None, None))],
+        use_single_obs: true,
+        action_parser: Box::new(TestAction::new()),
+        state_setter: Box::new(DefaultStateTester::new()),
+        state_modifiers: vec![],
+    };
+    let mut gym = make::make(game_config, None, None);
+    let (_, _, state) = gym.reset(None, None, None);
+    let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+    state_wrapper.ball.set_pos(None, None, Some(1000.));
+    gym.set_state(state_wrapper);
+
+    let (_, _, _, _, state) = gym.step(vec![vec![0.; 8]]);
+    let last_state = gym.last_state();
+    assert_eq!(last_state.tick_num, state.tick_num);
+    // the ball is falling from where it was set
+    assert!(last_state.ball.position.z < 1000.);
+    assert_eq!(last_state.ball.position.z, state.ball.position.z);
+}
+
+#[test]
+fn match_builder_test() {
+    rocketsim_rs::init(None);
+    let mut gym = rlgym_sim_rs::MatchBuilder::new().build().unwrap();
+    let (obs, _, state) = gym.reset(None, None, None);
+    assert_eq!(obs.len(), 2);
+    assert_eq!(state.players.len(), 2);
+
+    // DiscreteAction, 3 bins for the analog controls
+    let (obs, rewards, done, _, _) = gym.step(vec![vec![2., 1., 1., 1., 1., 0., 1., 0.]; 2]);
+    assert_eq!(obs.len(), 2);
+    assert_eq!(rewards, vec![0.; 2]);
+    assert!(!done);
+}
+
+#[test]
+fn reset_buffer_reuse_test() {
+    rocketsim_rs::init(None);
+    let mut gym = rlgym_sim_rs::MatchBuilder::new().build().unwrap();
+    gym.reset(None, None, None);
+    gym.step(vec![vec![1.; 8]; 2]);
+
+    // only the buffers the gym keeps (previous state and actions) are reused,
+    // every reset and step still decodes the state it returns into new allocations
+    let players_ptr = gym._prev_state.players.as_ptr();
+    let actions_ptrs: Vec<*const f32> = gym._game_match._prev_actions.iter().map(|act| act.as_ptr()).collect();
+    for i in 0..200 {
+        gym.reset(None, Some(i), None);
+        gym.step(vec![vec![1.; 8]; 2]);
+        assert_eq!(gym._prev_state.players.as_ptr(), players_ptr, "previous state players were reallocated");
+        assert_eq!(gym._game_match._prev_actions.capacity(), 2);
+        let ptrs: Vec<*const f32> = gym._game_match._prev_actions.iter().map(|act| act.as_ptr()).collect();
+        assert_eq!(ptrs, actions_ptrs, "previous actions were reallocated");
+    }
+}
+
+#[test]
+fn heatseeker_goal_test() {
+    rocketsim_rs::init(None);
+    let game_config = GameConfig {
+        game_mode: GameMode::Heatseeker,
+        ..GameConfig::default()
+    };
+    let mut gym = rlgym_sim_rs::MatchBuilder::new()
+        .game_config(game_config)
+        .terminal_condition(Box::new(GoalScoredCondition::new()))
+        .action_parser(Box::new(TestAction::new()))
+        .build()
+        .unwrap();
+    let (_, _, state) = gym.reset(None, None, None);
+    assert_eq!(state.game_type, GameMode::Heatseeker as u8 as i32);
+
+    // ball about to go into the orange net
+    let mut state_wrapper = StateWrapper::new(None, None, Some(&state));
+    state_wrapper.ball.set_pos(Some(0.), Some(4900.), Some(300.));
+    state_wrapper.ball.set_lin_vel(Some(0.), Some(3000.), Some(0.));
+    gym.set_state(state_wrapper);
+
+    let mut done = false;
+    let mut info = std::collections::HashMap::new();
+    for _ in 0..10 {
+        let step = gym.step(vec![vec![0.; 8]; 2]);
+        done = step.2;
+        info = step.3;
+        if done {
+            break;
+        }
+    }
+    assert!(done, "heatseeker goal did not end the episode");
+    assert_eq!(gym.last_state().blue_score - state.blue_score, 1);
+    assert_eq!(gym.last_state().orange_score, state.orange_score);
+    assert_eq!(info["result"], 1.);
+}
+
+#[test]
+fn shuffle_cars_test() {
+    rocketsim_rs::init(None);
+    let game_config = GameConfig {
+        team_size: 2,
+        shuffle_cars: true,
+        ..GameConfig::default()
+    };
+    let make_gym = || {
+        rlgym_sim_rs::MatchBuilder::new()
+            .game_config(game_config.clone())
+            .action_parser(Box::new(TestAction::new()))
+            .build()
+            .unwrap()
+    };
+    let mut gym_1 = make_gym();
+    let mut gym_2 = make_gym();
+
+    let mut shuffled = false;
+    for seed in 0..20 {
+        gym_1.reset(None, Some(seed), None);
+        gym_2.reset(None, Some(seed), None);
+        let order = gym_1._game_match.sim_wrapper.agent_order().to_vec();
+        assert_eq!(order, gym_2._game_match.sim_wrapper.agent_order(), "same seed gave different car orders");
+        shuffled |= order.iter().enumerate().any(|(agent, car_index)| agent != *car_index);
+
+        // the player of each agent must be the car it was assigned
+        let car_ids: Vec<i32> = gym_1.last_state().players.iter().map(|player| player.car_id).collect();
+        let expected: Vec<i32> = order.iter().map(|car_index| *car_index as i32 + 1).collect();
+        assert_eq!(car_ids, expected);
+
+        // only agent 0 drives, so only its car should move
+        let start = gym_1.last_state().players[0].car_data.position;
+        for _ in 0..15 {
+            let mut actions = vec![vec![0.; 8]; 4];
+            actions[0][0] = 1.;
+            gym_1.step(actions);
+        }
+        let state = gym_1.last_state();
+        let moved = state.players[0].car_data.position;
+        assert!((moved.x - start.x).abs() + (moved.y - start.y).abs() > 50., "agent 0's car did not move");
+        assert_eq!(state.players[0].car_id, order[0] as i32 + 1);
+    }
+    assert!(shuffled, "cars were never shuffled");
+}
+
+#[test]
+fn episode_steps_info_test() {
+    rocketsim_rs::init(None);
+    let n_steps = 37;
+    let mut gym = rlgym_sim_rs::MatchBuilder::new()
+        .terminal_condition(Box::new(TimeoutCondition::new(n_steps)))
+        .action_parser(Box::new(TestAction::new()))
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        let (_, info, _) = gym.reset(Some(true), None, None);
+        assert_eq!(info["episode_steps"], 0.);
+        let mut steps = 0;
+        loop {
+            let (_, _, done, info, _) = gym.step(vec![vec![0.; 8]; 2]);
+            steps += 1;
+            assert_eq!(info["episode_steps"], steps as f32);
+            if done {
+                assert_eq!(info["episode_length"], n_steps as f32);
+                break;
+            }
+            assert!(!info.contains_key("episode_length"));
+        }
+        assert_eq!(steps, n_steps);
+        assert_eq!(gym.episode_steps(), n_steps as u64);
+    }
+}
+
+#[test]
+fn f64_step_matches_f32_test() {
+    rocketsim_rs::init(None);
+    let build_gym = || rlgym_sim_rs::MatchBuilder::new()
+        .action_parser(Box::new(TestAction::new()))
+        .build()
+        .unwrap();
+    let mut gym = build_gym();
+    let mut gym_f64 = build_gym();
+    let (obs, _, _) = gym.reset(None, Some(7), None);
+    let (obs_f64, _, _) = gym_f64.reset_f64(None, Some(7), None);
+    assert_eq!(rlgym_sim_rs::obs_to_f64(&obs), obs_f64);
+    for _ in 0..20 {
+        let actions = vec![vec![1., 0.5, 0., 0., 0., 0., 1., 0.]; 2];
+        let (obs, reward, done, _, _) = gym.step(actions.clone());
+        let (obs_f64, reward_f64, done_f64, _, _) = gym_f64.step_f64(actions);
+        assert_eq!(done, done_f64);
+        for (vals, vals_f64) in obs.iter().zip(&obs_f64) {
+            assert!(vals.iter().zip(vals_f64).all(|(val, val_f64)| *val_f64 as f32 == *val));
+        }
+        assert!(reward.iter().zip(&reward_f64).all(|(val, val_f64)| *val_f64 as f32 == *val));
+    }
+}
+
+#[test]
+fn interpolated_action_tick_schedule_test() {
+    use std::{cell::RefCell, rc::Rc};
+
+    rocketsim_rs::init(None);
+    let mut gym = rlgym_sim_rs::MatchBuilder::new()
+        .action_parser(Box::new(rlgym_sim_rs::InterpolatedContinuousAction::new(None)))
+        .build()
+        .unwrap();
+    gym.reset(None, None, None);
+
+    let throttles = Rc::new(RefCell::new(Vec::new()));
+    let observed = throttles.clone();
+    gym.set_tick_observer(Some(Box::new(move |tick_state: &rocketsim_rs::GameState| {
+        observed.borrow_mut().push(tick_state.cars[0].state.last_controls.throttle)
+    })));
+
+    gym.step(vec![vec![1., 0., 0., 0., 0., 0., 0., 0.]; 2]);
+    gym.step(vec![vec![-1., 0., 0., 0., 0., 0., 0., 0.]; 2]);
+    let throttles = throttles.borrow();
+    assert_eq!(throttles.len(), 16);
+    // ramps from 0 to 1 and then from 1 to -1, the midpoint of the second step is the average
+    assert_eq!(throttles[3], 0.5);
+    assert_eq!(throttles[7], 1.);
+    assert_eq!(throttles[11], 0.);
+    assert_eq!(throttles[15], -1.);
+}
+
+#[test]
+fn tick_count_test() {
+    rocketsim_rs::init(None);
+    let mut gym = rlgym_sim_rs::MatchBuilder::new()
+        .action_parser(Box::new(TestAction::new()))
+        .build()
+        .unwrap();
+    let tick_skip = gym._game_match.game_config.tick_skip as u64;
+    for _ in 0..2 {
+        let (_, _, state) = gym.reset(None, None, None);
+        assert_eq!(state.tick_count, 0);
+        assert_eq!(state.seconds_elapsed, 0.);
+        let mut prev_tick_count = state.tick_count;
+        for _ in 0..5 {
+            let state = gym.step(vec![vec![0.; 8]; 2]).4;
+            assert_eq!(state.tick_count, prev_tick_count + tick_skip);
+            assert_eq!(state.seconds_elapsed, state.tick_count as f32 / 120.);
+            // the state is taken after the last tick of the step so it matches the arena
+            assert_eq!(state.tick_num, gym._game_match.sim_wrapper.get_rlgym_gamestate(false).0.tick_num);
+            prev_tick_count = state.tick_count;
+        }
+    }
+}
+
+#[test]
+fn trace_replay_self_consistency_test() {
+    // only checks that saving and replaying a trace round trips, the trace comes from this crate's sim
+    // so it can't catch parity drift with Python rlgym-sim (that is what golden_trace_test is for)
+    use rlgym_sim_rs::sim_wrapper::wrapper::RocketsimWrapper;
+    use rlgym_sim_rs::testing::{replay_and_compare, save_trace, TraceStep};
+
+    rocketsim_rs::init(None);
+    let config = GameConfig::default();
+    let mut sim_wrapper = RocketsimWrapper::new(config.clone());
+    let mut state_wrapper = StateWrapper::new(Some(config.blue_count()), Some(config.orange_count()), None);
+    DefaultStateTester::new().reset(&mut state_wrapper);
+    let (mut state, _) = sim_wrapper.set_state(state_wrapper, false);
+    let mut steps = Vec::new();
+    for i in 0..20 {
+        // setting the state only restores the physics and boost of the cars (not eg. jump or boost timers) so the cars only drive and steer
+        let actions = vec![vec![1., (i % 3) as f32 - 1., 0., 0., 0., 0., 0., 0.]; 2];
+        let (next_state, _) = sim_wrapper.step(actions.clone(), false);
+        assert_eq!(next_state.tick_num - state.tick_num, config.tick_skip as u64);
+        steps.push(TraceStep { state, actions, next_state: next_state.clone() });
+        state = next_state;
+    }
+    let path = "./tests/test_files_npy/self_trace.npy";
+    save_trace(path, &steps);
+    assert_eq!(replay_and_compare(path, None), steps.len());
+}
+
+#[test]
+#[ignore = "no golden trace is committed yet, record one with tests/golden_traces/record_trace.py and run with --ignored"]
+fn golden_trace_test() {
+    rocketsim_rs::init(None);
+    let dir = "./tests/golden_traces";
+    let mut traces: Vec<_> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.extension().is_some_and(|ext| ext == "npy")).collect())
+        .unwrap_or_default();
+    traces.sort();
+    assert!(!traces.is_empty(), "no golden traces in {dir}, record one with record_trace.py (see its README)");
+    for trace in traces {
+        assert!(rlgym_sim_rs::testing::replay_and_compare(&trace, None) > 0, "{} is empty", trace.display());
+    }
+}
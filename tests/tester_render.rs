@@ -29,7 +29,7 @@ use rlgym_sim_rs::state_setters::default_state::{
 };
 
 use rlgym_sim_rs::obs_builders::obs_builder::ObsBuilder;
-use rocketsim_rs::sim::CarConfig;
+use rocketsim_rs::sim::{CarConfig, GameMode};
 
 pub struct CombinedTerminalConditions {
     timeout_condition: TimeoutCondition,
@@ -96,6 +96,12 @@ fn render_test() {
         gravity: 1.,
         boost_consumption: 1.,
         car_config: CarConfig::octane(),
+        game_mode: GameMode::Soccar,
+        blue_size: None,
+        orange_size: None,
+        car_configs: vec![],
+        shuffle_cars: false,
+        pad_opponent_obs: false,
     };
     let game_config = make::MakeConfig {
         game_config: config,
@@ -105,6 +111,7 @@ fn render_test() {
         use_single_obs: true,
         action_parser: act_parse,
         state_setter: state_set, 
+        state_modifiers: vec![],
     };
     let render_config = make::RenderConfig {
         render: true,
@@ -262,6 +269,12 @@ fn render_test() {
         gravity: 1.,
         boost_consumption: 1.,
         car_config: CarConfig::octane(),
+        game_mode: GameMode::Soccar,
+        blue_size: None,
+        orange_size: None,
+        car_configs: vec![],
+        shuffle_cars: false,
+        pad_opponent_obs: false,
     };
     let game_config = make::MakeConfig {
         game_config: config,
@@ -271,6 +284,7 @@ fn render_test() {
         use_single_obs: true,
         action_parser: act_parse,
         state_setter: state_set, 
+        state_modifiers: vec![],
     };
     let mut gym = make::make(game_config, Some(render_config), None);
 